@@ -0,0 +1,283 @@
+use sea_orm::DeriveValueType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An exact amount of continuing-education credit, stored as whole hundredths.
+///
+/// SQLite has no exact decimal type (SeaORM reads `DECIMAL` back through `f64`),
+/// so credit columns hold integers and this type does the fixed-point
+/// conversion. In JSON it appears as an ordinary number such as `1.5`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, DeriveValueType)]
+pub struct Credits(i64);
+
+impl Credits {
+    pub const ZERO: Credits = Credits(0);
+    /// The most credit, either way, that parsing accepts. Far above any real
+    /// requirement, and low enough that summing amounts can't overflow.
+    pub const MAX: Credits = Credits::from_whole(100_000);
+
+    pub const fn from_hundredths(hundredths: i64) -> Self {
+        Credits(hundredths)
+    }
+
+    pub const fn from_whole(credits: i64) -> Self {
+        Credits(credits * 100)
+    }
+
+    pub const fn hundredths(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Rounds towards zero to a multiple of `increment`, e.g. quarter credits
+    pub fn round_down_to(self, increment: Credits) -> Credits {
+        if increment.0 <= 0 {
+            return self;
+        }
+        Credits(self.0 - self.0 % increment.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseCreditsError;
+
+impl fmt::Display for ParseCreditsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid credit amount, expected at most two decimal places and no more than 100000")
+    }
+}
+
+impl std::error::Error for ParseCreditsError {}
+
+impl FromStr for Credits {
+    type Err = ParseCreditsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        if whole.is_empty() && fraction.is_empty()
+            || fraction.len() > 2
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(ParseCreditsError);
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| ParseCreditsError)?
+        };
+        let fraction: i64 = format!("{:0<2}", fraction)
+            .parse()
+            .map_err(|_| ParseCreditsError)?;
+
+        let hundredths = whole
+            .checked_mul(100)
+            .and_then(|w| w.checked_add(fraction))
+            .filter(|h| *h <= Credits::MAX.0)
+            .ok_or(ParseCreditsError)?;
+
+        Ok(Credits(if negative { -hundredths } else { hundredths }))
+    }
+}
+
+impl fmt::Display for Credits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        match abs % 100 {
+            0 => write!(f, "{}{}", sign, abs / 100),
+            n if n % 10 == 0 => write!(f, "{}{}.{}", sign, abs / 100, n / 10),
+            n => write!(f, "{}{}.{:02}", sign, abs / 100, n),
+        }
+    }
+}
+
+impl Serialize for Credits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Credits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Integer(i64),
+            Float(f64),
+            Text(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Integer(n) => n
+                .checked_mul(100)
+                .map(Credits)
+                .filter(|c| c.0.abs() <= Credits::MAX.0)
+                .ok_or_else(|| serde::de::Error::custom(ParseCreditsError)),
+            // Going through the shortest decimal representation keeps 1.15 as 1.15
+            // rather than 1.1499999...
+            Repr::Float(n) => n.to_string().parse().map_err(serde::de::Error::custom),
+            Repr::Text(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Add for Credits {
+    type Output = Credits;
+
+    fn add(self, rhs: Credits) -> Credits {
+        Credits(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Credits {
+    fn add_assign(&mut self, rhs: Credits) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Credits {
+    type Output = Credits;
+
+    fn sub(self, rhs: Credits) -> Credits {
+        Credits(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Credits {
+    fn sub_assign(&mut self, rhs: Credits) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Credits {
+    type Output = Credits;
+
+    fn neg(self) -> Credits {
+        Credits(-self.0)
+    }
+}
+
+impl Sum for Credits {
+    fn sum<I: Iterator<Item = Credits>>(iter: I) -> Credits {
+        Credits(iter.map(|c| c.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a Credits> for Credits {
+    fn sum<I: Iterator<Item = &'a Credits>>(iter: I) -> Credits {
+        Credits(iter.map(|c| c.0).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Option<i64> {
+        s.parse::<Credits>().ok().map(Credits::hundredths)
+    }
+
+    fn from_json(json: &str) -> Option<i64> {
+        serde_json::from_str::<Credits>(json).ok().map(Credits::hundredths)
+    }
+
+    #[test]
+    fn parses_up_to_two_decimal_places() {
+        assert_eq!(parse("1"), Some(100));
+        assert_eq!(parse("1.5"), Some(150));
+        assert_eq!(parse("1.25"), Some(125));
+        assert_eq!(parse("0.05"), Some(5));
+        assert_eq!(parse(" 2.5 "), Some(250));
+    }
+
+    #[test]
+    fn parses_a_bare_point_on_either_side() {
+        assert_eq!(parse("1."), Some(100));
+        assert_eq!(parse(".5"), Some(50));
+        assert_eq!(parse("-.25"), Some(-25));
+        assert_eq!(parse("."), None);
+    }
+
+    #[test]
+    fn parses_negatives() {
+        assert_eq!(parse("-1.5"), Some(-150));
+        assert_eq!(parse("-0.01"), Some(-1));
+        assert_eq!(parse("-"), None);
+        assert_eq!(parse("--1"), None);
+    }
+
+    #[test]
+    fn rejects_more_than_two_decimal_places() {
+        assert_eq!(parse("1.234"), None);
+        assert_eq!(parse("0.001"), None);
+    }
+
+    #[test]
+    fn rejects_anything_but_digits() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("1,5"), None);
+        assert_eq!(parse("+1"), None);
+        assert_eq!(parse("1e2"), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(parse("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn rejects_more_than_the_maximum() {
+        assert_eq!(parse("100000"), Some(10_000_000));
+        assert_eq!(parse("-100000"), Some(-10_000_000));
+        assert_eq!(parse("100000.01"), None);
+        assert_eq!(parse("-100001"), None);
+        assert_eq!(from_json("100000"), Some(10_000_000));
+        assert_eq!(from_json("100001"), None);
+        assert_eq!(from_json("-100001"), None);
+        assert_eq!(from_json("100000.5"), None);
+        assert_eq!(from_json("1e300"), None);
+    }
+
+    #[test]
+    fn deserializes_numbers_and_strings() {
+        assert_eq!(from_json("2"), Some(200));
+        assert_eq!(from_json("-3"), Some(-300));
+        assert_eq!(from_json("1.15"), Some(115));
+        assert_eq!(from_json("0.1"), Some(10));
+        assert_eq!(from_json("\"1.5\""), Some(150));
+        assert_eq!(from_json("1.234"), None);
+        assert_eq!(from_json("\"1.234\""), None);
+        assert_eq!(from_json("true"), None);
+    }
+
+    #[test]
+    fn displays_without_trailing_zeros() {
+        assert_eq!(Credits::from_hundredths(100).to_string(), "1");
+        assert_eq!(Credits::from_hundredths(150).to_string(), "1.5");
+        assert_eq!(Credits::from_hundredths(125).to_string(), "1.25");
+        assert_eq!(Credits::from_hundredths(-5).to_string(), "-0.05");
+    }
+
+    #[test]
+    fn rounds_down_to_an_increment() {
+        let quarter = Credits::from_hundredths(25);
+        assert_eq!(Credits::from_hundredths(149).round_down_to(quarter), Credits::from_hundredths(125));
+        assert_eq!(Credits::from_hundredths(150).round_down_to(quarter), Credits::from_hundredths(150));
+        assert_eq!(Credits::from_hundredths(-149).round_down_to(quarter), Credits::from_hundredths(-125));
+        assert_eq!(Credits::from_hundredths(149).round_down_to(Credits::ZERO), Credits::from_hundredths(149));
+    }
+}
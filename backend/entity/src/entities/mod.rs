@@ -2,6 +2,26 @@
 
 pub mod prelude;
 
+pub mod budget;
+pub mod catalog_audit;
+pub mod certificate;
+pub mod compliance_period;
+pub mod course;
+pub mod course_approval;
+pub mod course_approval_credit;
+pub mod course_bookmark;
+pub mod course_format;
+pub mod course_registration;
+pub mod course_review;
+pub mod course_session;
+pub mod course_topic;
+pub mod credit_entry;
+pub mod credit_entry_category;
+pub mod hours_change;
+pub mod provider;
+pub mod provider_membership;
+pub mod requirement;
+pub mod requirement_category;
 pub mod session;
 pub mod state;
 pub mod user;
@@ -1,5 +1,25 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::budget::Entity as Budget;
+pub use super::catalog_audit::Entity as CatalogAudit;
+pub use super::certificate::Entity as Certificate;
+pub use super::compliance_period::Entity as CompliancePeriod;
+pub use super::course::Entity as Course;
+pub use super::course_approval::Entity as CourseApproval;
+pub use super::course_approval_credit::Entity as CourseApprovalCredit;
+pub use super::course_bookmark::Entity as CourseBookmark;
+pub use super::course_format::Entity as CourseFormat;
+pub use super::course_registration::Entity as CourseRegistration;
+pub use super::course_review::Entity as CourseReview;
+pub use super::course_session::Entity as CourseSession;
+pub use super::course_topic::Entity as CourseTopic;
+pub use super::credit_entry::Entity as CreditEntry;
+pub use super::credit_entry_category::Entity as CreditEntryCategory;
+pub use super::hours_change::Entity as HoursChange;
+pub use super::provider::Entity as Provider;
+pub use super::provider_membership::Entity as ProviderMembership;
+pub use super::requirement::Entity as Requirement;
+pub use super::requirement_category::Entity as RequirementCategory;
 pub use super::session::Entity as Session;
 pub use super::state::Entity as State;
 pub use super::user::Entity as User;
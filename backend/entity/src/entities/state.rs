@@ -13,10 +13,26 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::course_approval::Entity")]
+    CourseApproval,
+    #[sea_orm(has_many = "super::requirement::Entity")]
+    Requirement,
     #[sea_orm(has_many = "super::user_state::Entity")]
     UserState,
 }
 
+impl Related<super::course_approval::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseApproval.def()
+    }
+}
+
+impl Related<super::requirement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Requirement.def()
+    }
+}
+
 impl Related<super::user_state::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserState.def()
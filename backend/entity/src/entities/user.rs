@@ -10,16 +10,92 @@ pub struct Model {
     pub username: String,
     pub password: String,
     pub fullname: String,
+    pub profession: String,
+    pub version: i32,
+    pub is_admin: bool,
+    pub calendar_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::budget::Entity")]
+    Budget,
+    #[sea_orm(has_many = "super::catalog_audit::Entity")]
+    CatalogAudit,
+    #[sea_orm(has_many = "super::certificate::Entity")]
+    Certificate,
+    #[sea_orm(has_many = "super::course_bookmark::Entity")]
+    CourseBookmark,
+    #[sea_orm(has_many = "super::course_registration::Entity")]
+    CourseRegistration,
+    #[sea_orm(has_many = "super::course_review::Entity")]
+    CourseReview,
+    #[sea_orm(has_many = "super::credit_entry::Entity")]
+    CreditEntry,
+    #[sea_orm(has_many = "super::hours_change::Entity")]
+    HoursChange,
+    #[sea_orm(has_many = "super::provider_membership::Entity")]
+    ProviderMembership,
     #[sea_orm(has_many = "super::session::Entity")]
     Session,
     #[sea_orm(has_many = "super::user_state::Entity")]
     UserState,
 }
 
+impl Related<super::budget::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budget.def()
+    }
+}
+
+impl Related<super::catalog_audit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CatalogAudit.def()
+    }
+}
+
+impl Related<super::certificate::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Certificate.def()
+    }
+}
+
+impl Related<super::course_bookmark::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseBookmark.def()
+    }
+}
+
+impl Related<super::course_registration::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseRegistration.def()
+    }
+}
+
+impl Related<super::course_review::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseReview.def()
+    }
+}
+
+impl Related<super::credit_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CreditEntry.def()
+    }
+}
+
+impl Related<super::hours_change::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HoursChange.def()
+    }
+}
+
+impl Related<super::provider_membership::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProviderMembership.def()
+    }
+}
+
 impl Related<super::session::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Session.def()
@@ -9,12 +9,27 @@ pub struct Model {
     pub id: i32,
     pub user_id: i32,
     pub state_id: i32,
-    pub hours_complete: i32,
     pub renewal_date: Option<String>,
+    pub profession: String,
+    pub period_start: Option<Date>,
+    pub admission_date: Option<Date>,
+    pub bar_number: Option<String>,
+    pub status: String,
+    pub deadline_override: Option<Date>,
+    pub version: i32,
+    pub removed_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::compliance_period::Entity")]
+    CompliancePeriod,
+    #[sea_orm(has_many = "super::course_bookmark::Entity")]
+    CourseBookmark,
+    #[sea_orm(has_many = "super::credit_entry::Entity")]
+    CreditEntry,
+    #[sea_orm(has_many = "super::hours_change::Entity")]
+    HoursChange,
     #[sea_orm(
         belongs_to = "super::state::Entity",
         from = "Column::StateId",
@@ -33,6 +48,30 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::compliance_period::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CompliancePeriod.def()
+    }
+}
+
+impl Related<super::course_bookmark::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseBookmark.def()
+    }
+}
+
+impl Related<super::credit_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CreditEntry.def()
+    }
+}
+
+impl Related<super::hours_change::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HoursChange.def()
+    }
+}
+
 impl Related<super::state::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::State.def()
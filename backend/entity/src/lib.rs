@@ -1,2 +1,4 @@
+mod credits;
 mod entities;
+pub use credits::{Credits, ParseCreditsError};
 pub use entities::*;
@@ -4,6 +4,26 @@ mod m20251028_205246_add_users_table;
 mod m20251029_160900_state_table;
 mod m20251029_170000_add_session_table;
 mod m20251029_180000_add_renewal_date_to_user_state;
+mod m20251103_120000_add_profession;
+mod m20251104_120000_add_credit_units;
+mod m20251105_120000_add_credit_ledger;
+mod m20251106_120000_add_certificate_table;
+mod m20251108_120000_add_requirement_rules;
+mod m20251109_120000_add_compliance_periods;
+mod m20251110_120000_add_jurisdiction_details;
+mod m20251111_120000_add_hours_history;
+mod m20251112_120000_add_record_versions;
+mod m20251113_120000_add_course_catalog;
+mod m20251114_120000_add_catalog_admin;
+mod m20251115_120000_add_course_feeds;
+mod m20251116_120000_add_course_search;
+mod m20251117_120000_add_course_sessions;
+mod m20251118_120000_add_course_reviews;
+mod m20251119_120000_add_course_bookmarks;
+mod m20251120_120000_add_course_pricing;
+mod m20251121_120000_add_approval_details;
+mod m20251122_120000_add_new_admittee_rules;
+mod m20251123_120000_add_license_removal;
 
 pub struct Migrator;
 
@@ -15,6 +35,26 @@ impl MigratorTrait for Migrator {
             Box::new(m20251029_160900_state_table::Migration),
             Box::new(m20251029_170000_add_session_table::Migration),
             Box::new(m20251029_180000_add_renewal_date_to_user_state::Migration),
+            Box::new(m20251103_120000_add_profession::Migration),
+            Box::new(m20251104_120000_add_credit_units::Migration),
+            Box::new(m20251105_120000_add_credit_ledger::Migration),
+            Box::new(m20251106_120000_add_certificate_table::Migration),
+            Box::new(m20251108_120000_add_requirement_rules::Migration),
+            Box::new(m20251109_120000_add_compliance_periods::Migration),
+            Box::new(m20251110_120000_add_jurisdiction_details::Migration),
+            Box::new(m20251111_120000_add_hours_history::Migration),
+            Box::new(m20251112_120000_add_record_versions::Migration),
+            Box::new(m20251113_120000_add_course_catalog::Migration),
+            Box::new(m20251114_120000_add_catalog_admin::Migration),
+            Box::new(m20251115_120000_add_course_feeds::Migration),
+            Box::new(m20251116_120000_add_course_search::Migration),
+            Box::new(m20251117_120000_add_course_sessions::Migration),
+            Box::new(m20251118_120000_add_course_reviews::Migration),
+            Box::new(m20251119_120000_add_course_bookmarks::Migration),
+            Box::new(m20251120_120000_add_course_pricing::Migration),
+            Box::new(m20251121_120000_add_approval_details::Migration),
+            Box::new(m20251122_120000_add_new_admittee_rules::Migration),
+            Box::new(m20251123_120000_add_license_removal::Migration),
         ]
     }
 }
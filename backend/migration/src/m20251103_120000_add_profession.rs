@@ -0,0 +1,144 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing users and licenses were all registered as attorneys
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(string(User::Profession).default("Attorney"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(string(UserState::Profession).default("Attorney"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Requirement::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Requirement::Id))
+                    .col(integer(Requirement::StateId))
+                    .col(string(Requirement::Profession))
+                    .col(integer(Requirement::Hours))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(Requirement::Table)
+                            .from_col(Requirement::StateId)
+                            .to_tbl(State::Table)
+                            .to_col(State::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_requirement_state_profession")
+                    .table(Requirement::Table)
+                    .col(Requirement::StateId)
+                    .col(Requirement::Profession)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attorney requirements come from the existing per-state legal hours; the
+        // other professions use the same figures as the frontend's CE_REQUIREMENTS,
+        // falling back to its default for states it doesn't list.
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO requirement (state_id, profession, hours)
+             SELECT id, 'Attorney', legal_hours FROM state",
+        )
+        .await?;
+
+        let other_professions = [
+            ("Engineer", [("CA", 30), ("TX", 15), ("NY", 36), ("FL", 18), ("IL", 30)], 24),
+            ("CPA", [("CA", 80), ("TX", 120), ("NY", 40), ("FL", 80), ("IL", 120)], 40),
+            ("Architect", [("CA", 30), ("TX", 12), ("NY", 36), ("FL", 20), ("IL", 24)], 24),
+        ];
+
+        for (profession, overrides, default_hours) in other_professions {
+            let cases = overrides
+                .iter()
+                .map(|(code, hours)| format!("WHEN '{}' THEN {}", code, hours))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            db.execute_unprepared(&format!(
+                "INSERT INTO requirement (state_id, profession, hours)
+                 SELECT id, '{}', CASE name {} ELSE {} END FROM state",
+                profession, cases, default_hours
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Requirement::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .drop_column(UserState::Profession)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::Profession)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Requirement {
+    Table,
+    Id,
+    StateId,
+    Profession,
+    Hours,
+}
+
+#[derive(DeriveIden)]
+enum State {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Profession,
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    Profession,
+}
@@ -0,0 +1,97 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Credit amounts are now stored as hundredths of a credit
+        db.execute_unprepared("UPDATE user_state SET hours_complete = hours_complete * 100")
+            .await?;
+        db.execute_unprepared("UPDATE requirement SET hours = hours * 100")
+            .await?;
+
+        // How many minutes of instruction make up one credit, and the smallest
+        // fraction of a credit (in hundredths) that may be awarded
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .add_column(integer(Requirement::MinutesPerCredit).default(60))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .add_column(integer(Requirement::CreditIncrement).default(25))
+                    .to_owned(),
+            )
+            .await?;
+
+        // CPE credit for CPAs follows the NASBA 50-minute hour with fifth-credit increments
+        db.execute_unprepared(
+            "UPDATE requirement SET minutes_per_credit = 50, credit_increment = 20
+             WHERE profession = 'CPA'",
+        )
+        .await?;
+
+        // Attorney jurisdictions that count a 50-minute hour
+        db.execute_unprepared(
+            "UPDATE requirement SET minutes_per_credit = 50
+             WHERE profession = 'Attorney'
+             AND state_id IN (SELECT id FROM state WHERE name IN ('NY', 'NJ', 'CT', 'FL'))",
+        )
+        .await?;
+
+        // New York only awards credit in half-hour increments
+        db.execute_unprepared(
+            "UPDATE requirement SET credit_increment = 50
+             WHERE profession = 'Attorney'
+             AND state_id IN (SELECT id FROM state WHERE name = 'NY')",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .drop_column(Requirement::CreditIncrement)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .drop_column(Requirement::MinutesPerCredit)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE requirement SET hours = hours / 100")
+            .await?;
+        db.execute_unprepared("UPDATE user_state SET hours_complete = hours_complete / 100")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Requirement {
+    Table,
+    MinutesPerCredit,
+    CreditIncrement,
+}
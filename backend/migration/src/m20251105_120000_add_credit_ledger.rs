@@ -0,0 +1,172 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CreditEntry::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CreditEntry::Id))
+                    .col(integer(CreditEntry::UserId))
+                    // Set when the entry only counts towards one of the user's licenses
+                    .col(integer_null(CreditEntry::UserStateId))
+                    .col(string(CreditEntry::Kind))
+                    .col(string(CreditEntry::Title))
+                    .col(string_null(CreditEntry::Provider))
+                    .col(date(CreditEntry::CompletedOn))
+                    .col(string_null(CreditEntry::Format))
+                    .col(string_null(CreditEntry::ApprovalNumber))
+                    .col(text_null(CreditEntry::Notes))
+                    .col(integer_null(CreditEntry::Minutes))
+                    .col(timestamp(CreditEntry::CreatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CreditEntry::Table)
+                            .from_col(CreditEntry::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CreditEntry::Table)
+                            .from_col(CreditEntry::UserStateId)
+                            .to_tbl(UserState::Table)
+                            .to_col(UserState::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CreditEntryCategory::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CreditEntryCategory::Id))
+                    .col(integer(CreditEntryCategory::CreditEntryId))
+                    .col(string(CreditEntryCategory::Category))
+                    .col(integer(CreditEntryCategory::Credits))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CreditEntryCategory::Table)
+                            .from_col(CreditEntryCategory::CreditEntryId)
+                            .to_tbl(CreditEntry::Table)
+                            .to_col(CreditEntry::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_credit_entry_user")
+                    .table(CreditEntry::Table)
+                    .col(CreditEntry::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Carry the hand-entered totals over as opening adjustments so nobody
+        // loses the hours they already reported
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO credit_entry (user_id, user_state_id, kind, title, completed_on, created_at)
+             SELECT user_id, id, 'adjustment', 'Hours reported before itemized tracking', date('now'), datetime('now')
+             FROM user_state WHERE hours_complete <> 0",
+        )
+        .await?;
+        db.execute_unprepared(
+            "INSERT INTO credit_entry_category (credit_entry_id, category, credits)
+             SELECT credit_entry.id, 'general', user_state.hours_complete
+             FROM credit_entry JOIN user_state ON user_state.id = credit_entry.user_state_id",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .drop_column(UserState::HoursComplete)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(integer(UserState::HoursComplete).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Fold scoped ledger entries back into the per-license total
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE user_state SET hours_complete = COALESCE((
+                    SELECT SUM(credit_entry_category.credits)
+                    FROM credit_entry
+                    JOIN credit_entry_category ON credit_entry_category.credit_entry_id = credit_entry.id
+                    WHERE credit_entry.user_id = user_state.user_id
+                    AND (credit_entry.user_state_id IS NULL OR credit_entry.user_state_id = user_state.id)
+                 ), 0)",
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CreditEntryCategory::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CreditEntry::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CreditEntry {
+    Table,
+    Id,
+    UserId,
+    UserStateId,
+    Kind,
+    Title,
+    Provider,
+    CompletedOn,
+    Format,
+    ApprovalNumber,
+    Notes,
+    Minutes,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CreditEntryCategory {
+    Table,
+    Id,
+    CreditEntryId,
+    Category,
+    Credits,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    Id,
+    HoursComplete,
+}
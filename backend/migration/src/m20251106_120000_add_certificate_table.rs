@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Certificate::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Certificate::Id))
+                    .col(integer(Certificate::CreditEntryId))
+                    .col(integer(Certificate::UserId))
+                    .col(string(Certificate::Filename))
+                    .col(string(Certificate::ContentType))
+                    .col(integer(Certificate::SizeBytes))
+                    .col(string(Certificate::BlobKey).unique_key())
+                    .col(timestamp(Certificate::UploadedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(Certificate::Table)
+                            .from_col(Certificate::CreditEntryId)
+                            .to_tbl(CreditEntry::Table)
+                            .to_col(CreditEntry::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(Certificate::Table)
+                            .from_col(Certificate::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Certificate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Certificate {
+    Table,
+    Id,
+    CreditEntryId,
+    UserId,
+    Filename,
+    ContentType,
+    SizeBytes,
+    BlobKey,
+    UploadedAt,
+}
+
+#[derive(DeriveIden)]
+enum CreditEntry {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
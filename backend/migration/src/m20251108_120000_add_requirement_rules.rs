@@ -0,0 +1,148 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Length of the attorney compliance period in each state, in months
+const ATTORNEY_PERIOD_MONTHS: &[(&str, i32)] = &[
+    ("CA", 36), ("CO", 36), ("DE", 24), ("FL", 36), ("ID", 36), ("IL", 24), ("IN", 36),
+    ("MN", 36), ("NJ", 24), ("NY", 24), ("ND", 36), ("OH", 24), ("OR", 36), ("UT", 24),
+    ("VT", 24), ("WA", 36), ("WV", 24), ("WI", 24),
+];
+
+/// Specialty minimums included in the overall requirement, in whole credits
+const CATEGORY_MINIMUMS: &[(&str, &str, &str, i32)] = &[
+    ("Attorney", "CA", "ethics", 4),
+    ("Attorney", "CA", "elimination_of_bias", 2),
+    ("Attorney", "CA", "technology", 1),
+    ("Attorney", "CA", "wellness", 1),
+    ("Attorney", "NY", "ethics", 4),
+    ("Attorney", "NY", "elimination_of_bias", 1),
+    ("Attorney", "NY", "technology", 1),
+    ("Attorney", "TX", "ethics", 3),
+    ("Attorney", "FL", "ethics", 5),
+    ("Attorney", "FL", "technology", 3),
+    ("Attorney", "IL", "ethics", 4),
+    ("Attorney", "IL", "elimination_of_bias", 1),
+    ("Attorney", "IL", "wellness", 1),
+    ("CPA", "CA", "ethics", 4),
+    ("CPA", "TX", "ethics", 4),
+    ("CPA", "NY", "ethics", 4),
+    ("CPA", "FL", "ethics", 4),
+    ("CPA", "IL", "ethics", 4),
+    ("Engineer", "TX", "ethics", 1),
+    ("Engineer", "FL", "ethics", 1),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .add_column(integer(Requirement::PeriodMonths).default(12))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RequirementCategory::Table)
+                    .if_not_exists()
+                    .col(pk_auto(RequirementCategory::Id))
+                    .col(integer(RequirementCategory::RequirementId))
+                    .col(string(RequirementCategory::Category))
+                    .col(integer(RequirementCategory::Hours))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(RequirementCategory::Table)
+                            .from_col(RequirementCategory::RequirementId)
+                            .to_tbl(Requirement::Table)
+                            .to_col(Requirement::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        let attorney_cases = ATTORNEY_PERIOD_MONTHS
+            .iter()
+            .map(|(code, months)| format!("WHEN '{}' THEN {}", code, months))
+            .collect::<Vec<_>>()
+            .join(" ");
+        db.execute_unprepared(&format!(
+            "UPDATE requirement SET period_months = (
+                SELECT CASE state.name {} ELSE 12 END FROM state WHERE state.id = requirement.state_id
+             ) WHERE profession = 'Attorney'",
+            attorney_cases
+        ))
+        .await?;
+
+        db.execute_unprepared(
+            "UPDATE requirement SET period_months = (
+                SELECT CASE state.name WHEN 'CA' THEN 24 WHEN 'FL' THEN 24 WHEN 'TX' THEN 36
+                    WHEN 'NY' THEN 36 WHEN 'IL' THEN 36 ELSE 12 END
+                FROM state WHERE state.id = requirement.state_id
+             ) WHERE profession = 'CPA'",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "UPDATE requirement SET period_months = (
+                SELECT CASE state.name WHEN 'TX' THEN 12 WHEN 'NY' THEN 36 ELSE 24 END
+                FROM state WHERE state.id = requirement.state_id
+             ) WHERE profession IN ('Engineer', 'Architect')",
+        )
+        .await?;
+
+        for (profession, code, category, hours) in CATEGORY_MINIMUMS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO requirement_category (requirement_id, category, hours)
+                 SELECT requirement.id, '{}', {}
+                 FROM requirement JOIN state ON state.id = requirement.state_id
+                 WHERE requirement.profession = '{}' AND state.name = '{}'",
+                category,
+                hours * 100,
+                profession,
+                code
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RequirementCategory::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .drop_column(Requirement::PeriodMonths)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Requirement {
+    Table,
+    Id,
+    PeriodMonths,
+}
+
+#[derive(DeriveIden)]
+enum RequirementCategory {
+    Table,
+    Id,
+    RequirementId,
+    Category,
+    Hours,
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Unset until a license's first period has been rolled over
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(date_null(UserState::PeriodStart))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CompliancePeriod::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CompliancePeriod::Id))
+                    .col(integer(CompliancePeriod::UserStateId))
+                    .col(date_null(CompliancePeriod::PeriodStart))
+                    .col(date(CompliancePeriod::PeriodEnd))
+                    .col(integer(CompliancePeriod::Required))
+                    .col(integer(CompliancePeriod::Completed))
+                    .col(string(CompliancePeriod::Status))
+                    .col(json(CompliancePeriod::Summary))
+                    .col(timestamp(CompliancePeriod::ClosedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CompliancePeriod::Table)
+                            .from_col(CompliancePeriod::UserStateId)
+                            .to_tbl(UserState::Table)
+                            .to_col(UserState::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_compliance_period_user_state")
+                    .table(CompliancePeriod::Table)
+                    .col(CompliancePeriod::UserStateId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CompliancePeriod::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .drop_column(UserState::PeriodStart)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    Id,
+    PeriodStart,
+}
+
+#[derive(DeriveIden)]
+enum CompliancePeriod {
+    Table,
+    Id,
+    UserStateId,
+    PeriodStart,
+    PeriodEnd,
+    Required,
+    Completed,
+    Status,
+    Summary,
+    ClosedAt,
+}
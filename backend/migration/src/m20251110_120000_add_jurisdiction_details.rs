@@ -0,0 +1,75 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one change per ALTER TABLE statement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(date_null(UserState::AdmissionDate))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(string_null(UserState::BarNumber))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(string(UserState::Status).default("active"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserState::Table)
+                    .add_column(date_null(UserState::DeadlineOverride))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            UserState::AdmissionDate,
+            UserState::BarNumber,
+            UserState::Status,
+            UserState::DeadlineOverride,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserState::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    AdmissionDate,
+    BarNumber,
+    Status,
+    DeadlineOverride,
+}
@@ -0,0 +1,86 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(HoursChange::Table)
+                    .if_not_exists()
+                    .col(pk_auto(HoursChange::Id))
+                    .col(integer(HoursChange::UserId))
+                    .col(integer(HoursChange::UserStateId))
+                    .col(integer(HoursChange::ActorId))
+                    .col(integer(HoursChange::OldHours))
+                    .col(integer(HoursChange::NewHours))
+                    .col(string(HoursChange::Source))
+                    // Not a foreign key: the adjustment may later be edited or deleted
+                    .col(integer_null(HoursChange::CreditEntryId))
+                    .col(integer_null(HoursChange::RevertsChangeId))
+                    .col(timestamp(HoursChange::CreatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(HoursChange::Table)
+                            .from_col(HoursChange::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(HoursChange::Table)
+                            .from_col(HoursChange::UserStateId)
+                            .to_tbl(UserState::Table)
+                            .to_col(UserState::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_hours_change_user_state")
+                    .table(HoursChange::Table)
+                    .col(HoursChange::UserStateId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(HoursChange::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum HoursChange {
+    Table,
+    Id,
+    UserId,
+    UserStateId,
+    ActorId,
+    OldHours,
+    NewHours,
+    Source,
+    CreditEntryId,
+    RevertsChangeId,
+    CreatedAt,
+}
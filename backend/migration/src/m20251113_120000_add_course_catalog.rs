@@ -0,0 +1,313 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The catalog previously hard-coded in the recommendations handler: title,
+/// provider, credit hours in hundredths, topic, format, price in cents and URL
+const COURSES: &[(&str, &str, i32, &str, &str, i32, &str)] = &[
+    ("Professional Responsibility and Ethics", "Massachusetts CLE", 200, "Ethics", "online", 5000, "https://www.massbar.org/continuing-legal-education"),
+    ("Contract Drafting Essentials", "Practising Law Institute", 300, "Contract Law", "self_paced", 9900, "https://www.pli.edu/programs/contract-drafting"),
+    ("Introduction to Legal Technology Tools", "ABA Techshow", 150, "Legal Tech", "online", 0, "https://www.techshow.com/education"),
+    ("Florida Bar Ethics Update 2024", "Florida Bar CLE", 250, "Ethics", "live_webinar", 0, "https://www.floridabar.org/cle/"),
+    ("AI for Legal Professionals", "Stanford CodeX", 200, "Legal Tech", "online", 0, "https://law.stanford.edu/codex-the-stanford-center-for-legal-informatics/"),
+    ("Advanced Contract Negotiation", "Harvard Law School", 350, "Contract Law", "self_paced", 15000, "https://online-learning.harvard.edu/catalog"),
+    ("Data Privacy and GDPR Compliance", "IAPP", 200, "Privacy Law", "online", 20000, "https://iapp.org/store/courses/"),
+    ("Legal Writing for Clarity", "Legal Writing Institute", 150, "Legal Writing", "self_paced", 0, "https://www.lwionline.org/"),
+    ("Business Law Foundations", "Coursera", 400, "Business Law", "self_paced", 0, "https://www.coursera.org/courses?query=business%20law"),
+    ("Immigration Law Essentials", "AILA", 250, "Immigration", "live_webinar", 12500, "https://www.aila.org/cle"),
+    ("Federal Taxation Fundamentals", "NYU School of Law", 300, "Tax Law", "self_paced", 17500, "https://www.law.nyu.edu/academics/cle"),
+    ("Real Estate Transactions", "California Lawyers Association", 250, "Real Estate Law", "online", 9500, "https://calawyers.org/cle/"),
+    ("Divorce and Child Custody Essentials", "National Business Institute", 300, "Family Law", "live_webinar", 14900, "https://www.nbi-sems.com/"),
+    ("Family Law Practice Fundamentals", "State Bar of Texas", 250, "Family Law", "online", 12500, "https://www.texasbar.com/"),
+    ("Bankruptcy Law Basics", "American Bankruptcy Institute", 200, "Bankruptcy Law", "self_paced", 15000, "https://www.abi.org/"),
+    ("Personal Injury Litigation", "National Institute for Trial Advocacy", 350, "Personal Injury", "online", 20000, "https://www.nita.org/"),
+    ("Criminal Defense Strategies", "NACDL", 300, "Criminal Law", "live_webinar", 15000, "https://www.nacdl.org/cle/"),
+    ("Employment Discrimination Law", "Georgetown Law", 350, "Employment Law", "self_paced", 20000, "https://www.law.georgetown.edu/continuing-legal-education/"),
+    ("Civil Litigation Fundamentals", "American Bar Association", 250, "Civil Litigation", "online", 12500, "https://www.americanbar.org/cle/"),
+    ("Intellectual Property Overview", "American Bar Association", 250, "IP Law", "online", 12500, "https://www.americanbar.org/cle/"),
+    ("Estate Planning and Wills", "UC Berkeley Extension", 300, "Estate Planning", "online", 19500, "https://extension.berkeley.edu/"),
+    ("Healthcare Compliance Essentials", "American Health Law Association", 200, "Healthcare Law", "online", 17500, "https://www.americanbar.org/groups/health_law/"),
+    ("Environmental Law Basics", "Environmental Law Institute", 250, "Environmental Law", "self_paced", 10000, "https://www.eli.org/"),
+    ("Cybersecurity for Law Firms", "ILTA", 150, "Legal Tech", "online", 0, "https://www.iltanet.org/"),
+];
+
+/// Descriptions of the courses that were recommended when no better match was found
+const SUMMARIES: &[(&str, &str)] = &[
+    ("Professional Responsibility and Ethics", "Essential ethics training for maintaining professional standards"),
+    ("Introduction to Legal Technology Tools", "Learn practical technology tools for modern legal practice"),
+    ("Contract Drafting Essentials", "Build strong foundation in contract drafting skills"),
+    ("Business Law Foundations", "Comprehensive overview of key business law concepts"),
+];
+
+/// State bar-run courses approved for attorney credit in their own state
+const APPROVALS: &[(&str, &str, &str)] = &[
+    ("Professional Responsibility and Ethics", "MA", "Attorney"),
+    ("Florida Bar Ethics Update 2024", "FL", "Attorney"),
+    ("Federal Taxation Fundamentals", "NY", "Attorney"),
+    ("Real Estate Transactions", "CA", "Attorney"),
+    ("Family Law Practice Fundamentals", "TX", "Attorney"),
+];
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Provider::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Provider::Id))
+                    .col(string_uniq(Provider::Name))
+                    .col(string_null(Provider::Url))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Course::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Course::Id))
+                    .col(integer(Course::ProviderId))
+                    .col(string(Course::Title))
+                    .col(text_null(Course::Summary))
+                    .col(integer(Course::Credits))
+                    .col(integer(Course::DurationMinutes))
+                    .col(integer(Course::PriceCents))
+                    .col(string(Course::Url))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(Course::Table)
+                            .from_col(Course::ProviderId)
+                            .to_tbl(Provider::Table)
+                            .to_col(Provider::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseTopic::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseTopic::Id))
+                    .col(integer(CourseTopic::CourseId))
+                    .col(string(CourseTopic::Topic))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseTopic::Table)
+                            .from_col(CourseTopic::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseFormat::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseFormat::Id))
+                    .col(integer(CourseFormat::CourseId))
+                    .col(string(CourseFormat::Format))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseFormat::Table)
+                            .from_col(CourseFormat::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseApproval::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseApproval::Id))
+                    .col(integer(CourseApproval::CourseId))
+                    .col(integer(CourseApproval::StateId))
+                    .col(string(CourseApproval::Profession))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseApproval::Table)
+                            .from_col(CourseApproval::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseApproval::Table)
+                            .from_col(CourseApproval::StateId)
+                            .to_tbl(State::Table)
+                            .to_col(State::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_topic_course")
+                    .table(CourseTopic::Table)
+                    .col(CourseTopic::CourseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_format_course")
+                    .table(CourseFormat::Table)
+                    .col(CourseFormat::CourseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_approval_course")
+                    .table(CourseApproval::Table)
+                    .col(CourseApproval::CourseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        for (title, provider, credits, topic, format, price_cents, url) in COURSES {
+            db.execute_unprepared(&format!(
+                "INSERT OR IGNORE INTO provider (name) VALUES ({})",
+                quote(provider)
+            ))
+            .await?;
+
+            // Courses are assumed to award one credit per hour of instruction
+            db.execute_unprepared(&format!(
+                "INSERT INTO course (provider_id, title, credits, duration_minutes, price_cents, url)
+                 SELECT id, {}, {}, {}, {}, {} FROM provider WHERE name = {}",
+                quote(title),
+                credits,
+                credits * 60 / 100,
+                price_cents,
+                quote(url),
+                quote(provider)
+            ))
+            .await?;
+
+            for (table, column, value) in [("course_topic", "topic", topic), ("course_format", "format", format)] {
+                db.execute_unprepared(&format!(
+                    "INSERT INTO {table} (course_id, {column})
+                     SELECT id, {} FROM course WHERE title = {}",
+                    quote(value),
+                    quote(title)
+                ))
+                .await?;
+            }
+        }
+
+        for (title, summary) in SUMMARIES {
+            db.execute_unprepared(&format!(
+                "UPDATE course SET summary = {} WHERE title = {}",
+                quote(summary),
+                quote(title)
+            ))
+            .await?;
+        }
+
+        for (title, code, profession) in APPROVALS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO course_approval (course_id, state_id, profession)
+                 SELECT course.id, state.id, {} FROM course, state
+                 WHERE course.title = {} AND state.name = {}",
+                quote(profession),
+                quote(title),
+                quote(code)
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            CourseApproval::Table.into_iden(),
+            CourseFormat::Table.into_iden(),
+            CourseTopic::Table.into_iden(),
+            Course::Table.into_iden(),
+            Provider::Table.into_iden(),
+        ] {
+            manager
+                .drop_table(Table::drop().table(table).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum State {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Provider {
+    Table,
+    Id,
+    Name,
+    Url,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    Id,
+    ProviderId,
+    Title,
+    Summary,
+    Credits,
+    DurationMinutes,
+    PriceCents,
+    Url,
+}
+
+#[derive(DeriveIden)]
+enum CourseTopic {
+    Table,
+    Id,
+    CourseId,
+    Topic,
+}
+
+#[derive(DeriveIden)]
+enum CourseFormat {
+    Table,
+    Id,
+    CourseId,
+    Format,
+}
+
+#[derive(DeriveIden)]
+enum CourseApproval {
+    Table,
+    Id,
+    CourseId,
+    StateId,
+    Profession,
+}
@@ -0,0 +1,138 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Admins maintain the course catalog; granted directly in the database
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(boolean(User::IsAdmin).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Retired rows stay for the audit trail but are hidden from users
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Provider::Table)
+                    .add_column(timestamp_null(Provider::RetiredAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .add_column(timestamp_null(Course::RetiredAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CatalogAudit::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CatalogAudit::Id))
+                    .col(integer_null(CatalogAudit::ActorId))
+                    .col(string(CatalogAudit::Entity))
+                    .col(integer(CatalogAudit::EntityId))
+                    .col(string(CatalogAudit::Action))
+                    .col(json_null(CatalogAudit::Before))
+                    .col(json_null(CatalogAudit::After))
+                    .col(timestamp(CatalogAudit::CreatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CatalogAudit::Table)
+                            .from_col(CatalogAudit::ActorId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_catalog_audit_entity")
+                    .table(CatalogAudit::Table)
+                    .col(CatalogAudit::Entity)
+                    .col(CatalogAudit::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CatalogAudit::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .drop_column(Course::RetiredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Provider::Table)
+                    .drop_column(Provider::RetiredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::IsAdmin)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    IsAdmin,
+}
+
+#[derive(DeriveIden)]
+enum Provider {
+    Table,
+    RetiredAt,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    RetiredAt,
+}
+
+#[derive(DeriveIden)]
+enum CatalogAudit {
+    Table,
+    Id,
+    ActorId,
+    Entity,
+    EntityId,
+    Action,
+    Before,
+    After,
+    CreatedAt,
+}
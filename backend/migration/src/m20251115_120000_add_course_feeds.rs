@@ -0,0 +1,72 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Scheduled courses from a provider's feed are told apart by their date
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .add_column(date_null(Course::StartsOn))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Either `manual` or `feed`; only feed courses are retired when they
+        // disappear from the provider's feed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .add_column(string(Course::Source).default("manual"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_provider")
+                    .table(Course::Table)
+                    .col(Course::ProviderId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_course_provider").table(Course::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .drop_column(Course::Source)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .drop_column(Course::StartsOn)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    ProviderId,
+    StartsOn,
+    Source,
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Rebuilds the search row for the courses picked out by `condition`.
+fn refresh(condition: &str) -> String {
+    format!(
+        "DELETE FROM course_fts WHERE rowid IN (SELECT id FROM course WHERE {condition});
+         INSERT INTO course_fts (rowid, title, summary, provider, topics)
+         SELECT course.id, course.title, coalesce(course.summary, ''), provider.name,
+                coalesce((SELECT group_concat(topic, ' ') FROM course_topic
+                          WHERE course_topic.course_id = course.id), '')
+         FROM course JOIN provider ON provider.id = course.provider_id
+         WHERE {condition};"
+    )
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Course ids are used as row ids so matches join straight back to the catalog
+        db.execute_unprepared(
+            "CREATE VIRTUAL TABLE course_fts USING fts5(
+                 title, summary, provider, topics, tokenize = 'porter unicode61'
+             )",
+        )
+        .await?;
+
+        // Triggers keep the index in step with every write to the catalog,
+        // including the ones made by feed ingestion and migrations
+        let triggers = [
+            ("course_fts_course_insert", "AFTER INSERT ON course", refresh("course.id = NEW.id")),
+            ("course_fts_course_update", "AFTER UPDATE ON course", refresh("course.id = NEW.id")),
+            (
+                "course_fts_course_delete",
+                "AFTER DELETE ON course",
+                "DELETE FROM course_fts WHERE rowid = OLD.id;".to_string(),
+            ),
+            (
+                "course_fts_topic_insert",
+                "AFTER INSERT ON course_topic",
+                refresh("course.id = NEW.course_id"),
+            ),
+            (
+                "course_fts_topic_delete",
+                "AFTER DELETE ON course_topic",
+                refresh("course.id = OLD.course_id"),
+            ),
+            (
+                "course_fts_provider_update",
+                "AFTER UPDATE OF name ON provider",
+                refresh("course.provider_id = NEW.id"),
+            ),
+        ];
+        for (name, event, body) in triggers {
+            db.execute_unprepared(&format!("CREATE TRIGGER {name} {event} BEGIN {body} END"))
+                .await?;
+        }
+
+        db.execute_unprepared(&refresh("1")).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        for name in [
+            "course_fts_course_insert",
+            "course_fts_course_update",
+            "course_fts_course_delete",
+            "course_fts_topic_insert",
+            "course_fts_topic_delete",
+            "course_fts_provider_update",
+        ] {
+            db.execute_unprepared(&format!("DROP TRIGGER IF EXISTS {name}")).await?;
+        }
+
+        db.execute_unprepared("DROP TABLE IF EXISTS course_fts").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Times are stored in UTC; the time zone is the one the provider
+        // announced the session in, kept for display
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseSession::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseSession::Id))
+                    .col(integer(CourseSession::CourseId))
+                    .col(timestamp(CourseSession::StartsAt))
+                    .col(timestamp(CourseSession::EndsAt))
+                    .col(string(CourseSession::TimeZone))
+                    .col(integer_null(CourseSession::Capacity))
+                    .col(string_null(CourseSession::JoinUrl))
+                    .col(timestamp_null(CourseSession::CancelledAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseSession::Table)
+                            .from_col(CourseSession::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_session_course")
+                    .table(CourseSession::Table)
+                    .col(CourseSession::CourseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseRegistration::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseRegistration::Id))
+                    .col(integer(CourseRegistration::SessionId))
+                    .col(integer(CourseRegistration::UserId))
+                    .col(timestamp(CourseRegistration::CreatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseRegistration::Table)
+                            .from_col(CourseRegistration::SessionId)
+                            .to_tbl(CourseSession::Table)
+                            .to_col(CourseSession::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseRegistration::Table)
+                            .from_col(CourseRegistration::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_registration_session_user")
+                    .table(CourseRegistration::Table)
+                    .col(CourseRegistration::SessionId)
+                    .col(CourseRegistration::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Secret for the user's calendar feed; calendar clients can't send
+        // the session cookie
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(string_null(User::CalendarToken))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_calendar_token")
+                    .table(User::Table)
+                    .col(User::CalendarToken)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_user_calendar_token").table(User::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::CalendarToken)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CourseRegistration::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CourseSession::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    CalendarToken,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum CourseSession {
+    Table,
+    Id,
+    CourseId,
+    StartsAt,
+    EndsAt,
+    TimeZone,
+    Capacity,
+    JoinUrl,
+    CancelledAt,
+}
+
+#[derive(DeriveIden)]
+enum CourseRegistration {
+    Table,
+    Id,
+    SessionId,
+    UserId,
+    CreatedAt,
+}
@@ -0,0 +1,135 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Links logged credit to the catalog course it was earned from. SQLite
+        // can't add a foreign key to an existing table, so it isn't enforced.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .add_column(integer_null(CreditEntry::CourseId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_credit_entry_course")
+                    .table(CreditEntry::Table)
+                    .col(CreditEntry::CourseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // One review per user and course; `status` is `published` or `hidden`
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseReview::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseReview::Id))
+                    .col(integer(CourseReview::CourseId))
+                    .col(integer(CourseReview::UserId))
+                    .col(integer(CourseReview::Rating))
+                    .col(text_null(CourseReview::Body))
+                    .col(string(CourseReview::Status).default("published"))
+                    .col(integer_null(CourseReview::ModeratedBy))
+                    .col(text_null(CourseReview::ModerationNote))
+                    .col(timestamp(CourseReview::CreatedAt))
+                    .col(timestamp(CourseReview::UpdatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseReview::Table)
+                            .from_col(CourseReview::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseReview::Table)
+                            .from_col(CourseReview::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseReview::Table)
+                            .from_col(CourseReview::ModeratedBy)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_review_course_user")
+                    .table(CourseReview::Table)
+                    .col(CourseReview::CourseId)
+                    .col(CourseReview::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CourseReview::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx_credit_entry_course").table(CreditEntry::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .drop_column(CreditEntry::CourseId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum CreditEntry {
+    Table,
+    CourseId,
+}
+
+#[derive(DeriveIden)]
+enum CourseReview {
+    Table,
+    Id,
+    CourseId,
+    UserId,
+    Rating,
+    Body,
+    Status,
+    ModeratedBy,
+    ModerationNote,
+    CreatedAt,
+    UpdatedAt,
+}
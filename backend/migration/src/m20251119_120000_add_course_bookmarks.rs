@@ -0,0 +1,98 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // A course the user has shortlisted; `status` is `saved`, or `planned`
+        // once they mean to take it, optionally for one license and by a date
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseBookmark::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseBookmark::Id))
+                    .col(integer(CourseBookmark::UserId))
+                    .col(integer(CourseBookmark::CourseId))
+                    .col(string(CourseBookmark::Status))
+                    .col(integer_null(CourseBookmark::UserStateId))
+                    .col(date_null(CourseBookmark::PlannedFor))
+                    .col(timestamp(CourseBookmark::CreatedAt))
+                    .col(timestamp(CourseBookmark::UpdatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseBookmark::Table)
+                            .from_col(CourseBookmark::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseBookmark::Table)
+                            .from_col(CourseBookmark::CourseId)
+                            .to_tbl(Course::Table)
+                            .to_col(Course::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseBookmark::Table)
+                            .from_col(CourseBookmark::UserStateId)
+                            .to_tbl(UserState::Table)
+                            .to_col(UserState::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_bookmark_user_course")
+                    .table(CourseBookmark::Table)
+                    .col(CourseBookmark::UserId)
+                    .col(CourseBookmark::CourseId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CourseBookmark::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum UserState {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum CourseBookmark {
+    Table,
+    Id,
+    UserId,
+    CourseId,
+    Status,
+    UserStateId,
+    PlannedFor,
+    CreatedAt,
+    UpdatedAt,
+}
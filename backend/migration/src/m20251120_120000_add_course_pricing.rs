@@ -0,0 +1,215 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Prices are in minor units of the course's ISO 4217 currency
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .add_column(string(Course::Currency).default("USD"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // What members of the provider pay, when they get a discount
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .add_column(integer_null(Course::MemberPriceCents))
+                    .to_owned(),
+            )
+            .await?;
+
+        // What the user paid for the credit, in minor units of `currency`
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .add_column(integer_null(CreditEntry::AmountPaidCents))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .add_column(string_null(CreditEntry::Currency))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Providers the user is a member of, so member prices apply to them
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProviderMembership::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ProviderMembership::Id))
+                    .col(integer(ProviderMembership::UserId))
+                    .col(integer(ProviderMembership::ProviderId))
+                    .col(timestamp(ProviderMembership::CreatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(ProviderMembership::Table)
+                            .from_col(ProviderMembership::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(ProviderMembership::Table)
+                            .from_col(ProviderMembership::ProviderId)
+                            .to_tbl(Provider::Table)
+                            .to_col(Provider::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_provider_membership_user_provider")
+                    .table(ProviderMembership::Table)
+                    .col(ProviderMembership::UserId)
+                    .col(ProviderMembership::ProviderId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // What the user means to spend on continuing education in a calendar year
+        manager
+            .create_table(
+                Table::create()
+                    .table(Budget::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Budget::Id))
+                    .col(integer(Budget::UserId))
+                    .col(integer(Budget::Year))
+                    .col(integer(Budget::AmountCents))
+                    .col(string(Budget::Currency))
+                    .col(timestamp(Budget::UpdatedAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(Budget::Table)
+                            .from_col(Budget::UserId)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_budget_user_year")
+                    .table(Budget::Table)
+                    .col(Budget::UserId)
+                    .col(Budget::Year)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Budget::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProviderMembership::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .drop_column(CreditEntry::Currency)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CreditEntry::Table)
+                    .drop_column(CreditEntry::AmountPaidCents)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .drop_column(Course::MemberPriceCents)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Course::Table)
+                    .drop_column(Course::Currency)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Provider {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Course {
+    Table,
+    Currency,
+    MemberPriceCents,
+}
+
+#[derive(DeriveIden)]
+enum CreditEntry {
+    Table,
+    AmountPaidCents,
+    Currency,
+}
+
+#[derive(DeriveIden)]
+enum ProviderMembership {
+    Table,
+    Id,
+    UserId,
+    ProviderId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Budget {
+    Table,
+    Id,
+    UserId,
+    Year,
+    AmountCents,
+    Currency,
+    UpdatedAt,
+}
@@ -0,0 +1,121 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The activity number the jurisdiction assigned, which its reports ask for
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .add_column(string_null(CourseApproval::ApprovalNumber))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Approvals are granted for a window; either end may be open
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .add_column(date_null(CourseApproval::ValidFrom))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .add_column(date_null(CourseApproval::ValidUntil))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Credits the jurisdiction grants per category, in hundredths. Approvals
+        // without rows grant the course's credits under its topics.
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseApprovalCredit::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CourseApprovalCredit::Id))
+                    .col(integer(CourseApprovalCredit::CourseApprovalId))
+                    .col(string(CourseApprovalCredit::Category))
+                    .col(integer(CourseApprovalCredit::Credits))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(CourseApprovalCredit::Table)
+                            .from_col(CourseApprovalCredit::CourseApprovalId)
+                            .to_tbl(CourseApproval::Table)
+                            .to_col(CourseApproval::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_course_approval_credit_approval")
+                    .table(CourseApprovalCredit::Table)
+                    .col(CourseApprovalCredit::CourseApprovalId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CourseApprovalCredit::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .drop_column(CourseApproval::ValidUntil)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .drop_column(CourseApproval::ValidFrom)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CourseApproval::Table)
+                    .drop_column(CourseApproval::ApprovalNumber)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CourseApproval {
+    Table,
+    Id,
+    ApprovalNumber,
+    ValidFrom,
+    ValidUntil,
+}
+
+#[derive(DeriveIden)]
+enum CourseApprovalCredit {
+    Table,
+    Id,
+    CourseApprovalId,
+    Category,
+    Credits,
+}
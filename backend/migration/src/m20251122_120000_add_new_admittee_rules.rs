@@ -0,0 +1,111 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// What an attorney first admitted during the current period must complete,
+/// in whole credits. Zero means nothing is due until the first full period.
+const NEW_ADMITTEE_HOURS: &[(&str, &str, i32)] = &[
+    ("Attorney", "NY", 32),
+    ("Attorney", "TX", 0),
+    ("Attorney", "FL", 0),
+    ("Attorney", "IL", 0),
+];
+
+/// Specialty minimums within a transitional requirement, in whole credits
+const NEW_ADMITTEE_MINIMUMS: &[(&str, &str, &str, i32)] = &[
+    ("Attorney", "NY", "ethics", 3),
+    ("Attorney", "NY", "skills", 6),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Left empty where new admittees follow the regular requirement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .add_column(integer_null(Requirement::NewAdmitteeHours))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Marks minimums that apply only to the transitional requirement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RequirementCategory::Table)
+                    .add_column(boolean(RequirementCategory::NewAdmittee).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        for (profession, code, hours) in NEW_ADMITTEE_HOURS {
+            db.execute_unprepared(&format!(
+                "UPDATE requirement SET new_admittee_hours = {}
+                 WHERE profession = '{}'
+                   AND state_id = (SELECT id FROM state WHERE name = '{}')",
+                hours * 100,
+                profession,
+                code
+            ))
+            .await?;
+        }
+
+        for (profession, code, category, hours) in NEW_ADMITTEE_MINIMUMS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO requirement_category (requirement_id, category, hours, new_admittee)
+                 SELECT requirement.id, '{}', {}, TRUE
+                 FROM requirement JOIN state ON state.id = requirement.state_id
+                 WHERE requirement.profession = '{}' AND state.name = '{}'",
+                category,
+                hours * 100,
+                profession,
+                code
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM requirement_category WHERE new_admittee")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RequirementCategory::Table)
+                    .drop_column(RequirementCategory::NewAdmittee)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Requirement::Table)
+                    .drop_column(Requirement::NewAdmitteeHours)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Requirement {
+    Table,
+    NewAdmitteeHours,
+}
+
+#[derive(DeriveIden)]
+enum RequirementCategory {
+    Table,
+    NewAdmittee,
+}
@@ -0,0 +1,604 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use entity::{Credits, catalog_audit, course, provider};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tower_cookies::Cookies;
+
+use crate::catalog::{ApprovalRecord, CatalogCourse, CourseFormat, CourseResponse};
+use crate::currency::Currency;
+use crate::ledger::CategoryCredits;
+use crate::profession::Profession;
+use crate::register::UsState;
+
+/// Accepts absolute http(s) URLs only, so catalog links can't run script.
+pub fn check_url(url: &str) -> Result<(), (StatusCode, &'static str)> {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.host().is_some() => Ok(()),
+        _ => Err((StatusCode::BAD_REQUEST, "URL must be an absolute http or https address")),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProviderRequest {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ProviderResponse {
+    id: i32,
+    name: String,
+    url: Option<String>,
+    retired_at: Option<DateTime<Utc>>,
+}
+
+impl From<provider::Model> for ProviderResponse {
+    fn from(p: provider::Model) -> Self {
+        ProviderResponse {
+            id: p.id,
+            name: p.name,
+            url: p.url,
+            retired_at: p.retired_at,
+        }
+    }
+}
+
+async fn check_provider<C: ConnectionTrait>(
+    conn: &C,
+    data: &ProviderRequest,
+    id: Option<i32>,
+) -> Result<(), (StatusCode, &'static str)> {
+    if data.name.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Provider name is required"));
+    }
+    if let Some(url) = crate::ledger::non_blank(data.url.clone()) {
+        check_url(&url)?;
+    }
+
+    let existing = provider::Entity::find()
+        .filter(provider::Column::Name.eq(data.name.trim()))
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if existing.is_some_and(|p| Some(p.id) != id) {
+        return Err((StatusCode::CONFLICT, "A provider with this name already exists"));
+    }
+    Ok(())
+}
+
+async fn find_provider<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+) -> Result<provider::Model, (StatusCode, &'static str)> {
+    provider::Entity::find_by_id(id)
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Provider not found"))
+}
+
+pub async fn list_providers(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<ProviderResponse>>, (StatusCode, &'static str)> {
+    crate::auth::current_admin(&state.conn, &cookies).await?;
+
+    let providers = provider::Entity::find()
+        .order_by_asc(provider::Column::Name)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(providers.into_iter().map(Into::into).collect()))
+}
+
+pub async fn create_provider(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Json(data): Json<ProviderRequest>,
+) -> Result<Json<ProviderResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    check_provider(&state.conn, &data, None).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let created = provider::ActiveModel {
+        name: Set(data.name.trim().to_string()),
+        url: Set(crate::ledger::non_blank(data.url)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save provider"))?;
+
+    let response = ProviderResponse::from(created);
+    crate::catalog::record_audit(&txn, Some(admin.id), "provider", response.id, "create", None, Some(&response))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save provider"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save provider"))?;
+
+    Ok(Json(response))
+}
+
+pub async fn update_provider(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    Json(data): Json<ProviderRequest>,
+) -> Result<Json<ProviderResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let existing = find_provider(&state.conn, id).await?;
+    check_provider(&state.conn, &data, Some(id)).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut active: provider::ActiveModel = existing.clone().into();
+    active.name = Set(data.name.trim().to_string());
+    active.url = Set(crate::ledger::non_blank(data.url));
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update provider"))?;
+
+    let before = ProviderResponse::from(existing);
+    let after = ProviderResponse::from(updated);
+    crate::catalog::record_audit(&txn, Some(admin.id), "provider", id, "update", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update provider"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update provider"))?;
+
+    Ok(Json(after))
+}
+
+/// Hides a provider and all of its courses from users.
+pub async fn retire_provider(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<Json<ProviderResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let existing = find_provider(&state.conn, id).await?;
+    if existing.retired_at.is_some() {
+        return Ok(Json(existing.into()));
+    }
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut active: provider::ActiveModel = existing.clone().into();
+    active.retired_at = Set(Some(Utc::now()));
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire provider"))?;
+
+    let before = ProviderResponse::from(existing);
+    let after = ProviderResponse::from(updated);
+    crate::catalog::record_audit(&txn, Some(admin.id), "provider", id, "retire", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire provider"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire provider"))?;
+
+    Ok(Json(after))
+}
+
+#[derive(Deserialize)]
+pub struct ApprovalRequest {
+    pub state_code: UsState,
+    pub profession: Profession,
+    pub approval_number: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_until: Option<NaiveDate>,
+    /// Credits the jurisdiction grants per category, when they differ from
+    /// what the course's topics suggest
+    #[serde(default)]
+    pub credits: Vec<CategoryCredits>,
+}
+
+/// A course as the content team edits it; mirrors the fields users see in
+/// recommendations.
+#[derive(Deserialize)]
+pub struct CourseRequest {
+    pub provider_id: i32,
+    pub title: String,
+    pub summary: Option<String>,
+    pub hours: Credits,
+    /// Defaults to an hour per credit
+    pub duration_minutes: Option<u32>,
+    #[serde(default)]
+    pub price_cents: u32,
+    #[serde(default)]
+    pub currency: Currency,
+    /// Discounted price for members of the provider
+    pub member_price_cents: Option<u32>,
+    pub url: String,
+    pub topics: Vec<String>,
+    pub formats: Vec<CourseFormat>,
+    #[serde(default)]
+    pub approvals: Vec<ApprovalRequest>,
+    /// For courses held on a particular day
+    pub starts_on: Option<NaiveDate>,
+}
+
+/// Checks a course and resolves its topics and approvals to what's stored.
+async fn check_course<C: ConnectionTrait>(
+    conn: &C,
+    data: &CourseRequest,
+) -> Result<(Vec<String>, Vec<ApprovalRecord>), (StatusCode, &'static str)> {
+    if data.title.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Title is required"));
+    }
+    if data.hours <= Credits::ZERO {
+        return Err((StatusCode::BAD_REQUEST, "Hours must be greater than zero"));
+    }
+    // Keeps the duration worked out from the hours within range as well
+    if data.hours > Credits::MAX {
+        return Err((StatusCode::BAD_REQUEST, "Hours are too large"));
+    }
+    if data.duration_minutes == Some(0) {
+        return Err((StatusCode::BAD_REQUEST, "Duration must be greater than zero"));
+    }
+    if data.duration_minutes.is_some_and(|m| i32::try_from(m).is_err()) {
+        return Err((StatusCode::BAD_REQUEST, "Duration is too long"));
+    }
+    // The member price is checked against this one below, so it fits too
+    if i32::try_from(data.price_cents).is_err() {
+        return Err((StatusCode::BAD_REQUEST, "Price is too large"));
+    }
+    if data.member_price_cents.is_some_and(|m| m > data.price_cents) {
+        return Err((StatusCode::BAD_REQUEST, "Member price cannot be above the regular price"));
+    }
+    check_url(&data.url)?;
+
+    if data.topics.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "At least one topic is required"));
+    }
+    let topics = data
+        .topics
+        .iter()
+        .map(|t| crate::catalog::known_topic(t).map(str::to_string))
+        .collect::<Option<Vec<_>>>()
+        .ok_or((StatusCode::BAD_REQUEST, "Unknown topic"))?;
+    if topics.iter().collect::<HashSet<_>>().len() != topics.len() {
+        return Err((StatusCode::BAD_REQUEST, "Each topic may only appear once"));
+    }
+
+    if data.formats.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "At least one format is required"));
+    }
+    if data.formats.iter().collect::<HashSet<_>>().len() != data.formats.len() {
+        return Err((StatusCode::BAD_REQUEST, "Each format may only appear once"));
+    }
+
+    let provider = find_provider(conn, data.provider_id).await?;
+    if provider.retired_at.is_some() {
+        return Err((StatusCode::BAD_REQUEST, "Provider has been retired"));
+    }
+
+    let mut approvals: Vec<ApprovalRecord> = Vec::new();
+    for approval in &data.approvals {
+        if let (Some(from), Some(until)) = (approval.valid_from, approval.valid_until)
+            && from > until
+        {
+            return Err((StatusCode::BAD_REQUEST, "Approval ends before it starts"));
+        }
+        if approval.credits.iter().any(|c| c.credits <= Credits::ZERO) {
+            return Err((StatusCode::BAD_REQUEST, "Credits must be greater than zero"));
+        }
+        if approval.credits.iter().map(|c| c.category).collect::<HashSet<_>>().len() != approval.credits.len() {
+            return Err((StatusCode::BAD_REQUEST, "Each category may only appear once"));
+        }
+        let state_record = entity::state::Entity::find()
+            .filter(entity::state::Column::Name.eq(approval.state_code.to_string()))
+            .one(conn)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+            .ok_or((StatusCode::NOT_FOUND, "State code not found"))?;
+        let record = ApprovalRecord {
+            state_id: state_record.id,
+            profession: approval.profession.to_string(),
+            approval_number: crate::ledger::non_blank(approval.approval_number.clone()),
+            valid_from: approval.valid_from,
+            valid_until: approval.valid_until,
+            credits: approval.credits.clone(),
+        };
+        // A jurisdiction may re-approve a course under a new number, but only
+        // one approval can be in force on any day
+        if approvals.iter().any(|a| a.overlaps(&record)) {
+            return Err((StatusCode::BAD_REQUEST, "Approvals for a jurisdiction cannot overlap"));
+        }
+        approvals.push(record);
+    }
+
+    Ok((topics, approvals))
+}
+
+async fn find_course<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+) -> Result<CatalogCourse, (StatusCode, &'static str)> {
+    crate::catalog::load_course(conn, id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))
+}
+
+/// Lists every course, including retired ones.
+pub async fn list_courses(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<CourseResponse>>, (StatusCode, &'static str)> {
+    crate::auth::current_admin(&state.conn, &cookies).await?;
+
+    let courses = crate::catalog::load_courses(&state.conn, true)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(courses.iter().map(Into::into).collect()))
+}
+
+pub async fn create_course(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Json(data): Json<CourseRequest>,
+) -> Result<Json<CourseResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let (topics, approvals) = check_course(&state.conn, &data).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let created = course::ActiveModel {
+        provider_id: Set(data.provider_id),
+        title: Set(data.title.trim().to_string()),
+        summary: Set(crate::ledger::non_blank(data.summary)),
+        credits: Set(data.hours),
+        duration_minutes: Set(duration_minutes(data.duration_minutes, data.hours)),
+        price_cents: Set(data.price_cents as i32),
+        currency: Set(data.currency.to_string()),
+        member_price_cents: Set(data.member_price_cents.map(|m| m as i32)),
+        url: Set(data.url.trim().to_string()),
+        starts_on: Set(data.starts_on),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save course"))?;
+
+    crate::catalog::replace_course_details(&txn, created.id, &topics, &data.formats, &approvals)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save course"))?;
+
+    let after = CourseResponse::from(&find_course(&txn, created.id).await?);
+    crate::catalog::record_audit(&txn, Some(admin.id), "course", created.id, "create", None, Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save course"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save course"))?;
+
+    Ok(Json(after))
+}
+
+/// The given duration, or one worked out from the hours. Both have been
+/// bounded by `check_course`.
+fn duration_minutes(minutes: Option<u32>, hours: Credits) -> i32 {
+    minutes
+        .map(|m| m as i32)
+        .unwrap_or((hours.hundredths() * 60 / 100) as i32)
+}
+
+pub async fn update_course(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    Json(data): Json<CourseRequest>,
+) -> Result<Json<CourseResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let existing = find_course(&state.conn, id).await?;
+    let (topics, approvals) = check_course(&state.conn, &data).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut active: course::ActiveModel = existing.course.clone().into();
+    active.provider_id = Set(data.provider_id);
+    active.title = Set(data.title.trim().to_string());
+    active.summary = Set(crate::ledger::non_blank(data.summary));
+    active.credits = Set(data.hours);
+    active.duration_minutes = Set(duration_minutes(data.duration_minutes, data.hours));
+    active.price_cents = Set(data.price_cents as i32);
+    active.currency = Set(data.currency.to_string());
+    active.member_price_cents = Set(data.member_price_cents.map(|m| m as i32));
+    active.url = Set(data.url.trim().to_string());
+    active.starts_on = Set(data.starts_on);
+    active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update course"))?;
+
+    crate::catalog::replace_course_details(&txn, id, &topics, &data.formats, &approvals)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update course"))?;
+
+    let before = CourseResponse::from(&existing);
+    let after = CourseResponse::from(&find_course(&txn, id).await?);
+    crate::catalog::record_audit(&txn, Some(admin.id), "course", id, "update", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update course"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update course"))?;
+
+    Ok(Json(after))
+}
+
+/// Hides a course from users while keeping it for history and audit.
+pub async fn retire_course(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<Json<CourseResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let existing = find_course(&state.conn, id).await?;
+    if existing.course.retired_at.is_some() {
+        return Ok(Json((&existing).into()));
+    }
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut active: course::ActiveModel = existing.course.clone().into();
+    active.retired_at = Set(Some(Utc::now()));
+    active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire course"))?;
+
+    let before = CourseResponse::from(&existing);
+    let after = CourseResponse::from(&find_course(&txn, id).await?);
+    crate::catalog::record_audit(&txn, Some(admin.id), "course", id, "retire", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire course"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to retire course"))?;
+
+    Ok(Json(after))
+}
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    /// `course` or `provider`
+    entity: Option<String>,
+    entity_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct AuditResponse {
+    id: i32,
+    actor_id: Option<i32>,
+    actor: Option<String>,
+    entity: String,
+    entity_id: i32,
+    action: String,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+    created_at: DateTime<Utc>,
+}
+
+/// Lists catalog changes, most recent first.
+pub async fn list_audit(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditResponse>>, (StatusCode, &'static str)> {
+    crate::auth::current_admin(&state.conn, &cookies).await?;
+
+    let mut audit = catalog_audit::Entity::find().find_also_related(entity::user::Entity);
+    if let Some(entity) = query.entity {
+        audit = audit.filter(catalog_audit::Column::Entity.eq(entity));
+    }
+    if let Some(entity_id) = query.entity_id {
+        audit = audit.filter(catalog_audit::Column::EntityId.eq(entity_id));
+    }
+    let audit = audit
+        .order_by_desc(catalog_audit::Column::CreatedAt)
+        .order_by_desc(catalog_audit::Column::Id)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(
+        audit
+            .into_iter()
+            .map(|(entry, actor)| AuditResponse {
+                id: entry.id,
+                actor_id: entry.actor_id,
+                actor: actor.map(|a| a.username),
+                entity: entry.entity,
+                entity_id: entry.entity_id,
+                action: entry.action,
+                before: entry.before,
+                after: entry.after,
+                created_at: entry.created_at,
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn oversized_courses_are_rejected() {
+        let conn = crate::test_support::database().await;
+        let provider = entity::provider::Entity::find().one(&conn).await.unwrap().unwrap();
+        let request = |changes: serde_json::Value| -> CourseRequest {
+            let mut value = json!({
+                "provider_id": provider.id,
+                "title": "Evidence",
+                "hours": 2,
+                "price_cents": 9900,
+                "url": "https://example.com/evidence",
+                "topics": ["Ethics"],
+                "formats": ["online"],
+            });
+            value.as_object_mut().unwrap().extend(changes.as_object().unwrap().clone());
+            serde_json::from_value(value).unwrap()
+        };
+        let message = async |data: &CourseRequest| check_course(&conn, data).await.err().map(|(_, message)| message);
+
+        assert_eq!(message(&request(json!({}))).await, None);
+        assert_eq!(message(&request(json!({"price_cents": 3_000_000_000u32}))).await, Some("Price is too large"));
+        assert_eq!(message(&request(json!({"duration_minutes": 3_000_000_000u32}))).await, Some("Duration is too long"));
+        // Hours from the request are bounded when they're read; this is the
+        // backstop for the duration worked out from them
+        let mut huge = request(json!({}));
+        huge.hours = Credits::from_hundredths(i64::MAX);
+        assert_eq!(message(&huge).await, Some("Hours are too large"));
+    }
+}
@@ -0,0 +1,37 @@
+use axum::http::StatusCode;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tower_cookies::Cookies;
+
+/// Resolves the session cookie on a request to the logged-in user.
+pub async fn current_user(
+    conn: &DatabaseConnection,
+    cookies: &Cookies,
+) -> Result<entity::user::Model, (StatusCode, &'static str)> {
+    // Get session token from cookie
+    let session_token = cookies
+        .get("session")
+        .ok_or((StatusCode::UNAUTHORIZED, "Not logged in"))?
+        .value()
+        .to_string();
+
+    // Verify session and get user
+    entity::user::Entity::find()
+        .inner_join(entity::session::Entity)
+        .filter(entity::session::Column::Token.eq(session_token))
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::FORBIDDEN, "Not logged in"))
+}
+
+/// Resolves the logged-in user, rejecting anyone who isn't an admin.
+pub async fn current_admin(
+    conn: &DatabaseConnection,
+    cookies: &Cookies,
+) -> Result<entity::user::Model, (StatusCode, &'static str)> {
+    let user = current_user(conn, cookies).await?;
+    if !user.is_admin {
+        return Err((StatusCode::FORBIDDEN, "Admin access required"));
+    }
+    Ok(user)
+}
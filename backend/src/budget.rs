@@ -0,0 +1,452 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use entity::{budget, credit_entry, provider, provider_membership};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tower_cookies::Cookies;
+
+use crate::currency::Currency;
+use crate::plan::BookmarkStatus;
+
+/// Ids of the providers the user is a member of.
+pub async fn memberships<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<HashSet<i32>, DbErr> {
+    Ok(provider_membership::Entity::find()
+        .filter(provider_membership::Column::UserId.eq(user_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|m| m.provider_id)
+        .collect())
+}
+
+#[derive(Serialize)]
+pub struct MembershipResponse {
+    provider_id: i32,
+    provider: String,
+    since: DateTime<Utc>,
+}
+
+pub async fn list_memberships(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<MembershipResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let memberships = provider_membership::Entity::find()
+        .find_also_related(provider::Entity)
+        .filter(provider_membership::Column::UserId.eq(user.id))
+        .order_by_asc(provider_membership::Column::Id)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(
+        memberships
+            .into_iter()
+            .filter_map(|(membership, provider)| {
+                Some(MembershipResponse {
+                    provider_id: membership.provider_id,
+                    provider: provider?.name,
+                    since: membership.created_at,
+                })
+            })
+            .collect(),
+    ))
+}
+
+/// Records that the user is a member of a provider, so its member prices
+/// apply to them.
+pub async fn add_membership(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(provider_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    provider::Entity::find_by_id(provider_id)
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Provider not found"))?;
+
+    let existing = memberships(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if !existing.contains(&provider_id) {
+        provider_membership::ActiveModel {
+            user_id: Set(user.id),
+            provider_id: Set(provider_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save membership"))?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn delete_membership(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(provider_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let deleted = provider_membership::Entity::delete_many()
+        .filter(provider_membership::Column::UserId.eq(user.id))
+        .filter(provider_membership::Column::ProviderId.eq(provider_id))
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete membership"))?;
+    if deleted.rows_affected == 0 {
+        return Err((StatusCode::NOT_FOUND, "Membership not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn check_year(year: i32) -> Result<(), (StatusCode, &'static str)> {
+    if !(2000..=2100).contains(&year) {
+        return Err((StatusCode::BAD_REQUEST, "Year is out of range"));
+    }
+    Ok(())
+}
+
+async fn find_budget<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    year: i32,
+) -> Result<Option<budget::Model>, (StatusCode, &'static str)> {
+    budget::Entity::find()
+        .filter(budget::Column::UserId.eq(user_id))
+        .filter(budget::Column::Year.eq(year))
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
+}
+
+#[derive(Deserialize)]
+pub struct BudgetRequest {
+    amount_cents: u32,
+    #[serde(default)]
+    currency: Currency,
+}
+
+/// Sets the user's budget for a calendar year.
+pub async fn save_budget(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(year): Path<i32>,
+    Json(data): Json<BudgetRequest>,
+) -> Result<Json<BudgetResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    check_year(year)?;
+    let amount_cents =
+        i32::try_from(data.amount_cents).map_err(|_| (StatusCode::BAD_REQUEST, "Budget is too large"))?;
+
+    let mut active = match find_budget(&state.conn, user.id, year).await? {
+        Some(existing) => existing.into(),
+        None => budget::ActiveModel {
+            user_id: Set(user.id),
+            year: Set(year),
+            ..Default::default()
+        },
+    };
+    active.amount_cents = Set(amount_cents);
+    active.currency = Set(data.currency.to_string());
+    active.updated_at = Set(Utc::now());
+    active
+        .save(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save budget"))?;
+
+    Ok(Json(budget_for_year(&state.conn, user.id, year).await?))
+}
+
+pub async fn delete_budget(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(year): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let existing = find_budget(&state.conn, user.id, year)
+        .await?
+        .ok_or((StatusCode::NOT_FOUND, "Budget not found"))?;
+    budget::Entity::delete_by_id(existing.id)
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete budget"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+pub struct Payment {
+    credit_entry_id: i32,
+    title: String,
+    completed_on: NaiveDate,
+    amount_cents: i32,
+    currency: Currency,
+}
+
+#[derive(Serialize)]
+pub struct PlannedSpend {
+    course_id: i32,
+    title: String,
+    planned_for: Option<NaiveDate>,
+    /// The member price when the user is a member of the provider
+    amount_cents: i32,
+    currency: Currency,
+    member_price: bool,
+}
+
+/// Spending in one currency. Amounts in different currencies aren't
+/// converted, so each is totalled on its own.
+#[derive(Serialize)]
+pub struct CurrencySpend {
+    currency: Currency,
+    spent_cents: i64,
+    spent: String,
+    planned_cents: i64,
+    planned: String,
+    /// Spent to date plus planned courses
+    projected_cents: i64,
+    projected: String,
+}
+
+impl CurrencySpend {
+    fn new(currency: Currency, spent_cents: i64, planned_cents: i64) -> Self {
+        let projected_cents = spent_cents + planned_cents;
+        CurrencySpend {
+            currency,
+            spent_cents,
+            spent: currency.format(spent_cents),
+            planned_cents,
+            planned: currency.format(planned_cents),
+            projected_cents,
+            projected: currency.format(projected_cents),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BudgetResponse {
+    year: i32,
+    budget_cents: Option<i32>,
+    budget: Option<String>,
+    /// The budget's currency, or US dollars when there's no budget
+    currency: Currency,
+    spend: CurrencySpend,
+    /// Budget left once planned courses are paid for; negative when over
+    remaining_cents: Option<i64>,
+    remaining: Option<String>,
+    over_budget: bool,
+    /// Spending recorded in currencies other than the budget's
+    other_currencies: Vec<CurrencySpend>,
+    payments: Vec<Payment>,
+    planned_courses: Vec<PlannedSpend>,
+}
+
+/// What the user has paid for credit completed in `year`, and what their
+/// planned courses for the year will cost. Planned courses without a date
+/// count towards the current year.
+async fn budget_for_year<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    year: i32,
+) -> Result<BudgetResponse, (StatusCode, &'static str)> {
+    let budget = find_budget(conn, user_id, year).await?;
+    let currency = budget
+        .as_ref()
+        .and_then(|b| b.currency.parse().ok())
+        .unwrap_or_default();
+
+    let (Some(first_day), Some(last_day)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err((StatusCode::BAD_REQUEST, "Year is out of range"));
+    };
+    let payments: Vec<Payment> = credit_entry::Entity::find()
+        .filter(credit_entry::Column::UserId.eq(user_id))
+        .filter(credit_entry::Column::AmountPaidCents.is_not_null())
+        .filter(credit_entry::Column::CompletedOn.between(first_day, last_day))
+        .order_by_asc(credit_entry::Column::CompletedOn)
+        .order_by_asc(credit_entry::Column::Id)
+        .all(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .into_iter()
+        .map(|e| Payment {
+            credit_entry_id: e.id,
+            title: e.title,
+            completed_on: e.completed_on,
+            amount_cents: e.amount_paid_cents.unwrap_or_default(),
+            currency: e.currency.and_then(|c| c.parse().ok()).unwrap_or_default(),
+        })
+        .collect();
+
+    let this_year = Utc::now().year();
+    let member_of = memberships(conn, user_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let planned_courses: Vec<PlannedSpend> = crate::plan::bookmarks_for_user(conn, user_id)
+        .await?
+        .into_iter()
+        .filter(|(b, _)| b.status == BookmarkStatus::Planned.to_string())
+        .filter(|(b, _)| b.planned_for.map(|d| d.year()).unwrap_or(this_year) == year)
+        .map(|(bookmark, course)| {
+            let member = member_of.contains(&course.provider.id);
+            let amount_cents = course.price_for(member);
+            PlannedSpend {
+                course_id: course.course.id,
+                title: course.course.title.clone(),
+                planned_for: bookmark.planned_for,
+                amount_cents,
+                currency: course.currency(),
+                member_price: amount_cents < course.course.price_cents,
+            }
+        })
+        .collect();
+
+    let mut totals: BTreeMap<Currency, (i64, i64)> = BTreeMap::new();
+    for payment in &payments {
+        totals.entry(payment.currency).or_default().0 += payment.amount_cents as i64;
+    }
+    for planned in &planned_courses {
+        totals.entry(planned.currency).or_default().1 += planned.amount_cents as i64;
+    }
+    let (spent_cents, planned_cents) = totals.remove(&currency).unwrap_or_default();
+    let spend = CurrencySpend::new(currency, spent_cents, planned_cents);
+    let remaining_cents = budget
+        .as_ref()
+        .map(|b| b.amount_cents as i64 - spend.projected_cents);
+
+    Ok(BudgetResponse {
+        year,
+        budget_cents: budget.as_ref().map(|b| b.amount_cents),
+        budget: budget.as_ref().map(|b| currency.format(b.amount_cents.into())),
+        currency,
+        spend,
+        remaining_cents,
+        remaining: remaining_cents.map(|r| currency.format(r)),
+        over_budget: remaining_cents.is_some_and(|r| r < 0),
+        other_currencies: totals
+            .into_iter()
+            .map(|(currency, (spent, planned))| CurrencySpend::new(currency, spent, planned))
+            .collect(),
+        payments,
+        planned_courses,
+    })
+}
+
+pub async fn get_budget(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(year): Path<i32>,
+) -> Result<Json<BudgetResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    check_year(year)?;
+
+    Ok(Json(budget_for_year(&state.conn, user.id, year).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use entity::course;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn budget_counts_member_prices_and_keeps_currencies_apart() {
+        let state = test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (user, cookies) = test_support::sign_in(&conn, "ann").await;
+        let this_year = Utc::now().year();
+
+        let courses = course::Entity::find().all(&conn).await.unwrap();
+        let member_course = courses[0].clone();
+        let later_course = courses
+            .iter()
+            .find(|c| c.provider_id != member_course.provider_id)
+            .unwrap()
+            .clone();
+        let prices = [(&member_course, 20000, Some(15000)), (&later_course, 10000, None)];
+        for (course, price, member_price) in prices {
+            let mut active: course::ActiveModel = course.clone().into();
+            active.price_cents = Set(price);
+            active.member_price_cents = Set(member_price);
+            active.currency = Set("USD".to_string());
+            active.update(&conn).await.unwrap();
+        }
+        add_membership(State(state.clone()), cookies.clone(), Path(member_course.provider_id))
+            .await
+            .unwrap();
+
+        // Undated, so it counts towards the current year; the other is planned for next year
+        for (course_id, planned_for) in [
+            (member_course.id, None),
+            (later_course.id, NaiveDate::from_ymd_opt(this_year + 1, 3, 1)),
+        ] {
+            let _ = crate::plan::save_bookmark(
+                State(state.clone()),
+                cookies.clone(),
+                Path(course_id),
+                Json(serde_json::from_value(json!({"status": "planned", "planned_for": planned_for})).unwrap()),
+            )
+            .await
+            .unwrap();
+        }
+
+        for (amount, currency) in [(3000, "USD"), (5000, "EUR")] {
+            let request = serde_json::from_value(json!({
+                "title": "Paid course",
+                "completed_on": NaiveDate::from_ymd_opt(this_year, 1, 15),
+                "categories": [{"category": "general", "credits": 1}],
+                "amount_paid_cents": amount,
+                "currency": currency,
+            }))
+            .unwrap();
+            crate::ledger::insert_entry(&conn, user.id, request).await.unwrap();
+        }
+
+        let resp = save_budget(
+            State(state.clone()),
+            cookies.clone(),
+            Path(this_year),
+            Json(serde_json::from_value(json!({"amount_cents": 30000, "currency": "USD"})).unwrap()),
+        )
+        .await
+        .unwrap();
+        let body = serde_json::to_value(&resp.0).unwrap();
+
+        assert_eq!(body["planned_courses"].as_array().unwrap().len(), 1);
+        assert_eq!(body["planned_courses"][0]["course_id"], member_course.id);
+        assert_eq!(body["planned_courses"][0]["planned_for"], json!(null));
+        assert_eq!(body["planned_courses"][0]["amount_cents"], 15000);
+        assert_eq!(body["planned_courses"][0]["member_price"], true);
+
+        assert_eq!(body["spend"]["spent_cents"], 3000);
+        assert_eq!(body["spend"]["planned_cents"], 15000);
+        assert_eq!(body["remaining_cents"], 12000);
+        assert_eq!(body["over_budget"], false);
+
+        let others = body["other_currencies"].as_array().unwrap();
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0]["currency"], "EUR");
+        assert_eq!(others[0]["spent_cents"], 5000);
+        assert_eq!(others[0]["planned_cents"], 0);
+    }
+}
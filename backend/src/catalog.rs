@@ -0,0 +1,554 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use entity::{
+    Credits, catalog_audit, course, course_approval, course_approval_credit, course_format, course_topic,
+    provider, state,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::category::CreditCategory;
+use crate::currency::Currency;
+use crate::ledger::CategoryCredits;
+use crate::reviews::Rating;
+
+/// How a course is delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
+pub enum CourseFormat {
+    Online,
+    SelfPaced,
+    LiveWebinar,
+    InPerson,
+}
+
+impl CourseFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            CourseFormat::Online => "Online",
+            CourseFormat::SelfPaced => "Self-Paced",
+            CourseFormat::LiveWebinar => "Live Webinar",
+            CourseFormat::InPerson => "In Person",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseCourseFormatError;
+
+impl fmt::Display for ParseCourseFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid course format")
+    }
+}
+
+impl std::error::Error for ParseCourseFormatError {}
+
+impl FromStr for CourseFormat {
+    type Err = ParseCourseFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "online" => Ok(CourseFormat::Online),
+            "self_paced" => Ok(CourseFormat::SelfPaced),
+            "live_webinar" | "webinar" => Ok(CourseFormat::LiveWebinar),
+            "in_person" | "live" => Ok(CourseFormat::InPerson),
+            _ => Err(ParseCourseFormatError),
+        }
+    }
+}
+
+impl fmt::Display for CourseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CourseFormat::Online => "online",
+            CourseFormat::SelfPaced => "self_paced",
+            CourseFormat::LiveWebinar => "live_webinar",
+            CourseFormat::InPerson => "in_person",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Topics courses can be filed under; anything else is rejected so the
+/// catalog's filters stay consistent.
+pub const KNOWN_TOPICS: &[&str] = &[
+    "Accounting",
+    "Auditing",
+    "Bankruptcy Law",
+    "Business Law",
+    "Civil Litigation",
+    "Contract Law",
+    "Criminal Law",
+    "Elimination of Bias",
+    "Employment Law",
+    "Engineering",
+    "Environmental Law",
+    "Estate Planning",
+    "Ethics",
+    "Family Law",
+    "Healthcare Law",
+    "Immigration",
+    "IP Law",
+    "Legal Tech",
+    "Legal Writing",
+    "Personal Injury",
+    "Privacy Law",
+    "Real Estate Law",
+    "Tax Law",
+    "Wellness",
+];
+
+/// The known spelling of a topic, matched case-insensitively.
+pub fn known_topic(topic: &str) -> Option<&'static str> {
+    KNOWN_TOPICS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(topic.trim()))
+        .copied()
+}
+
+/// The credit category a topic counts towards. Topics without a specialty
+/// category of their own count as general credit.
+pub fn topic_category(topic: &str) -> CreditCategory {
+    match topic {
+        "Ethics" => CreditCategory::Ethics,
+        "Elimination of Bias" => CreditCategory::EliminationOfBias,
+        "Legal Tech" => CreditCategory::Technology,
+        "Legal Writing" => CreditCategory::Skills,
+        "Wellness" => CreditCategory::Wellness,
+        _ => CreditCategory::General,
+    }
+}
+
+/// A jurisdiction that grants credit for a course.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Approval {
+    pub state_code: String,
+    pub profession: String,
+    /// Activity number the jurisdiction assigned, quoted on reports
+    #[serde(default)]
+    pub approval_number: Option<String>,
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+    #[serde(default)]
+    pub valid_until: Option<NaiveDate>,
+    /// Credits granted per category; empty when they follow the course's topics
+    #[serde(default)]
+    pub credits: Vec<CategoryCredits>,
+}
+
+impl Approval {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|from| date >= from) && self.valid_until.is_none_or(|until| date <= until)
+    }
+}
+
+/// An approval as it's stored, with its state resolved to an id.
+#[derive(Clone, PartialEq)]
+pub struct ApprovalRecord {
+    pub state_id: i32,
+    pub profession: String,
+    pub approval_number: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_until: Option<NaiveDate>,
+    pub credits: Vec<CategoryCredits>,
+}
+
+impl ApprovalRecord {
+    /// Whether both approvals are for the same jurisdiction and in force on a
+    /// common day.
+    pub fn overlaps(&self, other: &ApprovalRecord) -> bool {
+        self.state_id == other.state_id
+            && self.profession == other.profession
+            && self.valid_from.is_none_or(|from| other.valid_until.is_none_or(|until| from <= until))
+            && self.valid_until.is_none_or(|until| other.valid_from.is_none_or(|from| from <= until))
+    }
+}
+
+/// A course with its provider and the rows that describe it.
+#[derive(Clone)]
+pub struct CatalogCourse {
+    pub course: course::Model,
+    pub provider: provider::Model,
+    pub topics: Vec<String>,
+    pub formats: Vec<CourseFormat>,
+    pub approvals: Vec<Approval>,
+    pub rating: Option<Rating>,
+    pub provider_rating: Option<Rating>,
+}
+
+impl CatalogCourse {
+    pub fn currency(&self) -> Currency {
+        self.course.currency.parse().unwrap_or_default()
+    }
+
+    /// Price as shown to users, e.g. `Free` or `$99`
+    pub fn price_label(&self) -> String {
+        price_label(self.course.price_cents, self.currency())
+    }
+
+    pub fn member_price_label(&self) -> Option<String> {
+        self.course
+            .member_price_cents
+            .map(|cents| price_label(cents, self.currency()))
+    }
+
+    /// What someone pays for the course, given whether they're a member of
+    /// its provider.
+    pub fn price_for(&self, member: bool) -> i32 {
+        match self.course.member_price_cents {
+            Some(cents) if member => cents.min(self.course.price_cents),
+            _ => self.course.price_cents,
+        }
+    }
+
+    pub fn format_label(&self) -> String {
+        self.formats
+            .iter()
+            .map(|f| f.label())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn topic_label(&self) -> String {
+        self.topics.join(", ")
+    }
+
+    /// The approval that grants credit in a jurisdiction for a course taken
+    /// on `date`.
+    pub fn approval_for(&self, state_code: &str, profession: &str, date: NaiveDate) -> Option<&Approval> {
+        self.approvals
+            .iter()
+            .find(|a| a.state_code == state_code && a.profession == profession && a.is_valid_on(date))
+    }
+
+    /// The day credit for the course would be earned: its scheduled date, or
+    /// `today` for on-demand courses.
+    pub fn taken_on(&self, today: NaiveDate) -> NaiveDate {
+        self.course.starts_on.unwrap_or(today)
+    }
+
+    /// How an approval awards the course's credits, falling back to the
+    /// catalog's estimate when it doesn't break them down.
+    pub fn credit_split_for(&self, approval: Option<&Approval>) -> Vec<(CreditCategory, Credits)> {
+        match approval {
+            Some(a) if !a.credits.is_empty() => a.credits.iter().map(|c| (c.category, c.credits)).collect(),
+            _ => self.credit_split(),
+        }
+    }
+
+    /// How the course's credits are expected to be awarded: all of them
+    /// towards its first specialty topic, or as general credit.
+    pub fn credit_split(&self) -> Vec<(CreditCategory, Credits)> {
+        let category = self
+            .topics
+            .iter()
+            .map(|t| topic_category(t))
+            .find(|c| *c != CreditCategory::General)
+            .unwrap_or_default();
+        vec![(category, self.course.credits)]
+    }
+}
+
+fn price_label(cents: i32, currency: Currency) -> String {
+    match cents {
+        0 => "Free".to_string(),
+        cents => currency.format(cents.into()),
+    }
+}
+
+#[derive(Serialize)]
+pub struct CourseResponse {
+    pub id: i32,
+    pub provider_id: i32,
+    pub provider: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub hours: Credits,
+    pub duration_minutes: i32,
+    pub price_cents: i32,
+    pub price: String,
+    pub currency: Currency,
+    /// Price for members of the provider, if they get a discount
+    pub member_price_cents: Option<i32>,
+    pub member_price: Option<String>,
+    pub url: String,
+    pub topics: Vec<String>,
+    pub formats: Vec<CourseFormat>,
+    pub approvals: Vec<Approval>,
+    pub starts_on: Option<NaiveDate>,
+    /// `manual` for courses entered by admins, `feed` for ingested ones
+    pub source: String,
+    pub rating: Option<Rating>,
+    pub provider_rating: Option<Rating>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl From<&CatalogCourse> for CourseResponse {
+    fn from(c: &CatalogCourse) -> Self {
+        CourseResponse {
+            id: c.course.id,
+            provider_id: c.provider.id,
+            provider: c.provider.name.clone(),
+            title: c.course.title.clone(),
+            summary: c.course.summary.clone(),
+            hours: c.course.credits,
+            duration_minutes: c.course.duration_minutes,
+            price_cents: c.course.price_cents,
+            price: c.price_label(),
+            currency: c.currency(),
+            member_price_cents: c.course.member_price_cents,
+            member_price: c.member_price_label(),
+            url: c.course.url.clone(),
+            topics: c.topics.clone(),
+            formats: c.formats.clone(),
+            approvals: c.approvals.clone(),
+            starts_on: c.course.starts_on,
+            source: c.course.source.clone(),
+            rating: c.rating,
+            provider_rating: c.provider_rating,
+            retired_at: c.course.retired_at,
+        }
+    }
+}
+
+/// Replaces a course's topics, formats and approvals.
+pub async fn replace_course_details<C: ConnectionTrait>(
+    conn: &C,
+    course_id: i32,
+    topics: &[String],
+    formats: &[CourseFormat],
+    approvals: &[ApprovalRecord],
+) -> Result<(), DbErr> {
+    course_topic::Entity::delete_many()
+        .filter(course_topic::Column::CourseId.eq(course_id))
+        .exec(conn)
+        .await?;
+    course_format::Entity::delete_many()
+        .filter(course_format::Column::CourseId.eq(course_id))
+        .exec(conn)
+        .await?;
+    let existing = course_approval::Entity::find()
+        .filter(course_approval::Column::CourseId.eq(course_id))
+        .all(conn)
+        .await?;
+    course_approval_credit::Entity::delete_many()
+        .filter(course_approval_credit::Column::CourseApprovalId.is_in(existing.iter().map(|a| a.id)))
+        .exec(conn)
+        .await?;
+    course_approval::Entity::delete_many()
+        .filter(course_approval::Column::CourseId.eq(course_id))
+        .exec(conn)
+        .await?;
+
+    for topic in topics {
+        course_topic::ActiveModel {
+            course_id: Set(course_id),
+            topic: Set(topic.clone()),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+    }
+    for format in formats {
+        course_format::ActiveModel {
+            course_id: Set(course_id),
+            format: Set(format.to_string()),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+    }
+    for approval in approvals {
+        let saved = course_approval::ActiveModel {
+            course_id: Set(course_id),
+            state_id: Set(approval.state_id),
+            profession: Set(approval.profession.clone()),
+            approval_number: Set(approval.approval_number.clone()),
+            valid_from: Set(approval.valid_from),
+            valid_until: Set(approval.valid_until),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+        for credit in &approval.credits {
+            course_approval_credit::ActiveModel {
+                course_approval_id: Set(saved.id),
+                category: Set(credit.category.to_string()),
+                credits: Set(credit.credits),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Records a change to the catalog. `actor_id` is absent for automated changes.
+pub async fn record_audit<C: ConnectionTrait, T: Serialize>(
+    conn: &C,
+    actor_id: Option<i32>,
+    entity: &str,
+    entity_id: i32,
+    action: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), DbErr> {
+    catalog_audit::ActiveModel {
+        actor_id: Set(actor_id),
+        entity: Set(entity.to_string()),
+        entity_id: Set(entity_id),
+        action: Set(action.to_string()),
+        before: Set(before.and_then(|b| serde_json::to_value(b).ok())),
+        after: Set(after.and_then(|a| serde_json::to_value(a).ok())),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+    Ok(())
+}
+
+/// Loads the catalog in the order courses were added. Retired courses, and
+/// courses from retired providers, are only included when asked for.
+pub async fn load_courses<C: ConnectionTrait>(
+    conn: &C,
+    include_retired: bool,
+) -> Result<Vec<CatalogCourse>, DbErr> {
+    let mut query = course::Entity::find().find_also_related(provider::Entity);
+    if !include_retired {
+        query = query
+            .filter(course::Column::RetiredAt.is_null())
+            .filter(provider::Column::RetiredAt.is_null());
+    }
+    let courses = query.order_by_asc(course::Column::Id).all(conn).await?;
+    let ratings = crate::reviews::ratings(conn).await?;
+    with_details(conn, courses, ratings).await
+}
+
+/// Loads every course a provider has listed, retired ones included.
+pub async fn load_provider_courses<C: ConnectionTrait>(
+    conn: &C,
+    provider_id: i32,
+) -> Result<Vec<CatalogCourse>, DbErr> {
+    let courses = course::Entity::find()
+        .find_also_related(provider::Entity)
+        .filter(course::Column::ProviderId.eq(provider_id))
+        .order_by_asc(course::Column::Id)
+        .all(conn)
+        .await?;
+    let ratings = crate::reviews::provider_ratings(conn, &[provider_id]).await?;
+    with_details(conn, courses, ratings).await
+}
+
+/// Loads the courses with these ids in catalog order, retired ones included.
+pub async fn load_courses_by_id<C: ConnectionTrait>(conn: &C, ids: &[i32]) -> Result<Vec<CatalogCourse>, DbErr> {
+    let courses = course::Entity::find()
+        .find_also_related(provider::Entity)
+        .filter(course::Column::Id.is_in(ids.iter().copied()))
+        .order_by_asc(course::Column::Id)
+        .all(conn)
+        .await?;
+    let mut provider_ids: Vec<i32> = courses.iter().map(|(c, _)| c.provider_id).collect();
+    provider_ids.sort();
+    provider_ids.dedup();
+    let ratings = crate::reviews::provider_ratings(conn, &provider_ids).await?;
+    with_details(conn, courses, ratings).await
+}
+
+/// Loads a single course by id, whether or not it has been retired.
+pub async fn load_course<C: ConnectionTrait>(conn: &C, id: i32) -> Result<Option<CatalogCourse>, DbErr> {
+    let Some(row) = course::Entity::find_by_id(id)
+        .find_also_related(provider::Entity)
+        .one(conn)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let ratings = crate::reviews::provider_ratings(conn, &[row.0.provider_id]).await?;
+    Ok(with_details(conn, vec![row], ratings).await?.pop())
+}
+
+/// Attaches topics, formats, approvals and ratings to course rows.
+async fn with_details<C: ConnectionTrait>(
+    conn: &C,
+    courses: Vec<(course::Model, Option<provider::Model>)>,
+    ratings: crate::reviews::Ratings,
+) -> Result<Vec<CatalogCourse>, DbErr> {
+    let ids: Vec<i32> = courses.iter().map(|(c, _)| c.id).collect();
+
+    let mut topics: HashMap<i32, Vec<String>> = HashMap::new();
+    for topic in course_topic::Entity::find()
+        .filter(course_topic::Column::CourseId.is_in(ids.clone()))
+        .order_by_asc(course_topic::Column::Id)
+        .all(conn)
+        .await?
+    {
+        topics.entry(topic.course_id).or_default().push(topic.topic);
+    }
+
+    let mut formats: HashMap<i32, Vec<CourseFormat>> = HashMap::new();
+    for format in course_format::Entity::find()
+        .filter(course_format::Column::CourseId.is_in(ids.clone()))
+        .order_by_asc(course_format::Column::Id)
+        .all(conn)
+        .await?
+    {
+        if let Ok(parsed) = format.format.parse() {
+            formats.entry(format.course_id).or_default().push(parsed);
+        }
+    }
+
+    let approval_rows = course_approval::Entity::find()
+        .find_also_related(state::Entity)
+        .filter(course_approval::Column::CourseId.is_in(ids))
+        .order_by_asc(course_approval::Column::Id)
+        .all(conn)
+        .await?;
+    let mut approval_credits: HashMap<i32, Vec<CategoryCredits>> = HashMap::new();
+    for credit in course_approval_credit::Entity::find()
+        .filter(course_approval_credit::Column::CourseApprovalId.is_in(approval_rows.iter().map(|(a, _)| a.id)))
+        .order_by_asc(course_approval_credit::Column::Id)
+        .all(conn)
+        .await?
+    {
+        approval_credits.entry(credit.course_approval_id).or_default().push(CategoryCredits {
+            category: credit.category.parse().unwrap_or_default(),
+            credits: credit.credits,
+        });
+    }
+
+    let mut approvals: HashMap<i32, Vec<Approval>> = HashMap::new();
+    for (approval, state) in approval_rows {
+        let Some(state) = state else { continue };
+        approvals.entry(approval.course_id).or_default().push(Approval {
+            state_code: state.name,
+            profession: approval.profession,
+            approval_number: approval.approval_number,
+            valid_from: approval.valid_from,
+            valid_until: approval.valid_until,
+            credits: approval_credits.remove(&approval.id).unwrap_or_default(),
+        });
+    }
+
+    Ok(courses
+        .into_iter()
+        .filter_map(|(course, provider)| {
+            Some(CatalogCourse {
+                topics: topics.remove(&course.id).unwrap_or_default(),
+                formats: formats.remove(&course.id).unwrap_or_default(),
+                approvals: approvals.remove(&course.id).unwrap_or_default(),
+                rating: ratings.by_course.get(&course.id).copied(),
+                provider_rating: ratings.by_provider.get(&course.provider_id).copied(),
+                provider: provider?,
+                course,
+            })
+        })
+        .collect())
+}
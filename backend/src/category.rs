@@ -0,0 +1,73 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// The subject-matter bucket a credit counts towards.
+///
+/// Most jurisdictions only care about a handful of specialty categories on top
+/// of general credit; anything else is reported as `general`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay)]
+pub enum CreditCategory {
+    #[default]
+    General,
+    Ethics,
+    EliminationOfBias,
+    Technology,
+    Skills,
+    Wellness,
+}
+
+impl CreditCategory {
+    /// Name shown on reports and forms
+    pub fn label(self) -> &'static str {
+        match self {
+            CreditCategory::General => "General",
+            CreditCategory::Ethics => "Ethics",
+            CreditCategory::EliminationOfBias => "Elimination of Bias",
+            CreditCategory::Technology => "Technology",
+            CreditCategory::Skills => "Skills",
+            CreditCategory::Wellness => "Wellness",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseCategoryError;
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid credit category")
+    }
+}
+
+impl std::error::Error for ParseCategoryError {}
+
+impl FromStr for CreditCategory {
+    type Err = ParseCategoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "general" => Ok(CreditCategory::General),
+            "ethics" => Ok(CreditCategory::Ethics),
+            "elimination_of_bias" => Ok(CreditCategory::EliminationOfBias),
+            "technology" => Ok(CreditCategory::Technology),
+            "skills" => Ok(CreditCategory::Skills),
+            "wellness" => Ok(CreditCategory::Wellness),
+            _ => Err(ParseCategoryError),
+        }
+    }
+}
+
+impl fmt::Display for CreditCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CreditCategory::General => "general",
+            CreditCategory::Ethics => "ethics",
+            CreditCategory::EliminationOfBias => "elimination_of_bias",
+            CreditCategory::Technology => "technology",
+            CreditCategory::Skills => "skills",
+            CreditCategory::Wellness => "wellness",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,420 @@
+use chrono::NaiveDate;
+use entity::Credits;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+use crate::category::CreditCategory;
+use crate::ledger::CategoryCredits;
+
+/// A credit entry pre-filled from a certificate, for the user to confirm.
+///
+/// Mirrors `CreditEntryRequest` so the client can post it back once reviewed.
+/// Anything the rules couldn't find is left empty and listed in `missing`.
+#[derive(Serialize, Default)]
+pub struct ProposedEntry {
+    pub title: Option<String>,
+    pub provider: Option<String>,
+    pub completed_on: Option<NaiveDate>,
+    pub minutes: Option<u32>,
+    pub approval_number: Option<String>,
+    pub categories: Vec<CategoryCredits>,
+    /// Which provider's rules matched, or `generic`
+    pub matched_rules: &'static str,
+    pub missing: Vec<&'static str>,
+}
+
+/// Patterns for pulling each field out of a certificate's text. Each must
+/// capture the value in its first group.
+struct FieldPatterns {
+    title: &'static [&'static str],
+    provider: &'static [&'static str],
+    date: &'static [&'static str],
+    total_credits: &'static [&'static str],
+    approval_number: &'static [&'static str],
+}
+
+struct ProviderRules {
+    name: &'static str,
+    /// Text that identifies a certificate as coming from this provider
+    detect: &'static str,
+    fields: FieldPatterns,
+}
+
+/// `FieldPatterns` compiled once, in the same order
+struct FieldRegexes {
+    title: Vec<Regex>,
+    provider: Vec<Regex>,
+    date: Vec<Regex>,
+    total_credits: Vec<Regex>,
+    approval_number: Vec<Regex>,
+}
+
+impl FieldRegexes {
+    fn compile(fields: &FieldPatterns) -> Self {
+        FieldRegexes {
+            title: compile(fields.title),
+            provider: compile(fields.provider),
+            date: compile(fields.date),
+            total_credits: compile(fields.total_credits),
+            approval_number: compile(fields.approval_number),
+        }
+    }
+}
+
+struct CompiledProvider {
+    name: &'static str,
+    detect: Regex,
+    fields: FieldRegexes,
+}
+
+const GENERIC: FieldPatterns = FieldPatterns {
+    title: &[
+        r"(?im)^\s*(?:course|program|activity|seminar|session)\s*(?:title|name)?\s*:\s*(.+)$",
+        r#"(?i)(?:attended|completed|participated in)\s+(?:the\s+)?(?:course|program)?\s*["“]([^"”\n]+)["”]"#,
+    ],
+    provider: &[r"(?im)^\s*(?:provider|sponsor(?:ed by)?|presented by|accredited provider)\s*:\s*(.+)$"],
+    date: &[
+        r"(?im)^\s*(?:date(?:\s+of\s+(?:completion|attendance|program|activity))?|completed(?:\s+on)?|completion date)\s*:\s*(.+)$",
+        r"(?i)\bon\s+((?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+\d{1,2},\s+\d{4})",
+    ],
+    total_credits: &[
+        r"(?im)^\s*total\s+(?:cle|cpe|ce|mcle)?\s*(?:credits?|hours?)(?:\s+(?:earned|awarded))?\s*:\s*(\d+(?:\.\d{1,2})?)",
+        r"(?im)^\s*(?:cle|cpe|ce|mcle|pdh|lu)?\s*(?:credits?|hours?)(?:\s+(?:earned|awarded))?\s*:\s*(\d+(?:\.\d{1,2})?)",
+        r"(?i)(\d+(?:\.\d{1,2})?)\s+(?:total\s+)?(?:cle|cpe|mcle)\s+(?:credits?|hours?)",
+    ],
+    approval_number: &[
+        r"(?i)(?:approval|activity|course|accreditation)\s*(?:number|no\.?|#|code|id)\s*:?\s*([A-Z0-9][A-Z0-9-]{2,})",
+    ],
+};
+
+/// Rules for providers whose certificate layout we know. Fields a provider
+/// doesn't override fall back to the generic patterns.
+const PROVIDERS: &[ProviderRules] = &[
+    ProviderRules {
+        name: "Practising Law Institute",
+        detect: r"(?i)practising law institute|\bPLI\b",
+        fields: FieldPatterns {
+            title: &[r"(?im)^\s*program(?:\s+title)?\s*:\s*(.+)$"],
+            provider: &[],
+            date: &[r"(?im)^\s*date\s+(?:viewed|attended)\s*:\s*(.+)$"],
+            total_credits: &[r"(?im)^\s*total\s+credit\s+hours\s*:\s*(\d+(?:\.\d{1,2})?)"],
+            approval_number: &[r"(?i)program\s+number\s*:?\s*([A-Z0-9-]{3,})"],
+        },
+    },
+    ProviderRules {
+        name: "American Bar Association",
+        detect: r"(?i)american bar association|\bABA\s+CLE\b",
+        fields: FieldPatterns {
+            title: &[r"(?im)^\s*(?:program|event)\s*:\s*(.+)$"],
+            provider: &[],
+            date: &[r"(?im)^\s*(?:program|event)\s+date\s*:\s*(.+)$"],
+            total_credits: &[r"(?im)^\s*(?:credits?\s+claimed|total\s+credits?)\s*:\s*(\d+(?:\.\d{1,2})?)"],
+            approval_number: &[r"(?i)event\s+code\s*:?\s*([A-Z0-9-]{3,})"],
+        },
+    },
+    ProviderRules {
+        name: "National Business Institute",
+        detect: r"(?i)national business institute|\bNBI\b",
+        fields: FieldPatterns {
+            title: &[r"(?im)^\s*seminar\s*:\s*(.+)$"],
+            provider: &[],
+            date: &[r"(?im)^\s*seminar\s+date\s*:\s*(.+)$"],
+            total_credits: &[r"(?im)^\s*(?:total\s+)?(?:substantive|general)?\s*hours\s*:\s*(\d+(?:\.\d{1,2})?)"],
+            approval_number: &[r"(?i)course\s+(?:id|code)\s*:?\s*([A-Z0-9-]{3,})"],
+        },
+    },
+];
+
+/// Category breakdown lines, e.g. "Ethics and Professionalism: 1.0"
+const CATEGORY_PATTERNS: &[(CreditCategory, &str)] = &[
+    (CreditCategory::Ethics, r"(?im)^\s*(?:legal\s+)?(?:ethics|professional\s+responsibility)[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+    (CreditCategory::EliminationOfBias, r"(?im)^\s*(?:elimination\s+of\s+bias|diversity|bias)[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+    (CreditCategory::Technology, r"(?im)^\s*(?:technology|cybersecurity)[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+    (CreditCategory::Skills, r"(?im)^\s*skills[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+    (CreditCategory::Wellness, r"(?im)^\s*(?:wellness|mental\s+health|competence|substance\s+(?:ab)?use)[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+    (CreditCategory::General, r"(?im)^\s*(?:general|areas\s+of\s+professional\s+practice)[^:\n]*:\s*(\d+(?:\.\d{1,2})?)"),
+];
+
+/// Minutes of instruction, only where a label says that's what the number is
+const MINUTES_PATTERNS: &[&str] = &[
+    r"(?im)^\s*(?:duration|length|program\s+length|running\s+time|instructional\s+time)\s*:\s*(\d{1,4})\s*(?:instructional\s+)?min(?:ute)?s?\b",
+    r"(?im)^\s*(?:total\s+)?(?:instructional\s+)?minutes(?:\s+of\s+instruction)?\s*:\s*(\d{1,4})\b",
+];
+
+/// Panics on an invalid pattern, so a typo fails the first parse instead of
+/// quietly never matching
+fn compile_one(pattern: &str) -> Regex {
+    Regex::new(pattern).unwrap_or_else(|e| panic!("Invalid certificate pattern {}: {}", pattern, e))
+}
+
+fn compile(patterns: &[&str]) -> Vec<Regex> {
+    patterns.iter().map(|p| compile_one(p)).collect()
+}
+
+static GENERIC_RULES: LazyLock<FieldRegexes> = LazyLock::new(|| FieldRegexes::compile(&GENERIC));
+
+static PROVIDER_RULES: LazyLock<Vec<CompiledProvider>> = LazyLock::new(|| {
+    PROVIDERS
+        .iter()
+        .map(|p| CompiledProvider {
+            name: p.name,
+            detect: compile_one(p.detect),
+            fields: FieldRegexes::compile(&p.fields),
+        })
+        .collect()
+});
+
+static CATEGORY_RULES: LazyLock<Vec<(CreditCategory, Regex)>> = LazyLock::new(|| {
+    CATEGORY_PATTERNS
+        .iter()
+        .map(|(category, pattern)| (*category, compile_one(pattern)))
+        .collect()
+});
+
+static MINUTES: LazyLock<Vec<Regex>> = LazyLock::new(|| compile(MINUTES_PATTERNS));
+
+const DATE_FORMATS: &[&str] = &["%B %d, %Y", "%b %d, %Y", "%b. %d, %Y", "%m/%d/%Y", "%Y-%m-%d", "%d %B %Y", "%m-%d-%Y"];
+
+fn first_capture(patterns: &[Regex], text: &str) -> Option<String> {
+    patterns.iter().find_map(|re| {
+        re.captures(text)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().trim().trim_end_matches(['.', ',']).to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// Parses the date layouts commonly printed on certificates and transcripts
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    // Dates are often followed by other text on the same line
+    let value = value.trim();
+    DATE_FORMATS.iter().find_map(|format| {
+        NaiveDate::parse_and_remainder(value, format)
+            .ok()
+            // A digit straight after means the format cut a number short,
+            // e.g. `%Y-%m-%d` reading 03-04-2025 as the year 3
+            .filter(|(_, rest)| !rest.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|(date, _)| date)
+    })
+}
+
+/// Applies the provider rules (falling back to generic ones) to the text of a certificate.
+pub fn propose_entry(text: &str) -> ProposedEntry {
+    let provider = PROVIDER_RULES.iter().find(|p| p.detect.is_match(text));
+
+    let field = |pick: fn(&FieldRegexes) -> &[Regex]| {
+        provider
+            .and_then(|p| first_capture(pick(&p.fields), text))
+            .or_else(|| first_capture(pick(&GENERIC_RULES), text))
+    };
+
+    let mut proposal = ProposedEntry {
+        title: field(|f| &f.title),
+        provider: field(|f| &f.provider).or_else(|| provider.map(|p| p.name.to_string())),
+        completed_on: field(|f| &f.date).and_then(|d| parse_date(&d)),
+        minutes: first_capture(&MINUTES, text).and_then(|m| m.parse().ok()),
+        approval_number: field(|f| &f.approval_number),
+        matched_rules: provider.map(|p| p.name).unwrap_or("generic"),
+        ..Default::default()
+    };
+
+    for (category, pattern) in CATEGORY_RULES.iter() {
+        let credits = first_capture(std::slice::from_ref(pattern), text).and_then(|c| c.parse::<Credits>().ok());
+        if let Some(credits) = credits.filter(|c| *c > Credits::ZERO) {
+            proposal.categories.push(CategoryCredits {
+                category: *category,
+                credits,
+            });
+        }
+    }
+
+    // Whatever part of the total isn't claimed by a specialty category is general credit
+    let total = field(|f| &f.total_credits).and_then(|c| c.parse::<Credits>().ok());
+    if let Some(total) = total {
+        let listed: Credits = proposal.categories.iter().map(|c| c.credits).sum();
+        let has_general = proposal
+            .categories
+            .iter()
+            .any(|c| c.category == CreditCategory::General);
+        if total > listed && !has_general {
+            proposal.categories.push(CategoryCredits {
+                category: CreditCategory::General,
+                credits: total - listed,
+            });
+        }
+    }
+
+    if proposal.title.is_none() {
+        proposal.missing.push("title");
+    }
+    if proposal.provider.is_none() {
+        proposal.missing.push("provider");
+    }
+    if proposal.completed_on.is_none() {
+        proposal.missing.push("completed_on");
+    }
+    if proposal.categories.is_empty() {
+        proposal.missing.push("categories");
+    }
+    if proposal.approval_number.is_none() {
+        proposal.missing.push("approval_number");
+    }
+
+    proposal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(proposal: &ProposedEntry) -> Vec<(CreditCategory, Credits)> {
+        proposal.categories.iter().map(|c| (c.category, c.credits)).collect()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn patterns_compile() {
+        assert_eq!(PROVIDER_RULES.len(), PROVIDERS.len());
+        assert_eq!(CATEGORY_RULES.len(), CATEGORY_PATTERNS.len());
+        assert_eq!(GENERIC_RULES.title.len(), GENERIC.title.len());
+        assert_eq!(MINUTES.len(), MINUTES_PATTERNS.len());
+    }
+
+    #[test]
+    fn reads_a_pli_certificate() {
+        let text = "Practising Law Institute\n\
+                    Certificate of Attendance\n\
+                    Attendee: Jane Doe\n\
+                    Program Title: Cybersecurity for Lawyers 2025\n\
+                    Program Number: 412345\n\
+                    Date Viewed: March 4, 2025\n\
+                    Total Credit Hours: 2.0\n\
+                    Ethics and Professionalism: 1.0\n\
+                    Duration: 120 minutes\n";
+        let proposal = propose_entry(text);
+
+        assert_eq!(proposal.matched_rules, "Practising Law Institute");
+        assert_eq!(proposal.title.as_deref(), Some("Cybersecurity for Lawyers 2025"));
+        assert_eq!(proposal.provider.as_deref(), Some("Practising Law Institute"));
+        assert_eq!(proposal.completed_on, date(2025, 3, 4));
+        assert_eq!(proposal.approval_number.as_deref(), Some("412345"));
+        assert_eq!(proposal.minutes, Some(120));
+        assert_eq!(
+            categories(&proposal),
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(1)),
+                (CreditCategory::General, Credits::from_whole(1)),
+            ]
+        );
+        assert!(proposal.missing.is_empty());
+    }
+
+    #[test]
+    fn reads_an_aba_certificate() {
+        let text = "AMERICAN BAR ASSOCIATION\n\
+                    Certificate of Attendance\n\
+                    Event: Antitrust Law Spring Meeting\n\
+                    Event Date: 04/15/2025\n\
+                    Event Code: CLE25-0415\n\
+                    Credits Claimed: 3.5\n\
+                    Elimination of Bias: 1\n";
+        let proposal = propose_entry(text);
+
+        assert_eq!(proposal.matched_rules, "American Bar Association");
+        assert_eq!(proposal.title.as_deref(), Some("Antitrust Law Spring Meeting"));
+        assert_eq!(proposal.completed_on, date(2025, 4, 15));
+        assert_eq!(proposal.approval_number.as_deref(), Some("CLE25-0415"));
+        assert_eq!(proposal.minutes, None);
+        assert_eq!(
+            categories(&proposal),
+            vec![
+                (CreditCategory::EliminationOfBias, Credits::from_whole(1)),
+                (CreditCategory::General, Credits::from_hundredths(250)),
+            ]
+        );
+        assert!(proposal.missing.is_empty());
+    }
+
+    #[test]
+    fn reads_an_nbi_certificate() {
+        let text = "National Business Institute\n\
+                    Seminar: Real Estate Title Issues\n\
+                    Seminar Date: 2025-06-10\n\
+                    Course ID: NBI-98765\n\
+                    Substantive Hours: 6.0\n\
+                    Ethics: 1.0\n\
+                    Minutes of instruction: 360\n";
+        let proposal = propose_entry(text);
+
+        assert_eq!(proposal.matched_rules, "National Business Institute");
+        assert_eq!(proposal.title.as_deref(), Some("Real Estate Title Issues"));
+        assert_eq!(proposal.completed_on, date(2025, 6, 10));
+        assert_eq!(proposal.approval_number.as_deref(), Some("NBI-98765"));
+        assert_eq!(proposal.minutes, Some(360));
+        assert_eq!(
+            categories(&proposal),
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(1)),
+                (CreditCategory::General, Credits::from_whole(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_generic_rules() {
+        let text = "Certificate of Completion\n\
+                    This certifies that John Smith completed the course \"Trust Accounting Basics\"\n\
+                    Provider: Lawline\n\
+                    Date of Completion: January 9, 2025\n\
+                    Total CLE Credits: 1.5\n\
+                    Activity Number: ACT-2025-77\n";
+        let proposal = propose_entry(text);
+
+        assert_eq!(proposal.matched_rules, "generic");
+        assert_eq!(proposal.title.as_deref(), Some("Trust Accounting Basics"));
+        assert_eq!(proposal.provider.as_deref(), Some("Lawline"));
+        assert_eq!(proposal.completed_on, date(2025, 1, 9));
+        assert_eq!(proposal.approval_number.as_deref(), Some("ACT-2025-77"));
+        assert_eq!(
+            categories(&proposal),
+            vec![(CreditCategory::General, Credits::from_hundredths(150))]
+        );
+    }
+
+    #[test]
+    fn finds_nothing_in_unrelated_text() {
+        let text = "Team offsite agenda\n\
+                    We will take a 15 minute break after 45 minutes of discussion.\n\
+                    Lunch is 60 minutes.\n\
+                    Length: 1.5 hours\n";
+        let proposal = propose_entry(text);
+
+        assert_eq!(proposal.matched_rules, "generic");
+        assert_eq!(proposal.minutes, None);
+        assert_eq!(proposal.title, None);
+        assert_eq!(proposal.completed_on, None);
+        assert!(proposal.categories.is_empty());
+        assert_eq!(
+            proposal.missing,
+            vec!["title", "provider", "completed_on", "categories", "approval_number"]
+        );
+    }
+
+    #[test]
+    fn parses_certificate_dates() {
+        assert_eq!(parse_date("March 4, 2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("Mar 4, 2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("Mar. 4, 2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("03/04/2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("2025-03-04"), date(2025, 3, 4));
+        assert_eq!(parse_date("4 March 2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("03-04-2025"), date(2025, 3, 4));
+        assert_eq!(parse_date("  March 4, 2025 at 9:00 AM"), date(2025, 3, 4));
+        assert_eq!(parse_date("02/30/2025"), None);
+        assert_eq!(parse_date("sometime in spring"), None);
+    }
+}
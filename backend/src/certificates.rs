@@ -0,0 +1,315 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Multipart, Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use entity::certificate;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+use crate::blob_store::BlobStore;
+use crate::certificate_parser::ProposedEntry;
+
+/// Upload size limit used when `MAX_CERTIFICATE_BYTES` isn't set
+pub const DEFAULT_MAX_CERTIFICATE_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Serialize)]
+pub struct CertificateResponse {
+    pub id: i32,
+    pub credit_entry_id: i32,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub uploaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<certificate::Model> for CertificateResponse {
+    fn from(c: certificate::Model) -> Self {
+        CertificateResponse {
+            id: c.id,
+            credit_entry_id: c.credit_entry_id,
+            filename: c.filename,
+            content_type: c.content_type,
+            size_bytes: c.size_bytes,
+            uploaded_at: c.uploaded_at,
+        }
+    }
+}
+
+/// Works out the file type from its leading bytes rather than trusting the
+/// client's declared content type. Only PDFs and common image formats are accepted.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.len() >= 12
+        && &bytes[4..8] == b"ftyp"
+        && matches!(&bytes[8..12], b"heic" | b"heix" | b"mif1")
+    {
+        Some("image/heic")
+    } else {
+        None
+    }
+}
+
+/// Keeps a client-supplied filename safe to echo back in a download header
+fn sanitize_filename(name: Option<&str>) -> String {
+    let base = name
+        .unwrap_or_default()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(200)
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').to_string();
+
+    if cleaned.is_empty() {
+        "certificate".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Reads the `file` part of a multipart upload, enforcing the size limit.
+pub async fn read_upload(
+    multipart: &mut Multipart,
+    max_bytes: usize,
+) -> Result<(String, Bytes), (StatusCode, &'static str)> {
+    let upload_error = |e: axum::extract::multipart::MultipartError| {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            (StatusCode::PAYLOAD_TOO_LARGE, "Certificate is too large")
+        } else {
+            (StatusCode::BAD_REQUEST, "Invalid upload")
+        }
+    };
+
+    while let Some(field) = multipart.next_field().await.map_err(upload_error)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let filename = sanitize_filename(field.file_name());
+        let bytes = field.bytes().await.map_err(upload_error)?;
+        if bytes.is_empty() {
+            return Err((StatusCode::BAD_REQUEST, "Uploaded file is empty"));
+        }
+        if bytes.len() > max_bytes {
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, "Certificate is too large"));
+        }
+        return Ok((filename, bytes));
+    }
+
+    Err((StatusCode::BAD_REQUEST, "Missing file field"))
+}
+
+/// Removes the certificate rows attached to a credit entry, returning the blob
+/// keys so the files can be deleted once the surrounding transaction commits.
+pub async fn delete_rows_for_entry<C: ConnectionTrait>(
+    conn: &C,
+    credit_entry_id: i32,
+) -> Result<Vec<String>, DbErr> {
+    let certificates = certificate::Entity::find()
+        .filter(certificate::Column::CreditEntryId.eq(credit_entry_id))
+        .all(conn)
+        .await?;
+
+    certificate::Entity::delete_many()
+        .filter(certificate::Column::CreditEntryId.eq(credit_entry_id))
+        .exec(conn)
+        .await?;
+
+    Ok(certificates.into_iter().map(|c| c.blob_key).collect())
+}
+
+/// Best-effort removal of stored files whose rows are already gone
+pub async fn remove_blobs(blobs: &dyn BlobStore, keys: Vec<String>) {
+    for key in keys {
+        if let Err(e) = blobs.delete(&key).await {
+            tracing::warn!(key, error = %e, "Failed to delete certificate blob");
+        }
+    }
+}
+
+async fn find_certificate(
+    state: &crate::AppState,
+    user_id: i32,
+    id: i32,
+) -> Result<certificate::Model, (StatusCode, &'static str)> {
+    certificate::Entity::find_by_id(id)
+        .filter(certificate::Column::UserId.eq(user_id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Certificate not found"))
+}
+
+async fn find_user_entry(
+    state: &crate::AppState,
+    user_id: i32,
+    entry_id: i32,
+) -> Result<entity::credit_entry::Model, (StatusCode, &'static str)> {
+    entity::credit_entry::Entity::find_by_id(entry_id)
+        .filter(entity::credit_entry::Column::UserId.eq(user_id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Credit entry not found"))
+}
+
+pub async fn upload_certificate(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(entry_id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<Json<CertificateResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let entry = find_user_entry(&state, user.id, entry_id).await?;
+
+    let (filename, bytes) = read_upload(&mut multipart, state.max_certificate_bytes).await?;
+    let content_type = sniff_content_type(&bytes).ok_or((
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "Certificates must be PDF, PNG, JPEG, WebP or HEIC files",
+    ))?;
+
+    let blob_key = Uuid::new_v4().to_string();
+    state
+        .blobs
+        .put(&blob_key, &bytes)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to store certificate"))?;
+
+    let saved = certificate::ActiveModel {
+        credit_entry_id: Set(entry.id),
+        user_id: Set(user.id),
+        filename: Set(filename),
+        content_type: Set(content_type.to_string()),
+        size_bytes: Set(bytes.len() as i32),
+        blob_key: Set(blob_key.clone()),
+        uploaded_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(&state.conn)
+    .await;
+
+    match saved {
+        Ok(saved) => Ok(Json(saved.into())),
+        Err(_) => {
+            remove_blobs(state.blobs.as_ref(), vec![blob_key]).await;
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to store certificate"))
+        }
+    }
+}
+
+pub async fn list_certificates(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(entry_id): Path<i32>,
+) -> Result<Json<Vec<CertificateResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let entry = find_user_entry(&state, user.id, entry_id).await?;
+
+    let certificates = certificate::Entity::find()
+        .filter(certificate::Column::CreditEntryId.eq(entry.id))
+        .order_by_asc(certificate::Column::Id)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(certificates.into_iter().map(Into::into).collect()))
+}
+
+pub async fn download_certificate(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let certificate = find_certificate(&state, user.id, id).await?;
+
+    let bytes = state
+        .blobs
+        .get(&certificate.blob_key)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read certificate"))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, certificate.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", certificate.filename),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        bytes,
+    ))
+}
+
+pub async fn delete_certificate(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let certificate = find_certificate(&state, user.id, id).await?;
+
+    certificate::Entity::delete_by_id(certificate.id)
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete certificate"))?;
+
+    remove_blobs(state.blobs.as_ref(), vec![certificate.blob_key]).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reads the text layer of an uploaded PDF certificate and proposes a credit
+/// entry from it. Nothing is saved; the client confirms the proposal by
+/// creating the entry and attaching the certificate as usual.
+pub async fn extract_certificate(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    mut multipart: Multipart,
+) -> Result<Json<ProposedEntry>, (StatusCode, &'static str)> {
+    crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let (_, bytes) = read_upload(&mut multipart, state.max_certificate_bytes).await?;
+    if sniff_content_type(&bytes) != Some("application/pdf") {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Only PDF certificates can be read automatically",
+        ));
+    }
+
+    // Parsing is CPU-bound and the PDF library may panic on malformed input
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|_| (StatusCode::UNPROCESSABLE_ENTITY, "Could not read PDF"))?
+        .map_err(|_| (StatusCode::UNPROCESSABLE_ENTITY, "Could not read PDF"))?;
+
+    if text.trim().is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "PDF has no text layer"));
+    }
+
+    Ok(Json(crate::certificate_parser::propose_entry(&text)))
+}
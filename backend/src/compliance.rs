@@ -0,0 +1,460 @@
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::NaiveDate;
+use entity::Credits;
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::category::CreditCategory;
+use crate::ledger::LicenseTotals;
+use crate::license_status::LicenseStatus;
+use crate::licenses::License;
+
+/// Licenses with an outstanding requirement are at risk once the deadline is this close
+pub const DEADLINE_WARNING_DAYS: i64 = 60;
+
+/// How far behind a steady pace through the period, as a share of the
+/// requirement, a license can fall before it is flagged as at risk
+const PACE_TOLERANCE_PERCENT: i64 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    /// Every requirement for the current period has been met
+    Compliant,
+    /// Requirements remain but progress is keeping up with the deadline
+    OnTrack,
+    /// Requirements remain and the deadline is near or progress is behind
+    AtRisk,
+    /// The deadline has passed with requirements outstanding
+    Deficient,
+    /// Nothing is required of this license
+    Exempt,
+}
+
+impl ComplianceStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ComplianceStatus::Compliant => "compliant",
+            ComplianceStatus::OnTrack => "on_track",
+            ComplianceStatus::AtRisk => "at_risk",
+            ComplianceStatus::Deficient => "deficient",
+            ComplianceStatus::Exempt => "exempt",
+        }
+    }
+}
+
+/// Why a license has the status it does. Serialised with a `code` tag so
+/// clients can switch on it without parsing text.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum Reason {
+    NoRequirement,
+    /// Inactive and retired licenses carry no education requirement
+    LicenseNotActive { status: LicenseStatus },
+    /// Admitted during the current period, which the state doesn't count
+    NewlyAdmitted { admitted_on: NaiveDate },
+    /// Admitted during the current period, so a transitional requirement applies
+    NewAdmitteeRequirement { admitted_on: NaiveDate },
+    RequirementMet,
+    HoursRemaining { hours: Credits },
+    CategoryShortfall { category: CreditCategory, hours: Credits },
+    NoDeadline,
+    DeadlineApproaching { days: i64 },
+    DeadlinePassed { days: i64 },
+    BehindPace { expected: Credits, completed: Credits },
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryStatus {
+    pub category: CreditCategory,
+    pub required: Credits,
+    pub completed: Credits,
+    pub remaining: Credits,
+}
+
+/// The outcome of evaluating one license against its requirement.
+#[derive(Debug, Serialize)]
+pub struct Evaluation {
+    pub status: ComplianceStatus,
+    pub required: Credits,
+    pub completed: Credits,
+    pub remaining: Credits,
+    pub categories: Vec<CategoryStatus>,
+    pub deadline: Option<NaiveDate>,
+    /// Negative once the deadline has passed
+    pub days_to_deadline: Option<i64>,
+    pub reasons: Vec<Reason>,
+}
+
+/// Works out what the license must complete this period, along with the
+/// reason when that differs from the state's regular requirement.
+fn effective_requirement(license: &License) -> (Credits, Vec<(CreditCategory, Credits)>, Option<Reason>) {
+    let status: LicenseStatus = license.license.status.parse().unwrap_or_default();
+    if matches!(status, LicenseStatus::Inactive | LicenseStatus::Retired) {
+        return (Credits::ZERO, Vec::new(), Some(Reason::LicenseNotActive { status }));
+    }
+
+    let admitted_on = license
+        .license
+        .admission_date
+        .filter(|admitted| license.period_start().is_some_and(|start| *admitted >= start));
+    // States without a rule hold new admittees to the regular requirement;
+    // a rule of zero hours means nothing is due until the first full period
+    let new_admittee_hours = license.requirement.as_ref().and_then(|r| r.new_admittee_hours);
+    let minimums = |rows: &[entity::requirement_category::Model]| {
+        rows.iter()
+            .map(|m| (m.category.parse().unwrap_or_default(), m.hours))
+            .collect()
+    };
+    match (admitted_on, new_admittee_hours) {
+        (Some(admitted_on), Some(hours)) if hours <= Credits::ZERO => {
+            (Credits::ZERO, Vec::new(), Some(Reason::NewlyAdmitted { admitted_on }))
+        }
+        (Some(admitted_on), Some(hours)) => (
+            hours,
+            minimums(&license.new_admittee_minimums),
+            Some(Reason::NewAdmitteeRequirement { admitted_on }),
+        ),
+        _ => (
+            license.requirement.as_ref().map(|r| r.hours).unwrap_or_default(),
+            minimums(&license.category_minimums),
+            None,
+        ),
+    }
+}
+
+/// Evaluates a license's ledger totals against its requirement as of `today`.
+pub fn evaluate(license: &License, totals: &LicenseTotals, deadline: Option<NaiveDate>, today: NaiveDate) -> Evaluation {
+    let (required, minimums, adjustment) = effective_requirement(license);
+    let completed = totals.total;
+    let remaining = (required - completed).max(Credits::ZERO);
+
+    let categories: Vec<CategoryStatus> = minimums
+        .into_iter()
+        .map(|(category, minimum)| {
+            let completed = totals.by_category.get(&category).copied().unwrap_or_default();
+            CategoryStatus {
+                category,
+                required: minimum,
+                completed,
+                remaining: (minimum - completed).max(Credits::ZERO),
+            }
+        })
+        .collect();
+
+    let days_to_deadline = deadline.map(|d| (d - today).num_days());
+    let mut evaluation = Evaluation {
+        status: ComplianceStatus::Compliant,
+        required,
+        completed,
+        remaining,
+        categories,
+        deadline,
+        days_to_deadline,
+        reasons: Vec::new(),
+    };
+
+    if required <= Credits::ZERO && evaluation.categories.iter().all(|c| c.required <= Credits::ZERO) {
+        evaluation.status = ComplianceStatus::Exempt;
+        evaluation.reasons.push(adjustment.unwrap_or(Reason::NoRequirement));
+        return evaluation;
+    }
+    evaluation.reasons.extend(adjustment);
+
+    if remaining > Credits::ZERO {
+        evaluation.reasons.push(Reason::HoursRemaining { hours: remaining });
+    }
+    for category in evaluation.categories.iter().filter(|c| c.remaining > Credits::ZERO) {
+        evaluation.reasons.push(Reason::CategoryShortfall {
+            category: category.category,
+            hours: category.remaining,
+        });
+    }
+    if remaining <= Credits::ZERO && evaluation.categories.iter().all(|c| c.remaining <= Credits::ZERO) {
+        evaluation.reasons.push(Reason::RequirementMet);
+        return evaluation;
+    }
+
+    let (Some(deadline), Some(days)) = (deadline, days_to_deadline) else {
+        evaluation.status = ComplianceStatus::AtRisk;
+        evaluation.reasons.push(Reason::NoDeadline);
+        return evaluation;
+    };
+
+    if days < 0 {
+        evaluation.status = ComplianceStatus::Deficient;
+        evaluation.reasons.push(Reason::DeadlinePassed { days: -days });
+        return evaluation;
+    }
+
+    evaluation.status = ComplianceStatus::OnTrack;
+    if days <= DEADLINE_WARNING_DAYS {
+        evaluation.status = ComplianceStatus::AtRisk;
+        evaluation.reasons.push(Reason::DeadlineApproaching { days });
+    }
+
+    // Compare progress with working through the requirement evenly over the period
+    let period_start = license.period_start().unwrap_or(deadline);
+    let period_days = (deadline - period_start).num_days().max(1);
+    let elapsed_days = (today - period_start).num_days().clamp(0, period_days);
+    let expected = Credits::from_hundredths(required.hundredths() * elapsed_days / period_days);
+    let tolerance = Credits::from_hundredths(required.hundredths() * PACE_TOLERANCE_PERCENT / 100);
+    if completed + tolerance < expected {
+        evaluation.status = ComplianceStatus::AtRisk;
+        evaluation.reasons.push(Reason::BehindPace { expected, completed });
+    }
+
+    evaluation
+}
+
+#[derive(Serialize)]
+pub struct JurisdictionCompliance {
+    license_id: i32,
+    state_code: String,
+    profession: String,
+    #[serde(flatten)]
+    evaluation: Evaluation,
+}
+
+#[derive(Serialize)]
+pub struct ComplianceResponse {
+    as_of: NaiveDate,
+    jurisdictions: Vec<JurisdictionCompliance>,
+}
+
+pub async fn get_compliance(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<ComplianceResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let (licenses, totals) = crate::ledger::totals_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let today = chrono::Utc::now().date_naive();
+    let jurisdictions = licenses
+        .iter()
+        .map(|license| {
+            let totals = totals.get(&license.license.id).cloned().unwrap_or_default();
+            let deadline = license.deadline();
+            JurisdictionCompliance {
+                license_id: license.license.id,
+                state_code: license.state_code.clone(),
+                profession: license.license.profession.clone(),
+                evaluation: evaluate(license, &totals, deadline, today),
+            }
+        })
+        .collect();
+
+    Ok(Json(ComplianceResponse {
+        as_of: today,
+        jurisdictions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use entity::{requirement, requirement_category, user_state};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn minimum(id: i32, category: &str, hours: i64, new_admittee: bool) -> requirement_category::Model {
+        requirement_category::Model {
+            id,
+            requirement_id: 1,
+            category: category.to_string(),
+            hours: Credits::from_whole(hours),
+            new_admittee,
+        }
+    }
+
+    /// A two-year license ending 2025-12-31 that requires 24 credits, 2 of
+    /// them ethics. New admittees follow the seeded rules for NY and TX.
+    fn license(state_code: &str, admission_date: Option<NaiveDate>) -> License {
+        let (new_admittee_hours, new_admittee_minimums) = match state_code {
+            "NY" => (
+                Some(Credits::from_whole(32)),
+                vec![minimum(2, "ethics", 3, true), minimum(3, "skills", 6, true)],
+            ),
+            "TX" => (Some(Credits::ZERO), Vec::new()),
+            _ => (None, Vec::new()),
+        };
+        License {
+            license: user_state::Model {
+                id: 1,
+                user_id: 1,
+                state_id: 1,
+                renewal_date: Some("2025-12-31".to_string()),
+                profession: "Attorney".to_string(),
+                period_start: None,
+                admission_date,
+                bar_number: None,
+                status: "active".to_string(),
+                deadline_override: None,
+                version: 1,
+                removed_at: None,
+            },
+            state_code: state_code.to_string(),
+            requirement: Some(requirement::Model {
+                id: 1,
+                state_id: 1,
+                profession: "Attorney".to_string(),
+                hours: Credits::from_whole(24),
+                minutes_per_credit: 60,
+                credit_increment: Credits::from_hundredths(25),
+                period_months: 24,
+                new_admittee_hours,
+            }),
+            category_minimums: vec![minimum(1, "ethics", 2, false)],
+            new_admittee_minimums,
+        }
+    }
+
+    fn totals(total: i64, ethics: i64) -> LicenseTotals {
+        let mut totals = LicenseTotals {
+            total: Credits::from_whole(total),
+            ..Default::default()
+        };
+        totals.by_category.insert(CreditCategory::Ethics, Credits::from_whole(ethics));
+        totals.by_category.insert(CreditCategory::General, Credits::from_whole(total - ethics));
+        totals
+    }
+
+    fn codes(evaluation: &Evaluation) -> Vec<String> {
+        evaluation
+            .reasons
+            .iter()
+            .map(|r| serde_json::to_value(r).unwrap()["code"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn compliant_once_every_requirement_is_met() {
+        let license = license("CA", None);
+        let evaluation = evaluate(&license, &totals(25, 3), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::Compliant);
+        assert_eq!(evaluation.remaining, Credits::ZERO);
+        assert_eq!(evaluation.categories[0].remaining, Credits::ZERO);
+        assert_eq!(codes(&evaluation), vec!["requirement_met"]);
+    }
+
+    #[test]
+    fn short_on_total_hours() {
+        let license = license("CA", None);
+
+        // Half way through the period with half the hours done keeps pace
+        let evaluation = evaluate(&license, &totals(12, 2), license.deadline(), date(2025, 1, 1));
+        assert_eq!(evaluation.status, ComplianceStatus::OnTrack);
+        assert_eq!(evaluation.remaining, Credits::from_whole(12));
+        assert_eq!(codes(&evaluation), vec!["hours_remaining"]);
+
+        // Close to the deadline the same shortfall is a risk
+        let evaluation = evaluate(&license, &totals(23, 2), license.deadline(), date(2025, 12, 1));
+        assert_eq!(evaluation.status, ComplianceStatus::AtRisk);
+        assert_eq!(codes(&evaluation), vec!["hours_remaining", "deadline_approaching"]);
+
+        // And once it has passed the license is deficient
+        let evaluation = evaluate(&license, &totals(23, 2), license.deadline(), date(2026, 1, 5));
+        assert_eq!(evaluation.status, ComplianceStatus::Deficient);
+        assert_eq!(codes(&evaluation), vec!["hours_remaining", "deadline_passed"]);
+    }
+
+    #[test]
+    fn falling_behind_pace_is_a_risk() {
+        let license = license("CA", None);
+        let evaluation = evaluate(&license, &totals(2, 2), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::AtRisk);
+        assert_eq!(codes(&evaluation), vec!["hours_remaining", "behind_pace"]);
+    }
+
+    #[test]
+    fn short_on_a_category() {
+        let license = license("CA", None);
+        let evaluation = evaluate(&license, &totals(30, 1), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::OnTrack);
+        assert_eq!(evaluation.remaining, Credits::ZERO);
+        assert_eq!(evaluation.categories[0].remaining, Credits::from_whole(1));
+        assert!(matches!(
+            evaluation.reasons[..],
+            [Reason::CategoryShortfall { category: CreditCategory::Ethics, hours }] if hours == Credits::from_whole(1)
+        ));
+    }
+
+    #[test]
+    fn no_deadline_is_a_risk_while_hours_remain() {
+        let license = license("CA", None);
+        let evaluation = evaluate(&license, &totals(10, 2), None, date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::AtRisk);
+        assert_eq!(codes(&evaluation), vec!["hours_remaining", "no_deadline"]);
+    }
+
+    #[test]
+    fn new_admittee_exempt_for_the_first_period() {
+        let license = license("TX", Some(date(2024, 3, 1)));
+        let evaluation = evaluate(&license, &totals(0, 0), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::Exempt);
+        assert_eq!(evaluation.required, Credits::ZERO);
+        assert!(matches!(
+            evaluation.reasons[..],
+            [Reason::NewlyAdmitted { admitted_on }] if admitted_on == date(2024, 3, 1)
+        ));
+    }
+
+    #[test]
+    fn new_admittee_transitional_requirement() {
+        let license = license("NY", Some(date(2024, 3, 1)));
+        let evaluation = evaluate(&license, &totals(24, 2), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.required, Credits::from_whole(32));
+        assert_eq!(evaluation.remaining, Credits::from_whole(8));
+        let minimums: Vec<_> = evaluation.categories.iter().map(|c| (c.category, c.required)).collect();
+        assert_eq!(
+            minimums,
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(3)),
+                (CreditCategory::Skills, Credits::from_whole(6)),
+            ]
+        );
+        assert_eq!(
+            codes(&evaluation),
+            vec!["new_admittee_requirement", "hours_remaining", "category_shortfall", "category_shortfall"]
+        );
+    }
+
+    #[test]
+    fn new_admittee_without_a_rule_uses_the_regular_requirement() {
+        let license = license("CA", Some(date(2024, 3, 1)));
+        let evaluation = evaluate(&license, &totals(25, 3), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.required, Credits::from_whole(24));
+        assert_eq!(codes(&evaluation), vec!["requirement_met"]);
+    }
+
+    #[test]
+    fn admission_before_the_period_uses_the_regular_requirement() {
+        let license = license("NY", Some(date(2020, 3, 1)));
+        let evaluation = evaluate(&license, &totals(25, 3), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.required, Credits::from_whole(24));
+        assert_eq!(evaluation.status, ComplianceStatus::Compliant);
+    }
+
+    #[test]
+    fn inactive_licenses_are_exempt() {
+        let mut license = license("CA", None);
+        license.license.status = "inactive".to_string();
+        let evaluation = evaluate(&license, &totals(0, 0), license.deadline(), date(2025, 6, 1));
+
+        assert_eq!(evaluation.status, ComplianceStatus::Exempt);
+        assert_eq!(codes(&evaluation), vec!["license_not_active"]);
+    }
+}
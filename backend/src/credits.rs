@@ -0,0 +1,90 @@
+use axum::{Json, extract::State, http::StatusCode};
+use entity::Credits;
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+/// How a jurisdiction turns minutes of instruction into credit.
+#[derive(Clone, Copy, Debug)]
+pub struct CreditUnit {
+    pub minutes_per_credit: u32,
+    pub increment: Credits,
+}
+
+impl Default for CreditUnit {
+    fn default() -> Self {
+        CreditUnit {
+            minutes_per_credit: 60,
+            increment: Credits::from_hundredths(25),
+        }
+    }
+}
+
+impl CreditUnit {
+    pub fn for_requirement(requirement: Option<&entity::requirement::Model>) -> Self {
+        match requirement {
+            Some(r) if r.minutes_per_credit > 0 => CreditUnit {
+                minutes_per_credit: r.minutes_per_credit as u32,
+                increment: r.credit_increment,
+            },
+            _ => CreditUnit::default(),
+        }
+    }
+
+    /// Credit earned for `minutes` of instruction, rounded down to the smallest
+    /// increment the jurisdiction awards.
+    pub fn credits_for_minutes(&self, minutes: u32) -> Credits {
+        let hundredths = i64::from(minutes) * 100 / i64::from(self.minutes_per_credit);
+        Credits::from_hundredths(hundredths).round_down_to(self.increment)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ConvertRequest {
+    pub minutes: u32,
+}
+
+#[derive(Serialize)]
+pub struct Conversion {
+    pub state_code: String,
+    pub profession: String,
+    pub minutes_per_credit: u32,
+    pub credits: Credits,
+}
+
+#[derive(Serialize)]
+pub struct ConvertResponse {
+    pub minutes: u32,
+    pub conversions: Vec<Conversion>,
+}
+
+/// Shows how many credits a course of the given length is worth in each of the
+/// user's jurisdictions.
+pub async fn convert_minutes(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Json(data): Json<ConvertRequest>,
+) -> Result<Json<ConvertResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let conversions = licenses
+        .iter()
+        .map(|l| {
+            let unit = CreditUnit::for_requirement(l.requirement.as_ref());
+            Conversion {
+                state_code: l.state_code.clone(),
+                profession: l.license.profession.clone(),
+                minutes_per_credit: unit.minutes_per_credit,
+                credits: unit.credits_for_minutes(data.minutes),
+            }
+        })
+        .collect();
+
+    Ok(Json(ConvertResponse {
+        minutes: data.minutes,
+        conversions,
+    }))
+}
@@ -0,0 +1,74 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// A currency prices and payments are recorded in.
+///
+/// Stored in the database as its ISO 4217 code. Amounts are always kept in
+/// minor units, so nothing is summed across currencies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, DeserializeFromStr, SerializeDisplay)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Cad,
+    Eur,
+    Gbp,
+}
+
+impl Currency {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Cad => "CA$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+        }
+    }
+
+    /// An amount as shown to users, e.g. `$99` or `€12.50`
+    pub fn format(&self, cents: i64) -> String {
+        let sign = if cents < 0 { "-" } else { "" };
+        let cents = cents.abs();
+        match cents % 100 {
+            0 => format!("{}{}{}", sign, self.symbol(), cents / 100),
+            rest => format!("{}{}{}.{:02}", sign, self.symbol(), cents / 100, rest),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseCurrencyError;
+
+impl fmt::Display for ParseCurrencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unsupported currency")
+    }
+}
+
+impl std::error::Error for ParseCurrencyError {}
+
+impl FromStr for Currency {
+    type Err = ParseCurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "USD" => Ok(Currency::Usd),
+            "CAD" => Ok(Currency::Cad),
+            "EUR" => Ok(Currency::Eur),
+            "GBP" => Ok(Currency::Gbp),
+            _ => Err(ParseCurrencyError),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Currency::Usd => "USD",
+            Currency::Cad => "CAD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+        };
+        write!(f, "{}", code)
+    }
+}
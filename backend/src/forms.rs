@@ -0,0 +1,517 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::NaiveDate;
+use entity::Credits;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::path::Path as FsPath;
+use tower_cookies::Cookies;
+
+use crate::pdf::PdfWriter;
+use crate::reports::ComplianceReport;
+
+/// A jurisdiction's certification form or affidavit, loaded from a JSON file
+/// in the forms directory. The file name (without `.json`) is the form's key.
+///
+/// Every `text`, `label` and `value` is a MiniJinja template rendered against
+/// the license's compliance data, so new forms need no code changes.
+#[derive(Deserialize)]
+pub struct FormTemplate {
+    pub title: String,
+    /// Two-letter code of the jurisdiction the form is for
+    pub state: String,
+    /// Limits the form to one profession; it applies to all when absent
+    pub profession: Option<String>,
+    pub blocks: Vec<FormBlock>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FormBlock {
+    Heading { text: String },
+    Paragraph { text: String },
+    /// A labelled value, e.g. the attorney's registration number
+    Field { label: String, value: String },
+    /// The courses completed in the period
+    Courses,
+    /// Completed, required and remaining credits by category
+    Totals,
+    /// A blank line for a handwritten signature
+    Signature { label: String },
+}
+
+#[derive(Serialize)]
+struct CourseContext {
+    completed_on: NaiveDate,
+    title: String,
+    provider: Option<String>,
+    approval_number: Option<String>,
+    total: Credits,
+}
+
+#[derive(Serialize)]
+struct CategoryContext {
+    label: &'static str,
+    completed: String,
+    required: String,
+    remaining: String,
+}
+
+/// The values a form template can refer to.
+#[derive(Serialize)]
+struct FormContext {
+    fullname: String,
+    state_code: String,
+    profession: String,
+    bar_number: Option<String>,
+    admission_date: Option<NaiveDate>,
+    period_start: Option<NaiveDate>,
+    period_end: Option<NaiveDate>,
+    status: &'static str,
+    required: Credits,
+    completed: Credits,
+    remaining: Credits,
+    courses: Vec<CourseContext>,
+    categories: Vec<CategoryContext>,
+    today: NaiveDate,
+}
+
+impl From<&ComplianceReport> for FormContext {
+    fn from(report: &ComplianceReport) -> Self {
+        FormContext {
+            fullname: report.fullname.clone(),
+            state_code: report.state_code.clone(),
+            profession: report.profession.clone(),
+            bar_number: report.bar_number.clone(),
+            admission_date: report.admission_date,
+            period_start: report.period_start,
+            period_end: report.period_end,
+            status: report.evaluation.status.as_str(),
+            required: report.evaluation.required,
+            completed: report.evaluation.completed,
+            remaining: report.evaluation.remaining,
+            courses: report
+                .courses
+                .iter()
+                .map(|c| CourseContext {
+                    completed_on: c.completed_on,
+                    title: c.title.clone(),
+                    provider: c.provider.clone(),
+                    approval_number: c.approval_number.clone(),
+                    total: c.total,
+                })
+                .collect(),
+            categories: report
+                .categories
+                .iter()
+                .map(|c| {
+                    let [completed, required, remaining] = report.category_totals(*c);
+                    CategoryContext {
+                        label: c.label(),
+                        completed,
+                        required,
+                        remaining,
+                    }
+                })
+                .collect(),
+            today: chrono::Utc::now().date_naive(),
+        }
+    }
+}
+
+/// A form's blocks with every template filled in.
+enum RenderedBlock {
+    Heading(String),
+    Paragraph(String),
+    Field(String, String),
+    Courses,
+    Totals,
+    Signature(String),
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Reads every form template in the directory, keyed by file name. Files
+/// that fail to parse are skipped with a warning rather than failing the request.
+pub async fn load_templates(dir: &FsPath) -> Vec<(String, FormTemplate)> {
+    let mut templates = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return templates;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(key) = path.file_stem().and_then(|s| s.to_str()).filter(|k| is_valid_key(k)) else {
+            continue;
+        };
+        let parsed = tokio::fs::read(&path)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| serde_json::from_slice::<FormTemplate>(&bytes).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(template) => templates.push((key.to_string(), template)),
+            Err(e) => tracing::warn!(path = %path.display(), error = %e, "Skipping form template"),
+        }
+    }
+
+    templates.sort_by(|a, b| a.0.cmp(&b.0));
+    templates
+}
+
+impl FormTemplate {
+    fn applies_to(&self, state_code: &str, profession: &str) -> bool {
+        self.state.eq_ignore_ascii_case(state_code)
+            && self
+                .profession
+                .as_deref()
+                .is_none_or(|p| p.eq_ignore_ascii_case(profession))
+    }
+
+    fn render(&self, context: &FormContext) -> Result<(String, Vec<RenderedBlock>), minijinja::Error> {
+        let env = Environment::new();
+        let fill = |text: &str| env.render_str(text, context);
+
+        let title = fill(&self.title)?;
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                Ok(match block {
+                    FormBlock::Heading { text } => RenderedBlock::Heading(fill(text)?),
+                    FormBlock::Paragraph { text } => RenderedBlock::Paragraph(fill(text)?),
+                    FormBlock::Field { label, value } => RenderedBlock::Field(fill(label)?, fill(value)?),
+                    FormBlock::Courses => RenderedBlock::Courses,
+                    FormBlock::Totals => RenderedBlock::Totals,
+                    FormBlock::Signature { label } => RenderedBlock::Signature(fill(label)?),
+                })
+            })
+            .collect::<Result<Vec<_>, minijinja::Error>>()?;
+        Ok((title, blocks))
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(title: &str, blocks: &[RenderedBlock], context: &FormContext) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\
+         body{{font-family:Helvetica,Arial,sans-serif;max-width:48rem;margin:2rem auto;line-height:1.4}}\
+         table{{border-collapse:collapse;width:100%}}th,td{{border:1px solid #999;padding:.25rem .5rem;text-align:left}}\
+         .field{{margin:.25rem 0}}.label{{display:inline-block;min-width:12rem;font-weight:bold}}\
+         .signature{{margin-top:3rem;border-top:1px solid #000;width:20rem;padding-top:.25rem}}\
+         </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape_html(title),
+        escape_html(title)
+    );
+
+    for block in blocks {
+        match block {
+            RenderedBlock::Heading(text) => html.push_str(&format!("<h2>{}</h2>\n", escape_html(text))),
+            RenderedBlock::Paragraph(text) => html.push_str(&format!("<p>{}</p>\n", escape_html(text))),
+            RenderedBlock::Field(label, value) => html.push_str(&format!(
+                "<div class=\"field\"><span class=\"label\">{}</span> {}</div>\n",
+                escape_html(label),
+                escape_html(value)
+            )),
+            RenderedBlock::Courses => {
+                html.push_str("<table>\n<tr><th>Date</th><th>Course</th><th>Provider</th><th>Approval no.</th><th>Credits</th></tr>\n");
+                for course in &context.courses {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        course.completed_on,
+                        escape_html(&course.title),
+                        escape_html(course.provider.as_deref().unwrap_or_default()),
+                        escape_html(course.approval_number.as_deref().unwrap_or_default()),
+                        course.total
+                    ));
+                }
+                html.push_str("</table>\n");
+            }
+            RenderedBlock::Totals => {
+                html.push_str("<table>\n<tr><th>Category</th><th>Completed</th><th>Required</th><th>Remaining</th></tr>\n");
+                for category in &context.categories {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        category.label, category.completed, category.required, category.remaining
+                    ));
+                }
+                html.push_str(&format!(
+                    "<tr><th>All credit</th><th>{}</th><th>{}</th><th>{}</th></tr>\n</table>\n",
+                    context.completed, context.required, context.remaining
+                ));
+            }
+            RenderedBlock::Signature(label) => html.push_str(&format!(
+                "<div class=\"signature\">{}</div>\n",
+                escape_html(label)
+            )),
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_pdf(title: &str, blocks: &[RenderedBlock], context: &FormContext) -> Result<Vec<u8>, printpdf::Error> {
+    let mut pdf = PdfWriter::new(title)?;
+    let width = pdf.content_width();
+
+    pdf.paragraph(title, 15.0, true);
+    pdf.gap(3.0);
+
+    for block in blocks {
+        match block {
+            RenderedBlock::Heading(text) => {
+                pdf.gap(2.0);
+                pdf.paragraph(text, 12.0, true);
+            }
+            RenderedBlock::Paragraph(text) => {
+                pdf.paragraph(text, 10.0, false);
+                pdf.gap(2.0);
+            }
+            RenderedBlock::Field(label, value) => {
+                pdf.row(&[(0.0, 55.0, label), (55.0, width - 55.0, value)], 10.0, false);
+            }
+            RenderedBlock::Courses => {
+                let columns = [(0.0, 20.0), (21.0, 75.0), (97.0, 40.0), (138.0, 25.0), (164.0, width - 164.0)];
+                let header = ["Date", "Course", "Provider", "Approval no.", "Credits"];
+                let cells: Vec<(f32, f32, &str)> =
+                    columns.iter().zip(header).map(|((x, w), t)| (*x, *w, t)).collect();
+                pdf.row(&cells, 9.0, true);
+                for course in &context.courses {
+                    let date = course.completed_on.to_string();
+                    let total = course.total.to_string();
+                    let values = [
+                        date.as_str(),
+                        course.title.as_str(),
+                        course.provider.as_deref().unwrap_or_default(),
+                        course.approval_number.as_deref().unwrap_or_default(),
+                        total.as_str(),
+                    ];
+                    let cells: Vec<(f32, f32, &str)> =
+                        columns.iter().zip(values).map(|((x, w), t)| (*x, *w, t)).collect();
+                    pdf.row(&cells, 9.0, false);
+                }
+                pdf.gap(2.0);
+            }
+            RenderedBlock::Totals => {
+                pdf.row(
+                    &[(0.0, 60.0, "Category"), (70.0, 30.0, "Completed"), (105.0, 30.0, "Required"), (140.0, 30.0, "Remaining")],
+                    10.0,
+                    true,
+                );
+                for category in &context.categories {
+                    pdf.row(
+                        &[
+                            (0.0, 60.0, category.label),
+                            (70.0, 30.0, &category.completed),
+                            (105.0, 30.0, &category.required),
+                            (140.0, 30.0, &category.remaining),
+                        ],
+                        10.0,
+                        false,
+                    );
+                }
+                let completed = context.completed.to_string();
+                let required = context.required.to_string();
+                let remaining = context.remaining.to_string();
+                pdf.row(
+                    &[(0.0, 60.0, "All credit"), (70.0, 30.0, &completed), (105.0, 30.0, &required), (140.0, 30.0, &remaining)],
+                    10.0,
+                    true,
+                );
+                pdf.gap(2.0);
+            }
+            RenderedBlock::Signature(label) => {
+                pdf.gap(12.0);
+                pdf.row(&[(0.0, 80.0, "________________________________________")], 10.0, false);
+                pdf.row(&[(0.0, 80.0, label)], 9.0, false);
+            }
+        }
+    }
+
+    pdf.finish()
+}
+
+#[derive(Serialize)]
+pub struct AvailableForm {
+    key: String,
+    title: String,
+    license_id: i32,
+    state_code: String,
+    profession: String,
+}
+
+/// Lists the forms that apply to each of the user's licenses.
+pub async fn list_forms(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<AvailableForm>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let templates = load_templates(&state.forms_dir).await;
+
+    let mut forms = Vec::new();
+    for license in &licenses {
+        for (key, template) in &templates {
+            if template.applies_to(&license.state_code, &license.license.profession) {
+                forms.push(AvailableForm {
+                    key: key.clone(),
+                    title: template.title.clone(),
+                    license_id: license.license.id,
+                    state_code: license.state_code.clone(),
+                    profession: license.license.profession.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(Json(forms))
+}
+
+#[derive(Deserialize)]
+pub struct FormQuery {
+    /// `pdf` (the default) or `html`
+    format: Option<String>,
+    /// A closed period from the compliance history; the current period otherwise
+    period_id: Option<i32>,
+}
+
+pub async fn render_form(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path((key, license_id)): Path<(String, i32)>,
+    Query(query): Query<FormQuery>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let report = crate::reports::build_report(&state.conn, &user, license_id, query.period_id).await?;
+
+    let template = load_templates(&state.forms_dir)
+        .await
+        .into_iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, t)| t)
+        .ok_or((StatusCode::NOT_FOUND, "Form not found"))?;
+    if !template.applies_to(&report.state_code, &report.profession) {
+        return Err((StatusCode::BAD_REQUEST, "Form doesn't apply to this license"));
+    }
+
+    let context = FormContext::from(&report);
+    let (title, blocks) = template
+        .render(&context)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Form template is invalid"))?;
+
+    let filename = format!("{}-{}", key, report.period_end.map(|d| d.to_string()).unwrap_or_default());
+    let (content_type, disposition, body) = match query.format.as_deref().unwrap_or("pdf") {
+        "pdf" => (
+            "application/pdf",
+            format!("attachment; filename=\"{}.pdf\"", filename),
+            render_pdf(&title, &blocks, &context)
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render form"))?,
+        ),
+        "html" => (
+            "text/html; charset=utf-8",
+            format!("inline; filename=\"{}.html\"", filename),
+            render_html(&title, &blocks, &context).into_bytes(),
+        ),
+        _ => return Err((StatusCode::BAD_REQUEST, "Format must be pdf or html")),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use serde_json::json;
+
+    async fn write_form(dir: &FsPath, file: &str, contents: &str) {
+        tokio::fs::create_dir_all(dir).await.unwrap();
+        tokio::fs::write(dir.join(file), contents).await.unwrap();
+    }
+
+    fn form(state: &str, profession: Option<&str>, blocks: serde_json::Value) -> String {
+        json!({"title": "Affirmation", "state": state, "profession": profession, "blocks": blocks}).to_string()
+    }
+
+    #[tokio::test]
+    async fn invalid_templates_are_skipped() {
+        let state = test_support::app_state().await;
+        let dir = &state.forms_dir;
+        write_form(dir, "ny_affirmation.json", &form("NY", None, json!([]))).await;
+        write_form(dir, "broken.json", "{\"title\": ").await;
+        write_form(dir, "unknown_block.json", &form("NY", None, json!([{"kind": "chart"}]))).await;
+        write_form(dir, "bad key.json", &form("NY", None, json!([]))).await;
+        write_form(dir, "notes.txt", &form("NY", None, json!([]))).await;
+
+        let keys: Vec<_> = load_templates(dir).await.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["ny_affirmation"]);
+        assert!(load_templates(&dir.join("missing")).await.is_empty());
+    }
+
+    #[test]
+    fn templates_apply_by_state_and_profession() {
+        let any: FormTemplate = serde_json::from_str(&form("ny", None, json!([]))).unwrap();
+        assert!(any.applies_to("NY", "Attorney"));
+        assert!(any.applies_to("NY", "CPA"));
+        assert!(!any.applies_to("CA", "Attorney"));
+
+        let attorneys: FormTemplate = serde_json::from_str(&form("NY", Some("attorney"), json!([]))).unwrap();
+        assert!(attorneys.applies_to("NY", "Attorney"));
+        assert!(!attorneys.applies_to("NY", "CPA"));
+    }
+
+    #[tokio::test]
+    async fn rendered_values_are_escaped_in_html() {
+        let state = test_support::app_state().await;
+        let (user, cookies) = test_support::sign_in(&state.conn, "<script>\"ann\" & co</script>").await;
+        let ny = test_support::add_license(&state.conn, user.id, "NY", "2027-06-30").await;
+        let blocks = json!([
+            {"kind": "field", "label": "Attorney", "value": "{{ fullname }}"},
+            {"kind": "signature", "label": "Signed by {{ fullname }}"},
+        ]);
+        write_form(&state.forms_dir, "ny_affirmation.json", &form("NY", None, blocks)).await;
+
+        let resp = render_form(
+            State(state.clone()),
+            cookies,
+            Path(("ny_affirmation".to_string(), ny.id)),
+            Query(FormQuery {
+                format: Some("html".to_string()),
+                period_id: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;&quot;ann&quot; &amp; co&lt;/script&gt;</div>"));
+        assert!(html.contains("Signed by &lt;script&gt;"));
+    }
+}
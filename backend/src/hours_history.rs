@@ -0,0 +1,372 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use entity::{Credits, credit_entry, hours_change};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tower_cookies::Cookies;
+
+use crate::ledger::LicenseTotals;
+use crate::licenses::License;
+use crate::versioning::IfMatch;
+
+/// What caused a license's completed hours to be set.
+#[derive(Clone, Copy)]
+pub enum Source {
+    Registration,
+    Jurisdiction,
+    Update,
+    /// Credit logged against one or more jurisdictions at once
+    Log,
+    /// A ledger entry added, edited or deleted
+    Credit,
+    /// Entries imported from a transcript or CSV file
+    Import,
+    /// A planned course marked as completed
+    Plan,
+    /// Undoes the change with the given id
+    Revert(i32),
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Registration => "registration",
+            Source::Jurisdiction => "jurisdiction",
+            Source::Update => "update",
+            Source::Log => "log",
+            Source::Credit => "credit",
+            Source::Import => "import",
+            Source::Plan => "plan",
+            Source::Revert(_) => "revert",
+        }
+    }
+
+    /// Title of the ledger entry that makes up the difference
+    pub fn entry_title(self) -> &'static str {
+        match self {
+            Source::Registration => "Hours reported at registration",
+            Source::Jurisdiction => "Hours reported when adding jurisdiction",
+            Source::Update => "Manual adjustment",
+            Source::Log => "Logged hours",
+            Source::Credit => "Credit entry",
+            Source::Import => "Imported credit",
+            Source::Plan => "Completed planned course",
+            Source::Revert(_) => "Reverted hours change",
+        }
+    }
+}
+
+/// Sets a license's completed hours by recording the difference as a ledger
+/// adjustment, and keeps the old and new values in the change history.
+/// Returns `None` when the hours already match.
+pub async fn set_hours<C: ConnectionTrait>(
+    conn: &C,
+    actor_id: i32,
+    user_id: i32,
+    license_id: i32,
+    hours: Credits,
+    source: Source,
+) -> Result<Option<hours_change::Model>, DbErr> {
+    let (_, totals) = crate::ledger::totals_for_user(conn, user_id).await?;
+    let current = totals
+        .get(&license_id)
+        .map(|t| t.total)
+        .unwrap_or_default();
+    if hours == current {
+        return Ok(None);
+    }
+
+    let entry = crate::ledger::record_adjustment(
+        conn,
+        user_id,
+        license_id,
+        source.entry_title(),
+        hours - current,
+    )
+    .await?;
+
+    record_change(conn, actor_id, license_id, &entry, current, hours, source)
+        .await
+        .map(Some)
+}
+
+/// Keeps a license's hours before and after the ledger entry that changed them.
+pub async fn record_change<C: ConnectionTrait>(
+    conn: &C,
+    actor_id: i32,
+    license_id: i32,
+    entry: &credit_entry::Model,
+    old_hours: Credits,
+    new_hours: Credits,
+    source: Source,
+) -> Result<hours_change::Model, DbErr> {
+    hours_change::ActiveModel {
+        user_id: Set(entry.user_id),
+        user_state_id: Set(license_id),
+        actor_id: Set(actor_id),
+        old_hours: Set(old_hours),
+        new_hours: Set(new_hours),
+        source: Set(source.as_str().to_string()),
+        credit_entry_id: Set(Some(entry.id)),
+        reverts_change_id: Set(match source {
+            Source::Revert(id) => Some(id),
+            _ => None,
+        }),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+/// Keeps a change for every license whose total differs between `before` and
+/// `after`, attributed to the ledger entry whose write moved it.
+pub async fn record_changes<C: ConnectionTrait>(
+    conn: &C,
+    actor_id: i32,
+    entry: &credit_entry::Model,
+    before: &HashMap<i32, LicenseTotals>,
+    after: &HashMap<i32, LicenseTotals>,
+    source: Source,
+) -> Result<(), DbErr> {
+    let license_ids: BTreeSet<i32> = before.keys().chain(after.keys()).copied().collect();
+    for license_id in license_ids {
+        let old = before.get(&license_id).map(|t| t.total).unwrap_or_default();
+        let new = after.get(&license_id).map(|t| t.total).unwrap_or_default();
+        if old != new {
+            record_change(conn, actor_id, license_id, entry, old, new, source).await?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct HoursChangeResponse {
+    id: i32,
+    license_id: i32,
+    state_code: String,
+    profession: String,
+    old_hours: Credits,
+    new_hours: Credits,
+    actor_id: i32,
+    source: String,
+    reverts_change_id: Option<i32>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl HoursChangeResponse {
+    fn new(change: hours_change::Model, license: &License) -> Self {
+        HoursChangeResponse {
+            id: change.id,
+            license_id: change.user_state_id,
+            state_code: license.state_code.clone(),
+            profession: license.license.profession.clone(),
+            old_hours: change.old_hours,
+            new_hours: change.new_hours,
+            actor_id: change.actor_id,
+            source: change.source,
+            reverts_change_id: change.reverts_change_id,
+            created_at: change.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HoursHistoryQuery {
+    license_id: Option<i32>,
+}
+
+/// Lists changes to the user's completed hours, most recent first.
+pub async fn hours_history(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Query(query): Query<HoursHistoryQuery>,
+) -> Result<Json<Vec<HoursChangeResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    if let Some(license_id) = query.license_id
+        && !licenses.iter().any(|l| l.license.id == license_id)
+    {
+        return Err((StatusCode::NOT_FOUND, "License not found"));
+    }
+
+    let mut changes = hours_change::Entity::find().filter(hours_change::Column::UserId.eq(user.id));
+    if let Some(license_id) = query.license_id {
+        changes = changes.filter(hours_change::Column::UserStateId.eq(license_id));
+    }
+    let changes = changes
+        .order_by_desc(hours_change::Column::CreatedAt)
+        .order_by_desc(hours_change::Column::Id)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(
+        changes
+            .into_iter()
+            .filter_map(|change| {
+                let license = licenses.iter().find(|l| l.license.id == change.user_state_id)?;
+                Some(HoursChangeResponse::new(change, license))
+            })
+            .collect(),
+    ))
+}
+
+/// Sets a license's hours back to the value they had before the given change.
+pub async fn revert_hours_change(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    if_match: IfMatch,
+) -> Result<Json<HoursChangeResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let change = hours_change::Entity::find_by_id(id)
+        .filter(hours_change::Column::UserId.eq(user.id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Change not found"))?;
+    // The license may have been removed since
+    crate::ledger::check_license(&state.conn, user.id, Some(change.user_state_id)).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    let revert = set_hours(
+        &txn,
+        user.id,
+        user.id,
+        change.user_state_id,
+        change.old_hours,
+        Source::Revert(change.id),
+    )
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to revert change"))?
+    .ok_or((StatusCode::CONFLICT, "Hours already match the value before this change"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to revert change"))?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let license = licenses
+        .iter()
+        .find(|l| l.license.id == revert.user_state_id)
+        .ok_or((StatusCode::NOT_FOUND, "License not found"))?;
+
+    Ok(Json(HoursChangeResponse::new(revert, license)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn history_adds_up_to_the_ledger_totals() {
+        let state = test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (user, cookies) = test_support::sign_in(&conn, "ann").await;
+        let ny = test_support::add_license(&conn, user.id, "NY", "2027-06-30").await;
+        test_support::add_license(&conn, user.id, "CA", "2027-01-31").await;
+
+        set_hours(&conn, user.id, user.id, ny.id, Credits::from_whole(5), Source::Registration)
+            .await
+            .unwrap();
+
+        // An entry for every license, then narrowed to one
+        let request = |credits: f64, license_id: Option<i32>| {
+            Json(
+                serde_json::from_value(json!({
+                    "title": "Evidence",
+                    "completed_on": "2026-01-15",
+                    "license_id": license_id,
+                    "categories": [{"category": "ethics", "credits": credits}],
+                }))
+                .unwrap(),
+            )
+        };
+        let created = crate::ledger::create_credit(State(state.clone()), cookies.clone(), IfMatch::Any, request(2.0, None))
+            .await
+            .unwrap();
+        let created_id = created.0.id;
+        let updated = crate::ledger::update_credit(
+            State(state.clone()),
+            cookies.clone(),
+            Path(created_id),
+            IfMatch::Any,
+            request(3.0, Some(ny.id)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.0.id, created_id);
+
+        let imported = crate::import::commit_import(
+            State(state.clone()),
+            cookies.clone(),
+            IfMatch::Any,
+            Json(
+                serde_json::from_value(json!({
+                    "content": "Title,Date,Credits\nContracts,2026-02-01,1.5\nTorts,2026-03-01,2\n",
+                }))
+                .unwrap(),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(serde_json::to_value(&imported.0).unwrap()["imported"], 2);
+
+        crate::ledger::delete_credit(State(state.clone()), cookies.clone(), Path(created_id), IfMatch::Any)
+            .await
+            .unwrap();
+
+        let (licenses, totals) = crate::ledger::totals_for_user(&conn, user.id).await.unwrap();
+        for license in &licenses {
+            let changes = hours_change::Entity::find()
+                .filter(hours_change::Column::UserStateId.eq(license.license.id))
+                .order_by_asc(hours_change::Column::Id)
+                .all(&conn)
+                .await
+                .unwrap();
+
+            // Every change picks up where the previous one left off
+            let mut hours = Credits::ZERO;
+            for change in &changes {
+                assert_eq!(change.old_hours, hours, "{} change {}", license.state_code, change.id);
+                hours = change.new_hours;
+            }
+            let sum: Credits = changes.iter().map(|c| c.new_hours - c.old_hours).sum();
+            assert_eq!(sum, totals[&license.license.id].total, "{}", license.state_code);
+        }
+
+        let sources: Vec<String> = hours_change::Entity::find()
+            .filter(hours_change::Column::UserStateId.eq(ny.id))
+            .order_by_asc(hours_change::Column::Id)
+            .all(&conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.source)
+            .collect();
+        assert_eq!(sources, vec!["registration", "credit", "credit", "import", "import", "credit"]);
+    }
+}
@@ -0,0 +1,809 @@
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::NaiveDate;
+use entity::Credits;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tower_cookies::Cookies;
+
+use crate::category::CreditCategory;
+use crate::hours_history::Source;
+use crate::ledger::{CategoryCredits, CreditEntryRequest, Entry};
+use crate::versioning::IfMatch;
+
+/// A credit entry field an import column can be mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportField {
+    Title,
+    Provider,
+    CompletedOn,
+    /// Total credits for the row
+    Credits,
+    /// Category the total counts towards when there are no specialty columns
+    Category,
+    Minutes,
+    Format,
+    ApprovalNumber,
+    Notes,
+    Ethics,
+    EliminationOfBias,
+    Technology,
+    Skills,
+    Wellness,
+}
+
+impl ImportField {
+    /// The category a per-category credit column counts towards
+    fn specialty(self) -> Option<CreditCategory> {
+        match self {
+            ImportField::Ethics => Some(CreditCategory::Ethics),
+            ImportField::EliminationOfBias => Some(CreditCategory::EliminationOfBias),
+            ImportField::Technology => Some(CreditCategory::Technology),
+            ImportField::Skills => Some(CreditCategory::Skills),
+            ImportField::Wellness => Some(CreditCategory::Wellness),
+            _ => None,
+        }
+    }
+}
+
+/// Which column header each field is read from
+pub type ColumnMapping = BTreeMap<ImportField, String>;
+
+/// Header names recognised for each field when suggesting a mapping for a plain CSV
+const FIELD_ALIASES: &[(ImportField, &[&str])] = &[
+    (ImportField::Title, &["title", "course", "course title", "course name", "activity", "activity title", "program", "program title"]),
+    (ImportField::Provider, &["provider", "sponsor", "provider name", "presented by"]),
+    (ImportField::CompletedOn, &["date", "completed", "completed on", "date completed", "completion date", "course date", "date attended"]),
+    (ImportField::Credits, &["credits", "hours", "total", "total credits", "total hours", "credit hours", "cle credits", "cpe credits"]),
+    (ImportField::Category, &["category", "credit type", "type"]),
+    (ImportField::Minutes, &["minutes", "instructional minutes"]),
+    (ImportField::Format, &["format", "delivery", "delivery method"]),
+    (ImportField::ApprovalNumber, &["approval number", "approval", "course number", "course code", "activity number"]),
+    (ImportField::Notes, &["notes", "comments"]),
+    (ImportField::Ethics, &["ethics", "ethics credits", "ethics hours", "professional responsibility"]),
+    (ImportField::EliminationOfBias, &["elimination of bias", "bias", "diversity"]),
+    (ImportField::Technology, &["technology", "technology credits", "cybersecurity"]),
+    (ImportField::Skills, &["skills", "skills credits"]),
+    (ImportField::Wellness, &["wellness", "mental health", "competence"]),
+];
+
+/// A transcript export whose columns we know.
+struct TranscriptFormat {
+    key: &'static str,
+    columns: &'static [(ImportField, &'static str)],
+}
+
+const TRANSCRIPT_FORMATS: &[TranscriptFormat] = &[
+    // State Bar of California MCLE compliance report
+    TranscriptFormat {
+        key: "ca_mcle",
+        columns: &[
+            (ImportField::Title, "Activity Title"),
+            (ImportField::Provider, "Provider"),
+            (ImportField::CompletedOn, "Date Completed"),
+            (ImportField::Credits, "Total Hours"),
+            (ImportField::Ethics, "Legal Ethics"),
+            (ImportField::EliminationOfBias, "Elimination of Bias"),
+            (ImportField::Technology, "Technology in Practice"),
+            (ImportField::Wellness, "Competence"),
+            (ImportField::ApprovalNumber, "Provider Number"),
+        ],
+    },
+    // New York CLE Board attorney transcript
+    TranscriptFormat {
+        key: "ny_cle",
+        columns: &[
+            (ImportField::Title, "Course Title"),
+            (ImportField::Provider, "Provider"),
+            (ImportField::CompletedOn, "Date Attended"),
+            (ImportField::Credits, "Total Credits"),
+            (ImportField::Ethics, "Ethics and Professionalism"),
+            (ImportField::Skills, "Skills"),
+            (ImportField::EliminationOfBias, "Diversity, Inclusion and Elimination of Bias"),
+            (ImportField::Technology, "Cybersecurity, Privacy and Data Protection"),
+            (ImportField::Format, "Format"),
+        ],
+    },
+    // State Bar of Texas MCLE transcript
+    TranscriptFormat {
+        key: "tx_mcle",
+        columns: &[
+            (ImportField::Title, "Course Title"),
+            (ImportField::Provider, "Sponsor"),
+            (ImportField::CompletedOn, "Date Completed"),
+            (ImportField::Credits, "Total Hours"),
+            (ImportField::Ethics, "Ethics Hours"),
+            (ImportField::ApprovalNumber, "Course Number"),
+        ],
+    },
+    // The Florida Bar CLE transcript
+    TranscriptFormat {
+        key: "fl_cle",
+        columns: &[
+            (ImportField::Title, "Course Title"),
+            (ImportField::CompletedOn, "Course Date"),
+            (ImportField::Credits, "General"),
+            (ImportField::Ethics, "Ethics"),
+            (ImportField::Technology, "Technology"),
+            (ImportField::Wellness, "Mental Illness Awareness"),
+            (ImportField::ApprovalNumber, "Course Number"),
+        ],
+    },
+];
+
+const CSV_FORMAT: &str = "csv";
+
+#[derive(Deserialize)]
+pub struct ImportRequest {
+    /// `csv` or the key of a known transcript format; detected from the
+    /// headers when absent
+    format: Option<String>,
+    content: String,
+    /// Overrides the mapping the format or header names suggest
+    mapping: Option<ColumnMapping>,
+    /// Scopes every imported entry to one of the user's licenses
+    license_id: Option<i32>,
+    /// Also import rows that look like entries already recorded
+    #[serde(default)]
+    include_duplicates: bool,
+}
+
+#[derive(Serialize)]
+pub struct ImportRow {
+    line: u64,
+    entry: Option<CreditEntryRequest>,
+    errors: Vec<&'static str>,
+    /// An existing ledger entry with the same title, date and credits
+    duplicate_of_entry: Option<i32>,
+    /// An earlier row of the same file with the same title, date and credits
+    duplicate_of_line: Option<u64>,
+}
+
+impl ImportRow {
+    fn is_duplicate(&self) -> bool {
+        self.duplicate_of_entry.is_some() || self.duplicate_of_line.is_some()
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportPreview {
+    format: String,
+    headers: Vec<String>,
+    mapping: ColumnMapping,
+    /// Fields the mapping must include before rows can be read
+    missing_fields: Vec<ImportField>,
+    rows: Vec<ImportRow>,
+    valid: usize,
+    invalid: usize,
+    duplicates: usize,
+}
+
+#[derive(Serialize)]
+pub struct ImportResult {
+    imported: usize,
+    skipped_duplicates: usize,
+    entry_ids: Vec<i32>,
+}
+
+/// Identifies an entry for duplicate detection
+type DuplicateKey = (String, NaiveDate, i64);
+
+fn duplicate_key(title: &str, completed_on: NaiveDate, total: Credits) -> DuplicateKey {
+    (title.trim().to_lowercase(), completed_on, total.hundredths())
+}
+
+fn suggest_mapping(headers: &[String]) -> ColumnMapping {
+    FIELD_ALIASES
+        .iter()
+        .filter_map(|(field, aliases)| {
+            headers
+                .iter()
+                .find(|h| aliases.contains(&h.trim().to_lowercase().as_str()))
+                .map(|h| (*field, h.clone()))
+        })
+        .collect()
+}
+
+fn transcript_mapping(format: &TranscriptFormat, headers: &[String]) -> ColumnMapping {
+    format
+        .columns
+        .iter()
+        .filter_map(|(field, column)| {
+            headers
+                .iter()
+                .find(|h| h.trim().eq_ignore_ascii_case(column))
+                .map(|h| (*field, h.clone()))
+        })
+        .collect()
+}
+
+/// Picks the format and column mapping for a file.
+fn resolve_mapping(
+    request: &ImportRequest,
+    headers: &[String],
+) -> Result<(String, ColumnMapping), (StatusCode, &'static str)> {
+    let transcript = match request.format.as_deref().map(str::trim) {
+        Some(key) if key.eq_ignore_ascii_case(CSV_FORMAT) => None,
+        Some(key) => Some(
+            TRANSCRIPT_FORMATS
+                .iter()
+                .find(|f| f.key.eq_ignore_ascii_case(key))
+                .ok_or((StatusCode::BAD_REQUEST, "Unknown import format"))?,
+        ),
+        // A transcript is recognised when every one of its columns is present
+        None => TRANSCRIPT_FORMATS.iter().find(|f| {
+            f.columns
+                .iter()
+                .all(|(_, column)| headers.iter().any(|h| h.trim().eq_ignore_ascii_case(column)))
+        }),
+    };
+
+    let format = transcript.map(|t| t.key).unwrap_or(CSV_FORMAT).to_string();
+    let mapping = match &request.mapping {
+        Some(mapping) => {
+            if mapping.values().any(|column| !headers.contains(column)) {
+                return Err((StatusCode::BAD_REQUEST, "Mapping refers to a column the file doesn't have"));
+            }
+            mapping.clone()
+        }
+        None => match transcript {
+            Some(transcript) => transcript_mapping(transcript, headers),
+            None => suggest_mapping(headers),
+        },
+    };
+
+    Ok((format, mapping))
+}
+
+fn missing_fields(mapping: &ColumnMapping) -> Vec<ImportField> {
+    let mut missing = Vec::new();
+    for field in [ImportField::Title, ImportField::CompletedOn] {
+        if !mapping.contains_key(&field) {
+            missing.push(field);
+        }
+    }
+    let has_credits = mapping
+        .keys()
+        .any(|f| *f == ImportField::Credits || f.specialty().is_some());
+    if !has_credits {
+        missing.push(ImportField::Credits);
+    }
+    missing
+}
+
+/// Reads one record into an entry, collecting every problem found.
+fn parse_row(
+    record: &csv::StringRecord,
+    columns: &HashMap<ImportField, usize>,
+    license_id: Option<i32>,
+) -> (Option<CreditEntryRequest>, Vec<&'static str>) {
+    let value = |field: ImportField| {
+        columns
+            .get(&field)
+            .and_then(|i| record.get(*i))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let mut errors = Vec::new();
+
+    let title = value(ImportField::Title);
+    if title.is_none() {
+        errors.push("Missing title");
+    }
+
+    let completed_on = match value(ImportField::CompletedOn) {
+        Some(date) => {
+            let parsed = crate::certificate_parser::parse_date(date);
+            if parsed.is_none() {
+                errors.push("Unrecognised completion date");
+            }
+            parsed
+        }
+        None => {
+            errors.push("Missing completion date");
+            None
+        }
+    };
+
+    let mut credits_for = |field: ImportField| {
+        let parsed = value(field).map(|v| v.parse::<Credits>());
+        match parsed {
+            Some(Ok(credits)) if credits.is_negative() => {
+                errors.push("Credits cannot be negative");
+                None
+            }
+            Some(Ok(credits)) => Some(credits),
+            Some(Err(_)) => {
+                errors.push("Invalid credit amount");
+                None
+            }
+            None => None,
+        }
+    };
+
+    let mut categories: Vec<CategoryCredits> = Vec::new();
+    for field in [
+        ImportField::Ethics,
+        ImportField::EliminationOfBias,
+        ImportField::Technology,
+        ImportField::Skills,
+        ImportField::Wellness,
+    ] {
+        if let (Some(category), Some(credits)) = (field.specialty(), credits_for(field))
+            && credits > Credits::ZERO
+        {
+            categories.push(CategoryCredits { category, credits });
+        }
+    }
+
+    // Whatever part of the total isn't in a specialty column goes to the row's category
+    let total = credits_for(ImportField::Credits);
+    let category = match value(ImportField::Category).map(|c| c.replace(' ', "_").parse::<CreditCategory>()) {
+        Some(Ok(category)) => category,
+        Some(Err(_)) => {
+            errors.push("Unknown credit category");
+            CreditCategory::General
+        }
+        None => CreditCategory::General,
+    };
+    if let Some(total) = total {
+        let specialty: Credits = categories.iter().map(|c| c.credits).sum();
+        let rest = total - specialty;
+        if rest.is_negative() {
+            errors.push("Category credits exceed the row's total");
+        } else if rest > Credits::ZERO {
+            match categories.iter_mut().find(|c| c.category == category) {
+                Some(existing) => existing.credits += rest,
+                None => categories.push(CategoryCredits { category, credits: rest }),
+            }
+        }
+    }
+
+    let minutes = match value(ImportField::Minutes).map(|m| m.parse::<u32>()) {
+        Some(Ok(minutes)) => Some(minutes),
+        Some(Err(_)) => {
+            errors.push("Invalid minutes");
+            None
+        }
+        None => None,
+    };
+
+    let (Some(title), Some(completed_on)) = (title, completed_on) else {
+        return (None, errors);
+    };
+    let entry = CreditEntryRequest {
+        title: title.to_string(),
+        provider: value(ImportField::Provider).map(str::to_string),
+        completed_on,
+        format: value(ImportField::Format).map(str::to_string),
+        approval_number: value(ImportField::ApprovalNumber).map(str::to_string),
+        notes: value(ImportField::Notes).map(str::to_string),
+        minutes,
+        license_id,
+        categories,
+        course_id: None,
+        amount_paid_cents: None,
+        currency: None,
+    };
+    if errors.is_empty()
+        && let Err((_, message)) = crate::ledger::check_entry(&entry)
+    {
+        errors.push(message);
+    }
+
+    (Some(entry), errors)
+}
+
+/// The line a record starting at `byte` is on, 1-based and counting the
+/// header as in a spreadsheet. The reader's own line count stops short of any
+/// blank lines it skipped before the record.
+fn line_at(content: &str, byte: u64) -> u64 {
+    let byte = (byte as usize).min(content.len());
+    let start = content[byte..]
+        .find(|c| c != '\r' && c != '\n')
+        .map_or(content.len(), |offset| byte + offset);
+    content[..start].matches('\n').count() as u64 + 1
+}
+
+/// Parses an import against the user's existing ledger without saving anything.
+fn build_preview(request: &ImportRequest, existing: &[Entry]) -> Result<ImportPreview, (StatusCode, &'static str)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(request.content.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Could not read the file's header row"))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    if headers.iter().all(|h| h.is_empty()) {
+        return Err((StatusCode::BAD_REQUEST, "File has no header row"));
+    }
+
+    let (format, mapping) = resolve_mapping(request, &headers)?;
+    let mut preview = ImportPreview {
+        format,
+        missing_fields: missing_fields(&mapping),
+        headers,
+        mapping,
+        rows: Vec::new(),
+        valid: 0,
+        invalid: 0,
+        duplicates: 0,
+    };
+    if !preview.missing_fields.is_empty() {
+        return Ok(preview);
+    }
+
+    let columns: HashMap<ImportField, usize> = preview
+        .mapping
+        .iter()
+        .filter_map(|(field, column)| Some((*field, preview.headers.iter().position(|h| h == column)?)))
+        .collect();
+
+    let recorded: HashMap<DuplicateKey, i32> = existing
+        .iter()
+        .map(|e| {
+            let total = e.categories.iter().map(|c| c.credits).sum();
+            (duplicate_key(&e.entry.title, e.entry.completed_on, total), e.entry.id)
+        })
+        .collect();
+    let mut seen: HashMap<DuplicateKey, u64> = HashMap::new();
+
+    let mut last_line = 1;
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                last_line = e
+                    .position()
+                    .map(|p| line_at(&request.content, p.byte()))
+                    .unwrap_or(last_line + 1);
+                preview.rows.push(ImportRow {
+                    line: last_line,
+                    entry: None,
+                    errors: vec!["Malformed row"],
+                    duplicate_of_entry: None,
+                    duplicate_of_line: None,
+                });
+                preview.invalid += 1;
+                continue;
+            }
+        };
+        let line = record
+            .position()
+            .map(|p| line_at(&request.content, p.byte()))
+            .unwrap_or(last_line + 1);
+        last_line = line;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let (entry, errors) = parse_row(&record, &columns, request.license_id);
+
+        let mut row = ImportRow {
+            line,
+            entry,
+            errors,
+            duplicate_of_entry: None,
+            duplicate_of_line: None,
+        };
+        if let Some(entry) = row.entry.as_ref().filter(|_| row.errors.is_empty()) {
+            let total = entry.categories.iter().map(|c| c.credits).sum();
+            let key = duplicate_key(&entry.title, entry.completed_on, total);
+            row.duplicate_of_entry = recorded.get(&key).copied();
+            row.duplicate_of_line = seen.get(&key).copied();
+            seen.entry(key).or_insert(line);
+        }
+
+        if !row.errors.is_empty() {
+            preview.invalid += 1;
+        } else {
+            preview.valid += 1;
+            if row.is_duplicate() {
+                preview.duplicates += 1;
+            }
+        }
+        preview.rows.push(row);
+    }
+
+    Ok(preview)
+}
+
+pub async fn preview_import(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Json(request): Json<ImportRequest>,
+) -> Result<Json<ImportPreview>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    crate::ledger::check_license(&state.conn, user.id, request.license_id).await?;
+
+    let existing = crate::ledger::entries_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(build_preview(&request, &existing)?))
+}
+
+/// Imports every row of a file in one transaction. Nothing is saved if any
+/// row is invalid; duplicates are skipped unless `include_duplicates` is set.
+pub async fn commit_import(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    if_match: IfMatch,
+    Json(request): Json<ImportRequest>,
+) -> Result<Json<ImportResult>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    crate::ledger::check_license(&state.conn, user.id, request.license_id).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    let existing = crate::ledger::entries_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let preview = build_preview(&request, &existing)?;
+
+    if !preview.missing_fields.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Mapping needs title, completion date and credit columns"));
+    }
+    if preview.invalid > 0 {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "Import has rows with errors"));
+    }
+
+    let licenses = crate::licenses::for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let mut totals = crate::ledger::totals(&licenses, &existing);
+
+    let mut result = ImportResult {
+        imported: 0,
+        skipped_duplicates: 0,
+        entry_ids: Vec::new(),
+    };
+    for row in preview.rows {
+        if row.is_duplicate() && !request.include_duplicates {
+            result.skipped_duplicates += 1;
+            continue;
+        }
+        let Some(entry) = row.entry else { continue };
+        let saved = crate::ledger::insert_entry(&txn, user.id, entry)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to import credits"))?;
+
+        let before = totals.clone();
+        crate::ledger::add_to_totals(&mut totals, &licenses, &saved);
+        crate::hours_history::record_changes(&txn, user.id, &saved.entry, &before, &totals, Source::Import)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to import credits"))?;
+        result.imported += 1;
+        result.entry_ids.push(saved.entry.id);
+    }
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to import credits"))?;
+
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(format: Option<&str>, content: &str) -> ImportRequest {
+        ImportRequest {
+            format: format.map(str::to_string),
+            content: content.to_string(),
+            mapping: None,
+            license_id: None,
+            include_duplicates: false,
+        }
+    }
+
+    /// Parses the first data row of `content` with the mapping its format suggests
+    fn first_row(format: Option<&str>, content: &str) -> (String, Option<CreditEntryRequest>, Vec<&'static str>) {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let headers: Vec<String> = reader.headers().unwrap().iter().map(str::to_string).collect();
+        let (format, mapping) = resolve_mapping(&request(format, content), &headers).unwrap();
+        let columns = mapping
+            .iter()
+            .map(|(field, column)| (*field, headers.iter().position(|h| h == column).unwrap()))
+            .collect();
+        let record = reader.records().next().unwrap().unwrap();
+        let (entry, errors) = parse_row(&record, &columns, Some(7));
+        (format, entry, errors)
+    }
+
+    fn categories(entry: &CreditEntryRequest) -> Vec<(CreditCategory, Credits)> {
+        entry.categories.iter().map(|c| (c.category, c.credits)).collect()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn reads_a_plain_csv_by_header_names() {
+        let (format, entry, errors) = first_row(
+            None,
+            "Course Title,Sponsor,Date,Hours,Category,Minutes,Notes\n\
+             Trial Advocacy,NITA,2025-03-04,3.5,skills,210,Day one\n",
+        );
+        let entry = entry.unwrap();
+
+        assert_eq!(format, "csv");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entry.title, "Trial Advocacy");
+        assert_eq!(entry.provider.as_deref(), Some("NITA"));
+        assert_eq!(entry.completed_on, date(2025, 3, 4));
+        assert_eq!(entry.minutes, Some(210));
+        assert_eq!(entry.notes.as_deref(), Some("Day one"));
+        assert_eq!(entry.license_id, Some(7));
+        assert_eq!(categories(&entry), vec![(CreditCategory::Skills, Credits::from_hundredths(350))]);
+    }
+
+    #[test]
+    fn reads_a_california_transcript() {
+        let (format, entry, errors) = first_row(
+            None,
+            "Activity Title,Provider,Date Completed,Total Hours,Legal Ethics,Elimination of Bias,Technology in Practice,Competence,Provider Number\n\
+             Ethics Update,CEB,01/15/2025,4,1,0.5,0,0.5,1234\n",
+        );
+        let entry = entry.unwrap();
+
+        assert_eq!(format, "ca_mcle");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entry.completed_on, date(2025, 1, 15));
+        assert_eq!(entry.approval_number.as_deref(), Some("1234"));
+        assert_eq!(
+            categories(&entry),
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(1)),
+                (CreditCategory::EliminationOfBias, Credits::from_hundredths(50)),
+                (CreditCategory::Wellness, Credits::from_hundredths(50)),
+                (CreditCategory::General, Credits::from_whole(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_a_new_york_transcript() {
+        let (format, entry, errors) = first_row(
+            None,
+            "Course Title,Provider,Date Attended,Total Credits,Ethics and Professionalism,Skills,\"Diversity, Inclusion and Elimination of Bias\",\"Cybersecurity, Privacy and Data Protection\",Format\n\
+             Deposition Skills,NYSBA,\"March 4, 2025\",7,1,6,0,0,Live\n",
+        );
+        let entry = entry.unwrap();
+
+        assert_eq!(format, "ny_cle");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entry.format.as_deref(), Some("Live"));
+        assert_eq!(
+            categories(&entry),
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(1)),
+                (CreditCategory::Skills, Credits::from_whole(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_a_texas_transcript() {
+        let (format, entry, errors) = first_row(
+            None,
+            "Course Title,Sponsor,Date Completed,Total Hours,Ethics Hours,Course Number\n\
+             Oil and Gas Law,TexasBarCLE,2025-02-20,6.75,1.25,900123\n",
+        );
+        let entry = entry.unwrap();
+
+        assert_eq!(format, "tx_mcle");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entry.provider.as_deref(), Some("TexasBarCLE"));
+        assert_eq!(entry.approval_number.as_deref(), Some("900123"));
+        assert_eq!(
+            categories(&entry),
+            vec![
+                (CreditCategory::Ethics, Credits::from_hundredths(125)),
+                (CreditCategory::General, Credits::from_hundredths(550)),
+            ]
+        );
+    }
+
+    #[test]
+    fn florida_general_column_is_the_total() {
+        // The Florida Bar reports every hour under General, with the
+        // specialty hours broken out of it
+        let (format, entry, errors) = first_row(
+            None,
+            "Course Title,Course Date,General,Ethics,Technology,Mental Illness Awareness,Course Number\n\
+             Florida Practice Update,06/10/2025,10,2,1,0,2505123N\n",
+        );
+        let entry = entry.unwrap();
+
+        assert_eq!(format, "fl_cle");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entry.provider, None);
+        assert_eq!(
+            categories(&entry),
+            vec![
+                (CreditCategory::Ethics, Credits::from_whole(2)),
+                (CreditCategory::Technology, Credits::from_whole(1)),
+                (CreditCategory::General, Credits::from_whole(7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_every_problem_in_a_row() {
+        let (_, entry, errors) = first_row(
+            Some("csv"),
+            "Title,Date,Credits,Ethics,Category,Minutes\n\
+             ,someday,1.234,-1,basketry,ninety\n",
+        );
+
+        assert!(entry.is_none());
+        assert_eq!(
+            errors,
+            vec![
+                "Missing title",
+                "Unrecognised completion date",
+                "Credits cannot be negative",
+                "Invalid credit amount",
+                "Unknown credit category",
+                "Invalid minutes",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_specialty_credits_above_the_total() {
+        let (_, entry, errors) = first_row(
+            Some("tx_mcle"),
+            "Course Title,Sponsor,Date Completed,Total Hours,Ethics Hours,Course Number\n\
+             Ethics Intensive,TexasBarCLE,2025-02-20,1,2,1\n",
+        );
+
+        assert!(entry.is_some());
+        assert_eq!(errors, vec!["Category credits exceed the row's total"]);
+    }
+
+    #[test]
+    fn reports_rows_by_file_line() {
+        let content = "Title,Date,Credits\n\
+                       Evidence,2025-01-10,2\n\
+                       \n\
+                       \"Contracts,\nPart Two\",2025-01-11,1\n\
+                       ,2025-01-12,1\n\
+                       Evidence,2025-01-10,2\n";
+        let preview = build_preview(&request(None, content), &[]).unwrap();
+        let lines: Vec<(u64, Vec<&str>, Option<u64>)> = preview
+            .rows
+            .iter()
+            .map(|r| (r.line, r.errors.clone(), r.duplicate_of_line))
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                (2, vec![], None),
+                (4, vec![], None),
+                (6, vec!["Missing title"], None),
+                (7, vec![], Some(2)),
+            ]
+        );
+        assert_eq!((preview.valid, preview.invalid, preview.duplicates), (3, 1, 1));
+
+        let crlf = build_preview(&request(None, &content.replace('\n', "\r\n")), &[]).unwrap();
+        let crlf_lines: Vec<u64> = crlf.rows.iter().map(|r| r.line).collect();
+        assert_eq!(crlf_lines, vec![2, 4, 6, 7]);
+    }
+}
@@ -0,0 +1,951 @@
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use entity::{Credits, course, provider};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set, TransactionTrait, TryIntoModel,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use tower_cookies::Cookies;
+
+use crate::catalog::{Approval, ApprovalRecord, CatalogCourse, CourseFormat, CourseResponse};
+use crate::currency::Currency;
+
+/// Source value for courses that came from a provider feed
+pub const SOURCE_FEED: &str = "feed";
+
+/// The kind of document a provider publishes its course list in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeserializeFromStr, SerializeDisplay)]
+pub enum FeedFormat {
+    Csv,
+    Json,
+    Ical,
+}
+
+#[derive(Debug)]
+pub struct ParseFeedFormatError;
+
+impl fmt::Display for ParseFeedFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Feed format must be csv, json or ical")
+    }
+}
+
+impl std::error::Error for ParseFeedFormatError {}
+
+impl FromStr for FeedFormat {
+    type Err = ParseFeedFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(FeedFormat::Csv),
+            "json" => Ok(FeedFormat::Json),
+            "ical" | "ics" => Ok(FeedFormat::Ical),
+            _ => Err(ParseFeedFormatError),
+        }
+    }
+}
+
+impl fmt::Display for FeedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FeedFormat::Csv => "csv",
+            FeedFormat::Json => "json",
+            FeedFormat::Ical => "ical",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FeedFormat {
+    /// Guesses the format from a file's extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        path.rsplit_once('.').and_then(|(_, ext)| ext.parse().ok())
+    }
+
+    fn adapter(self) -> &'static dyn FeedAdapter {
+        match self {
+            FeedFormat::Csv => &CsvAdapter,
+            FeedFormat::Json => &JsonAdapter,
+            FeedFormat::Ical => &IcalAdapter,
+        }
+    }
+}
+
+/// One course from a feed, keyed by lowercased field name before it's
+/// normalized. Adapters only have to get their format into this shape.
+type FeedRecord = HashMap<String, String>;
+
+/// Turns a provider's document into records.
+trait FeedAdapter: Sync {
+    fn records(&self, content: &str) -> Result<Vec<FeedRecord>, String>;
+}
+
+struct CsvAdapter;
+
+impl FeedAdapter for CsvAdapter {
+    fn records(&self, content: &str) -> Result<Vec<FeedRecord>, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|h| h.to_lowercase())
+            .collect();
+
+        reader
+            .records()
+            .map(|row| {
+                let row = row.map_err(|e| e.to_string())?;
+                Ok(headers
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(str::to_string))
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+struct JsonAdapter;
+
+impl FeedAdapter for JsonAdapter {
+    /// Accepts an array of course objects, or an object with a `courses` array.
+    fn records(&self, content: &str) -> Result<Vec<FeedRecord>, String> {
+        let value: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        let items = match &value {
+            serde_json::Value::Array(items) => items,
+            serde_json::Value::Object(object) => object
+                .get("courses")
+                .and_then(|c| c.as_array())
+                .ok_or("Expected a `courses` array")?,
+            _ => return Err("Expected an array of courses".to_string()),
+        };
+
+        items
+            .iter()
+            .map(|item| {
+                let object = item.as_object().ok_or("Each course must be an object")?;
+                Ok(object
+                    .iter()
+                    .map(|(key, value)| (key.to_lowercase().replace('_', " "), json_text(value)))
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+/// Flattens a JSON value to the text a CSV cell would hold; lists are joined
+/// with semicolons.
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items.iter().map(json_text).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+struct IcalAdapter;
+
+impl FeedAdapter for IcalAdapter {
+    /// Reads each `VEVENT` as a scheduled course. Credit hours and prices come
+    /// from `X-CREDITS`, `X-PRICE`, `X-MEMBER-PRICE` and `X-CURRENCY` when the
+    /// provider includes them.
+    fn records(&self, content: &str) -> Result<Vec<FeedRecord>, String> {
+        // Long lines are folded onto following lines that start with a space
+        let unfolded = content.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+        if !unfolded.contains("BEGIN:VCALENDAR") {
+            return Err("Not an iCalendar file".to_string());
+        }
+
+        let mut records = Vec::new();
+        let mut event: Option<FeedRecord> = None;
+        let mut start: Option<NaiveDateTime> = None;
+        let mut end: Option<NaiveDateTime> = None;
+        for line in unfolded.lines() {
+            let Some((property, value)) = line.split_once(':') else {
+                continue;
+            };
+            let name = property.split(';').next().unwrap_or_default().to_uppercase();
+            match (name.as_str(), event.as_mut()) {
+                ("BEGIN", _) if value == "VEVENT" => {
+                    event = Some(FeedRecord::new());
+                    start = None;
+                    end = None;
+                }
+                ("END", Some(_)) if value == "VEVENT" => {
+                    let mut finished = event.take().unwrap_or_default();
+                    if let Some(start) = start {
+                        finished.insert("date".to_string(), start.date().to_string());
+                        if let Some(end) = end.filter(|end| *end > start) {
+                            let minutes = (end - start).num_minutes();
+                            finished.insert("minutes".to_string(), minutes.to_string());
+                        }
+                    }
+                    records.push(finished);
+                }
+                ("SUMMARY", Some(e)) => {
+                    e.insert("title".to_string(), ical_text(value));
+                }
+                ("DESCRIPTION", Some(e)) => {
+                    e.insert("summary".to_string(), ical_text(value));
+                }
+                ("URL", Some(e)) => {
+                    e.insert("url".to_string(), value.to_string());
+                }
+                ("CATEGORIES", Some(e)) => {
+                    e.insert("topics".to_string(), ical_text(value).replace(',', ";"));
+                }
+                ("LOCATION", Some(e)) => {
+                    let location = ical_text(value).to_lowercase();
+                    let format = if location.contains("webinar") {
+                        "live_webinar"
+                    } else if location.contains("online") || location.contains("virtual") {
+                        "online"
+                    } else {
+                        "in_person"
+                    };
+                    e.insert("format".to_string(), format.to_string());
+                }
+                ("X-CREDITS", Some(e)) => {
+                    e.insert("hours".to_string(), value.to_string());
+                }
+                ("X-PRICE", Some(e)) => {
+                    e.insert("price".to_string(), value.to_string());
+                }
+                ("X-MEMBER-PRICE", Some(e)) => {
+                    e.insert("member price".to_string(), value.to_string());
+                }
+                ("X-CURRENCY", Some(e)) => {
+                    e.insert("currency".to_string(), value.to_string());
+                }
+                ("X-APPROVALS", Some(e)) => {
+                    e.insert("approvals".to_string(), value.replace(',', ";"));
+                }
+                ("DTSTART", Some(_)) => start = ical_datetime(value),
+                ("DTEND", Some(_)) => end = ical_datetime(value),
+                _ => {}
+            }
+        }
+        Ok(records)
+    }
+}
+
+fn ical_text(value: &str) -> String {
+    value
+        .replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Reads `DTSTART`/`DTEND` values, which are either dates or local or UTC
+/// date-times. Time zones are ignored since only the date and length matter.
+fn ical_datetime(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim().trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+/// Names providers use for each course field, lowercased
+const FIELD_ALIASES: &[(&str, &[&str])] = &[
+    ("title", &["title", "course", "course title", "course name", "name", "program"]),
+    ("summary", &["summary", "description", "overview"]),
+    ("hours", &["hours", "credits", "credit hours", "cle hours", "cle credits", "cpe credits"]),
+    ("minutes", &["minutes", "duration", "duration minutes", "length"]),
+    ("price", &["price", "cost", "fee", "tuition", "price cents", "non-member price", "nonmember price"]),
+    ("member price", &["member price", "members price", "member fee", "member cost"]),
+    ("currency", &["currency", "currency code"]),
+    ("url", &["url", "link", "registration url", "course url"]),
+    ("topics", &["topics", "topic", "subject", "subjects", "practice area"]),
+    ("format", &["format", "formats", "delivery", "delivery method"]),
+    ("date", &["date", "start date", "starts on", "session date"]),
+    ("approvals", &["approvals", "approved in", "states", "jurisdictions"]),
+];
+
+fn field<'a>(record: &'a FeedRecord, name: &str) -> Option<&'a str> {
+    let aliases = FIELD_ALIASES
+        .iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, aliases)| *aliases)
+        .unwrap_or_default();
+    aliases
+        .iter()
+        .find_map(|alias| record.get(*alias))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+fn list(value: Option<&str>) -> Vec<&str> {
+    value
+        .unwrap_or_default()
+        .split([';', '|'])
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Reads prices like `Free`, `$1,299.50`, `€45` or `99`.
+fn parse_price(value: &str) -> Option<i32> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, '$' | '€' | '£' | ','))
+        .collect();
+    if cleaned.trim().eq_ignore_ascii_case("free") {
+        return Some(0);
+    }
+    cleaned
+        .parse::<Credits>()
+        .ok()
+        .filter(|p| !p.is_negative())
+        .and_then(|p| i32::try_from(p.hundredths()).ok())
+}
+
+/// A feed course ready to be saved.
+#[derive(Clone)]
+pub struct FeedCourse {
+    pub title: String,
+    pub summary: Option<String>,
+    pub hours: Credits,
+    pub duration_minutes: i32,
+    pub price_cents: i32,
+    pub member_price_cents: Option<i32>,
+    pub currency: Currency,
+    pub url: String,
+    pub topics: Vec<String>,
+    pub formats: Vec<CourseFormat>,
+    pub starts_on: Option<NaiveDate>,
+    pub approvals: Vec<ApprovalRecord>,
+}
+
+impl FeedCourse {
+    fn key(&self) -> (String, Option<NaiveDate>) {
+        (self.title.to_lowercase(), self.starts_on)
+    }
+
+    /// Whether a stored course already matches this one.
+    fn matches(&self, existing: &CatalogCourse, states: &HashMap<String, i32>) -> bool {
+        let c = &existing.course;
+        let approvals: Vec<ApprovalRecord> = existing
+            .approvals
+            .iter()
+            .filter_map(|a| {
+                Some(ApprovalRecord {
+                    state_id: *states.get(&a.state_code)?,
+                    profession: a.profession.clone(),
+                    approval_number: a.approval_number.clone(),
+                    valid_from: a.valid_from,
+                    valid_until: a.valid_until,
+                    credits: a.credits.clone(),
+                })
+            })
+            .collect();
+        c.summary == self.summary
+            && c.credits == self.hours
+            && c.duration_minutes == self.duration_minutes
+            && c.price_cents == self.price_cents
+            && c.member_price_cents == self.member_price_cents
+            && existing.currency() == self.currency
+            && c.url == self.url
+            && existing.topics == self.topics
+            && existing.formats == self.formats
+            && approvals == self.approvals
+            && c.retired_at.is_none()
+    }
+}
+
+/// Validates and normalizes one record; states map two-letter codes to ids.
+fn normalize(record: &FeedRecord, states: &HashMap<String, i32>) -> Result<FeedCourse, String> {
+    let title = field(record, "title").ok_or("Title is required")?.to_string();
+
+    let hours: Credits = field(record, "hours")
+        .ok_or("Hours are required")?
+        .parse()
+        .map_err(|_| "Hours must be a number up to 100000 with at most two decimal places")?;
+    if hours <= Credits::ZERO {
+        return Err("Hours must be greater than zero".to_string());
+    }
+    let duration_minutes = match field(record, "minutes") {
+        Some(minutes) => minutes
+            .parse::<i32>()
+            .ok()
+            .filter(|m| *m > 0)
+            .ok_or("Duration must be a whole number of minutes")?,
+        None => hours
+            .hundredths()
+            .checked_mul(60)
+            .and_then(|m| i32::try_from(m / 100).ok())
+            .ok_or("Hours are too large")?,
+    };
+
+    let price_cents = match field(record, "price") {
+        Some(price) => parse_price(price).ok_or("Price must be an amount or Free")?,
+        None => 0,
+    };
+    let member_price_cents = match field(record, "member price") {
+        Some(price) => Some(parse_price(price).ok_or("Member price must be an amount or Free")?),
+        None => None,
+    };
+    if member_price_cents.is_some_and(|m| m > price_cents) {
+        return Err("Member price is above the regular price".to_string());
+    }
+    let currency = match field(record, "currency") {
+        Some(code) => code.parse().map_err(|_| format!("Unsupported currency {}", code))?,
+        None => Currency::default(),
+    };
+
+    let url = field(record, "url").ok_or("URL is required")?.to_string();
+    crate::admin::check_url(&url).map_err(|(_, message)| message.to_string())?;
+
+    // Providers use their own topic names; anything we don't file under is dropped
+    let mut topics: Vec<String> = Vec::new();
+    for topic in list(field(record, "topics")).into_iter().filter_map(crate::catalog::known_topic) {
+        if !topics.iter().any(|t| t == topic) {
+            topics.push(topic.to_string());
+        }
+    }
+    if topics.is_empty() {
+        return Err("No known topic".to_string());
+    }
+
+    let mut formats = Vec::new();
+    for format in list(field(record, "format")) {
+        let format: CourseFormat = format.parse().map_err(|_| format!("Unknown format {}", format))?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    if formats.is_empty() {
+        formats.push(CourseFormat::Online);
+    }
+
+    let starts_on = match field(record, "date") {
+        Some(date) => Some(crate::certificate_parser::parse_date(date).ok_or("Date is not recognised")?),
+        None => None,
+    };
+
+    // Approvals are state codes, optionally with a profession and the
+    // jurisdiction's approval number, e.g. `NY`, `TX:CPA` or `NY:Attorney:2026-0412`
+    let mut approvals: Vec<ApprovalRecord> = Vec::new();
+    for approval in list(field(record, "approvals")) {
+        let mut parts = approval.splitn(3, ':').map(str::trim);
+        let code = parts.next().unwrap_or_default();
+        let profession = parts.next().filter(|p| !p.is_empty()).unwrap_or("Attorney");
+        let state_id = states
+            .get(&code.to_uppercase())
+            .ok_or(format!("Unknown state {}", code))?;
+        let profession: crate::profession::Profession =
+            profession.parse().map_err(|_| format!("Unknown profession {}", profession))?;
+        let approval = ApprovalRecord {
+            state_id: *state_id,
+            profession: profession.to_string(),
+            approval_number: parts.next().filter(|n| !n.is_empty()).map(str::to_string),
+            valid_from: None,
+            valid_until: None,
+            credits: Vec::new(),
+        };
+        if !approvals.iter().any(|a| a.overlaps(&approval)) {
+            approvals.push(approval);
+        }
+    }
+
+    Ok(FeedCourse {
+        title,
+        summary: field(record, "summary").map(str::to_string),
+        hours,
+        duration_minutes,
+        price_cents,
+        member_price_cents,
+        currency,
+        url,
+        topics,
+        formats,
+        starts_on,
+        approvals,
+    })
+}
+
+#[derive(Serialize)]
+pub struct SkippedRow {
+    /// Position of the course in the feed, starting at 1
+    row: usize,
+    title: Option<String>,
+    error: String,
+}
+
+#[derive(Default, Serialize)]
+pub struct IngestSummary {
+    provider: String,
+    format: Option<FeedFormat>,
+    dry_run: bool,
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    retired: usize,
+    duplicates: usize,
+    skipped: Vec<SkippedRow>,
+}
+
+impl fmt::Display for IngestSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}{}: {} created, {} updated, {} unchanged, {} retired, {} duplicates, {} skipped",
+            self.provider,
+            if self.dry_run { " (dry run)" } else { "" },
+            self.created,
+            self.updated,
+            self.unchanged,
+            self.retired,
+            self.duplicates,
+            self.skipped.len()
+        )?;
+        for skipped in &self.skipped {
+            writeln!(
+                f,
+                "  row {}{}: {}",
+                skipped.row,
+                skipped.title.as_deref().map(|t| format!(" ({})", t)).unwrap_or_default(),
+                skipped.error
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads a provider's feed into the catalog in one transaction. Courses are
+/// matched on provider, title and date; feed courses missing from the feed
+/// are retired. With `dry_run` the summary is worked out but nothing is saved.
+pub async fn ingest(
+    conn: &DatabaseConnection,
+    provider_name: &str,
+    format: FeedFormat,
+    content: &str,
+    dry_run: bool,
+) -> Result<IngestSummary, IngestError> {
+    let provider_name = provider_name.trim();
+    if provider_name.is_empty() {
+        return Err(IngestError::Invalid("Provider name is required"));
+    }
+    let records = format.adapter().records(content).map_err(IngestError::Unreadable)?;
+
+    let states: HashMap<String, i32> = entity::state::Entity::find()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|s| (s.name, s.id))
+        .collect();
+
+    let mut summary = IngestSummary {
+        provider: provider_name.to_string(),
+        format: Some(format),
+        dry_run,
+        ..Default::default()
+    };
+    let mut courses = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, record) in records.iter().enumerate() {
+        match normalize(record, &states) {
+            Ok(course) if !seen.insert(course.key()) => summary.duplicates += 1,
+            Ok(course) => courses.push(course),
+            Err(error) => summary.skipped.push(SkippedRow {
+                row: index + 1,
+                title: field(record, "title").map(str::to_string),
+                error,
+            }),
+        }
+    }
+
+    let txn = conn.begin().await?;
+
+    let provider = match provider::Entity::find()
+        .filter(provider::Column::Name.eq(provider_name))
+        .one(&txn)
+        .await?
+    {
+        Some(p) if p.retired_at.is_some() => {
+            return Err(IngestError::Invalid("Provider has been retired"));
+        }
+        Some(p) => p,
+        None => {
+            let created = provider::ActiveModel {
+                name: Set(provider_name.to_string()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            crate::catalog::record_audit(&txn, None, "provider", created.id, "create", None, Some(&created.name))
+                .await?;
+            created
+        }
+    };
+
+    let mut existing: HashMap<(String, Option<NaiveDate>), CatalogCourse> =
+        crate::catalog::load_provider_courses(&txn, provider.id)
+            .await?
+            .into_iter()
+            .map(|c| ((c.course.title.to_lowercase(), c.course.starts_on), c))
+            .collect();
+    let provider_rating = crate::reviews::provider_ratings(&txn, &[provider.id])
+        .await?
+        .by_provider
+        .get(&provider.id)
+        .copied();
+    let state_codes: HashMap<i32, String> = states.iter().map(|(code, id)| (*id, code.clone())).collect();
+
+    for feed_course in &courses {
+        let current = existing.remove(&feed_course.key());
+        if current.as_ref().is_some_and(|c| feed_course.matches(c, &states)) {
+            summary.unchanged += 1;
+            continue;
+        }
+        let saved = save_course(&txn, current.as_ref(), &provider, feed_course).await?;
+        // The audit snapshot is built from what was saved rather than re-read
+        let after = CatalogCourse {
+            course: saved,
+            provider: provider.clone(),
+            topics: feed_course.topics.clone(),
+            formats: feed_course.formats.clone(),
+            approvals: feed_course
+                .approvals
+                .iter()
+                .filter_map(|a| {
+                    Some(Approval {
+                        state_code: state_codes.get(&a.state_id)?.clone(),
+                        profession: a.profession.clone(),
+                        approval_number: a.approval_number.clone(),
+                        valid_from: a.valid_from,
+                        valid_until: a.valid_until,
+                        credits: a.credits.clone(),
+                    })
+                })
+                .collect(),
+            rating: current.as_ref().and_then(|c| c.rating),
+            provider_rating,
+        };
+        crate::catalog::record_audit(
+            &txn,
+            None,
+            "course",
+            after.course.id,
+            if current.is_some() { "update" } else { "create" },
+            current.as_ref().map(CourseResponse::from).as_ref(),
+            Some(&CourseResponse::from(&after)),
+        )
+        .await?;
+        if current.is_some() {
+            summary.updated += 1;
+        } else {
+            summary.created += 1;
+        }
+    }
+
+    // Whatever is left was in an earlier feed but not this one
+    for vanished in existing.values() {
+        if vanished.course.source != SOURCE_FEED || vanished.course.retired_at.is_some() {
+            continue;
+        }
+        let mut active: course::ActiveModel = vanished.course.clone().into();
+        active.retired_at = Set(Some(Utc::now()));
+        let after = CatalogCourse {
+            course: active.update(&txn).await?,
+            ..vanished.clone()
+        };
+        crate::catalog::record_audit(
+            &txn,
+            None,
+            "course",
+            vanished.course.id,
+            "retire",
+            Some(&CourseResponse::from(vanished)),
+            Some(&CourseResponse::from(&after)),
+        )
+        .await?;
+        summary.retired += 1;
+    }
+
+    if dry_run {
+        txn.rollback().await?;
+    } else {
+        txn.commit().await?;
+    }
+    Ok(summary)
+}
+
+/// Creates or updates a course from the feed, un-retiring it if it had been
+/// dropped from an earlier feed.
+async fn save_course<C: ConnectionTrait>(
+    conn: &C,
+    current: Option<&CatalogCourse>,
+    provider: &provider::Model,
+    feed_course: &FeedCourse,
+) -> Result<course::Model, DbErr> {
+    let mut active = match current {
+        Some(c) => c.course.clone().into(),
+        None => course::ActiveModel {
+            provider_id: Set(provider.id),
+            starts_on: Set(feed_course.starts_on),
+            ..Default::default()
+        },
+    };
+    active.title = Set(feed_course.title.clone());
+    active.summary = Set(feed_course.summary.clone());
+    active.credits = Set(feed_course.hours);
+    active.duration_minutes = Set(feed_course.duration_minutes);
+    active.price_cents = Set(feed_course.price_cents);
+    active.member_price_cents = Set(feed_course.member_price_cents);
+    active.currency = Set(feed_course.currency.to_string());
+    active.url = Set(feed_course.url.clone());
+    active.source = Set(SOURCE_FEED.to_string());
+    active.retired_at = Set(None);
+    let saved = active.save(conn).await?.try_into_model()?;
+
+    crate::catalog::replace_course_details(
+        conn,
+        saved.id,
+        &feed_course.topics,
+        &feed_course.formats,
+        &feed_course.approvals,
+    )
+    .await?;
+    Ok(saved)
+}
+
+#[derive(Debug)]
+pub enum IngestError {
+    /// The feed couldn't be parsed in the given format
+    Unreadable(String),
+    Invalid(&'static str),
+    Database(DbErr),
+}
+
+impl From<DbErr> for IngestError {
+    fn from(e: DbErr) -> Self {
+        IngestError::Database(e)
+    }
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::Unreadable(message) => write!(f, "Feed could not be read: {}", message),
+            IngestError::Invalid(message) => write!(f, "{}", message),
+            IngestError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+/// Runs `ingest [--dry-run] [--format csv|json|ical] <provider> <file>` from
+/// the command line, printing the summary.
+pub async fn run_cli(conn: &DatabaseConnection, args: &[String]) -> anyhow::Result<()> {
+    let mut dry_run = false;
+    let mut format = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--format" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--format needs a value"))?;
+                format = Some(value.parse::<FeedFormat>()?);
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    let [provider_name, path] = positional[..] else {
+        anyhow::bail!("Usage: coffee_overflow ingest [--dry-run] [--format csv|json|ical] <provider> <file>");
+    };
+    let format = format
+        .or_else(|| FeedFormat::from_path(path))
+        .ok_or_else(|| anyhow::anyhow!("Can't tell the feed format from {}; pass --format", path))?;
+
+    let content = tokio::fs::read_to_string(path).await?;
+    let summary = ingest(conn, provider_name, format, &content, dry_run).await?;
+    print!("{}", summary);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct IngestRequest {
+    pub provider: String,
+    pub format: FeedFormat,
+    pub content: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Ingests a feed uploaded by an admin.
+pub async fn ingest_feed(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Json(request): Json<IngestRequest>,
+) -> Result<Json<IngestSummary>, (StatusCode, &'static str)> {
+    crate::auth::current_admin(&state.conn, &cookies).await?;
+
+    match ingest(&state.conn, &request.provider, request.format, &request.content, request.dry_run).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(IngestError::Unreadable(_)) => Err((StatusCode::BAD_REQUEST, "Feed could not be read")),
+        Err(IngestError::Invalid(message)) => Err((StatusCode::BAD_REQUEST, message)),
+        Err(IngestError::Database(_)) => Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to ingest feed")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::PaginatorTrait;
+
+    const BARPREP: &str = include_str!("../fixtures/feeds/barprep_courses.json");
+    const LEXCLE: &str = include_str!("../fixtures/feeds/lexcle_courses.csv");
+    const SEMINARS: &str = include_str!("../fixtures/feeds/state_bar_seminars.ics");
+
+    fn skipped(summary: &IngestSummary) -> Vec<(usize, Option<&str>)> {
+        summary.skipped.iter().map(|s| (s.row, s.title.as_deref())).collect()
+    }
+
+    /// Titles of the provider's courses, and whether each has been retired
+    async fn course_titles(conn: &DatabaseConnection, provider_name: &str) -> Vec<(String, bool)> {
+        crate::catalog::load_courses(conn, true)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|c| c.provider.name == provider_name)
+            .map(|c| (c.course.title, c.course.retired_at.is_some()))
+            .collect()
+    }
+
+    #[test]
+    fn prices_must_fit_the_price_column() {
+        assert_eq!(parse_price("Free"), Some(0));
+        assert_eq!(parse_price("$1,299.50"), Some(129_950));
+        assert_eq!(parse_price("€99,999.99"), Some(9_999_999));
+        assert_eq!(parse_price("$21,474,836.48"), None);
+        assert_eq!(parse_price("-5"), None);
+    }
+
+    #[test]
+    fn oversized_rows_are_row_errors() {
+        let record = |pairs: &[(&str, &str)]| -> FeedRecord {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let course = |hours: &str, price: &str| {
+            normalize(
+                &record(&[
+                    ("title", "Evidence"),
+                    ("hours", hours),
+                    ("price", price),
+                    ("url", "https://example.com/evidence"),
+                    ("topics", "Ethics"),
+                ]),
+                &HashMap::new(),
+            )
+        };
+        assert_eq!(course("2", "$10").unwrap().duration_minutes, 120);
+        assert!(course("9999999999999999", "$10").is_err());
+        assert!(course("2", "$99999999999").is_err());
+    }
+
+    #[tokio::test]
+    async fn json_feed_skips_non_web_links() {
+        let conn = crate::test_support::database().await;
+        let summary = ingest(&conn, "BarPrep", FeedFormat::Json, BARPREP, false).await.unwrap();
+        assert_eq!((summary.created, summary.duplicates), (2, 0));
+        assert_eq!(skipped(&summary), [(3, Some("Bad Link Course"))]);
+
+        let privacy = crate::catalog::load_courses(&conn, true)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|c| c.course.title == "Privacy Law Update 2026")
+            .unwrap();
+        assert_eq!(privacy.course.price_cents, 4900);
+        assert_eq!(privacy.course.member_price_cents, Some(2900));
+        assert_eq!(privacy.course.starts_on, NaiveDate::from_ymd_opt(2026, 12, 2));
+        let states: Vec<&str> = privacy.approvals.iter().map(|a| a.state_code.as_str()).collect();
+        assert_eq!(states, ["NY", "FL"]);
+    }
+
+    #[tokio::test]
+    async fn csv_feed_counts_duplicates_and_bad_rows() {
+        let conn = crate::test_support::database().await;
+        let summary = ingest(&conn, "LexCLE", FeedFormat::Csv, LEXCLE, false).await.unwrap();
+        assert_eq!((summary.created, summary.duplicates), (3, 1));
+        assert_eq!(
+            skipped(&summary),
+            [(5, Some("Underwater Basket Law")), (6, Some("Missing Hours"))]
+        );
+        assert_eq!(course_titles(&conn, "LexCLE").await.len(), 3);
+
+        // Running the same feed again changes nothing
+        let again = ingest(&conn, "LexCLE", FeedFormat::Csv, LEXCLE, false).await.unwrap();
+        assert_eq!((again.created, again.updated, again.unchanged), (0, 0, 3));
+    }
+
+    #[tokio::test]
+    async fn ical_feed_reads_events() {
+        let conn = crate::test_support::database().await;
+        let summary = ingest(&conn, "State Bar Seminars", FeedFormat::Ical, SEMINARS, false)
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 2);
+        assert!(summary.skipped.is_empty());
+
+        let courses = crate::catalog::load_courses(&conn, true).await.unwrap();
+        let workshop = courses.iter().find(|c| c.course.title == "Contract Drafting Workshop").unwrap();
+        assert_eq!(workshop.course.duration_minutes, 90);
+        assert_eq!(workshop.course.price_cents, 15000);
+        assert_eq!(workshop.course.member_price_cents, Some(9500));
+        assert!(courses.iter().any(|c| c.course.title == "Elimination of Bias in Jury Selection"));
+    }
+
+    #[tokio::test]
+    async fn courses_dropped_from_the_feed_are_retired() {
+        let conn = crate::test_support::database().await;
+        ingest(&conn, "LexCLE", FeedFormat::Csv, LEXCLE, false).await.unwrap();
+
+        let without_wellness: String = LEXCLE
+            .lines()
+            .filter(|line| !line.starts_with("Lawyer Wellness Basics"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let summary = ingest(&conn, "LexCLE", FeedFormat::Csv, &without_wellness, false)
+            .await
+            .unwrap();
+        assert_eq!((summary.unchanged, summary.retired), (2, 1));
+        assert!(course_titles(&conn, "LexCLE").await.contains(&("Lawyer Wellness Basics".to_string(), true)));
+
+        // Coming back in a later feed brings it back
+        let summary = ingest(&conn, "LexCLE", FeedFormat::Csv, LEXCLE, false).await.unwrap();
+        assert_eq!((summary.updated, summary.retired), (1, 0));
+        assert!(course_titles(&conn, "LexCLE").await.iter().all(|(_, retired)| !retired));
+    }
+
+    #[tokio::test]
+    async fn dry_run_saves_nothing() {
+        let conn = crate::test_support::database().await;
+        let counts = async || {
+            (
+                course::Entity::find().count(&conn).await.unwrap(),
+                provider::Entity::find().count(&conn).await.unwrap(),
+                entity::catalog_audit::Entity::find().count(&conn).await.unwrap(),
+            )
+        };
+        let before = counts().await;
+        let summary = ingest(&conn, "BarPrep", FeedFormat::Json, BARPREP, true).await.unwrap();
+        assert!(summary.dry_run);
+        assert_eq!(summary.created, 2);
+        assert_eq!(counts().await, before);
+    }
+}
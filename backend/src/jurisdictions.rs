@@ -0,0 +1,441 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::NaiveDate;
+use entity::{Credits, course_bookmark, credit_entry, user_state};
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tower_cookies::Cookies;
+
+use crate::license_status::LicenseStatus;
+use crate::licenses::License;
+use crate::profession::Profession;
+use crate::register::UsState;
+use crate::versioning::IfMatch;
+
+/// The editable details of a license.
+#[derive(Deserialize)]
+pub struct JurisdictionDetails {
+    pub renewal_date: NaiveDate,
+    pub admission_date: Option<NaiveDate>,
+    pub bar_number: Option<String>,
+    #[serde(default)]
+    pub status: LicenseStatus,
+    /// Replaces the renewal date for the current period only
+    pub deadline_override: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+pub struct AddJurisdictionRequest {
+    pub state_code: UsState,
+    /// Defaults to the user's profession
+    pub profession: Option<Profession>,
+    /// Hours already completed in the current period
+    #[serde(default)]
+    pub completed: Credits,
+    #[serde(flatten)]
+    pub details: JurisdictionDetails,
+}
+
+#[derive(Serialize)]
+pub struct JurisdictionResponse {
+    id: i32,
+    state_code: String,
+    profession: String,
+    renewal_date: Option<String>,
+    deadline_override: Option<NaiveDate>,
+    deadline: Option<NaiveDate>,
+    period_start: Option<NaiveDate>,
+    admission_date: Option<NaiveDate>,
+    bar_number: Option<String>,
+    status: String,
+    version: i32,
+}
+
+impl From<&License> for JurisdictionResponse {
+    fn from(l: &License) -> Self {
+        JurisdictionResponse {
+            id: l.license.id,
+            state_code: l.state_code.clone(),
+            profession: l.license.profession.clone(),
+            renewal_date: l.license.renewal_date.clone(),
+            deadline_override: l.license.deadline_override,
+            deadline: l.deadline(),
+            period_start: l.period_start(),
+            admission_date: l.license.admission_date,
+            bar_number: l.license.bar_number.clone(),
+            status: l.license.status.clone(),
+            version: l.license.version,
+        }
+    }
+}
+
+/// Attorney registration number formats for states that publish one. Other
+/// states, and other professions, only get the general sanity check.
+const BAR_NUMBER_FORMATS: &[(&str, &str)] = &[
+    ("CA", r"^\d{1,6}$"),
+    ("FL", r"^\d{1,7}$"),
+    ("IL", r"^\d{7}$"),
+    ("NJ", r"^\d{9}$"),
+    ("NY", r"^\d{7}$"),
+    ("PA", r"^\d{1,6}$"),
+    ("TX", r"^\d{8}$"),
+];
+
+static STATE_BAR_NUMBERS: LazyLock<HashMap<&'static str, Regex>> = LazyLock::new(|| {
+    BAR_NUMBER_FORMATS
+        .iter()
+        .map(|(code, pattern)| (*code, Regex::new(pattern).unwrap()))
+        .collect()
+});
+
+static GENERAL_BAR_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9-]{0,19}$").unwrap());
+
+/// Checks a bar or registration number against the format used by the state.
+pub fn validate_bar_number(
+    state_code: &str,
+    profession: Profession,
+    bar_number: &str,
+) -> Result<(), (StatusCode, &'static str)> {
+    if !GENERAL_BAR_NUMBER.is_match(bar_number) {
+        return Err((StatusCode::BAD_REQUEST, "Bar number may only contain letters, digits and dashes"));
+    }
+
+    let format = STATE_BAR_NUMBERS
+        .get(state_code)
+        .filter(|_| profession == Profession::Attorney);
+    if let Some(format) = format
+        && !format.is_match(bar_number)
+    {
+        return Err((StatusCode::BAD_REQUEST, "Bar number format is not valid for this state"));
+    }
+
+    Ok(())
+}
+
+fn validate(
+    state_code: &str,
+    profession: Profession,
+    details: &JurisdictionDetails,
+) -> Result<(), (StatusCode, &'static str)> {
+    if let Some(bar_number) = details.bar_number.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+        validate_bar_number(state_code, profession, bar_number)?;
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    if details.admission_date.is_some_and(|d| d > today) {
+        return Err((StatusCode::BAD_REQUEST, "Admission date cannot be in the future"));
+    }
+    if details
+        .admission_date
+        .is_some_and(|d| d > details.renewal_date)
+    {
+        return Err((StatusCode::BAD_REQUEST, "Renewal date must be after admission"));
+    }
+    Ok(())
+}
+
+async fn find_license<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    id: i32,
+) -> Result<License, (StatusCode, &'static str)> {
+    crate::licenses::for_user(conn, user_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .into_iter()
+        .find(|l| l.license.id == id)
+        .ok_or((StatusCode::NOT_FOUND, "License not found"))
+}
+
+pub async fn list_jurisdictions(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<JurisdictionResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(licenses.iter().map(Into::into).collect()))
+}
+
+pub async fn add_jurisdiction(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    if_match: IfMatch,
+    Json(data): Json<AddJurisdictionRequest>,
+) -> Result<Json<JurisdictionResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    if data.completed.is_negative() {
+        return Err((StatusCode::BAD_REQUEST, "Hours cannot be negative"));
+    }
+
+    let profession = match data.profession {
+        Some(profession) => profession,
+        None => user.profession.parse().unwrap_or_default(),
+    };
+    validate(&data.state_code.to_string(), profession, &data.details)?;
+
+    let state_record = entity::state::Entity::find()
+        .filter(entity::state::Column::Name.eq(data.state_code.to_string()))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "State code not found"))?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    // Bumping the user's version first takes the write lock, so a concurrent
+    // request for the same jurisdiction waits and then sees this one's license
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    let existing = user_state::Entity::find()
+        .filter(user_state::Column::UserId.eq(user.id))
+        .filter(user_state::Column::StateId.eq(state_record.id))
+        .filter(user_state::Column::Profession.eq(profession.to_string()))
+        .filter(user_state::Column::RemovedAt.is_null())
+        .one(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if existing.is_some() {
+        return Err((StatusCode::CONFLICT, "Already licensed in this state for this profession"));
+    }
+    let details = data.details;
+    let license = user_state::ActiveModel {
+        user_id: Set(user.id),
+        state_id: Set(state_record.id),
+        renewal_date: Set(Some(details.renewal_date.to_string())),
+        profession: Set(profession.to_string()),
+        admission_date: Set(details.admission_date),
+        bar_number: Set(crate::ledger::non_blank(details.bar_number)),
+        status: Set(details.status.to_string()),
+        deadline_override: Set(details.deadline_override),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to add jurisdiction"))?;
+
+    if data.completed > Credits::ZERO {
+        crate::hours_history::set_hours(
+            &txn,
+            user.id,
+            user.id,
+            license.id,
+            data.completed,
+            crate::hours_history::Source::Jurisdiction,
+        )
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to add jurisdiction"))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to add jurisdiction"))?;
+
+    let license = find_license(&state.conn, user.id, license.id).await?;
+    Ok(Json((&license).into()))
+}
+
+pub async fn update_jurisdiction(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    if_match: IfMatch,
+    Json(details): Json<JurisdictionDetails>,
+) -> Result<Json<JurisdictionResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let existing = find_license(&state.conn, user.id, id).await?;
+    validate(
+        &existing.state_code,
+        existing.license.profession.parse().unwrap_or_default(),
+        &details,
+    )?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_license(&txn, id, &if_match).await?;
+    crate::versioning::bump_user(&txn, user.id, &IfMatch::Any).await?;
+
+    let mut active: user_state::ActiveModel = existing.license.into();
+    active.renewal_date = Set(Some(details.renewal_date.to_string()));
+    active.admission_date = Set(details.admission_date);
+    active.bar_number = Set(crate::ledger::non_blank(details.bar_number));
+    active.status = Set(details.status.to_string());
+    active.deadline_override = Set(details.deadline_override);
+    active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update jurisdiction"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update jurisdiction"))?;
+
+    let license = find_license(&state.conn, user.id, id).await?;
+    Ok(Json((&license).into()))
+}
+
+/// Removes a license along with the credit entries scoped to it. Entries that
+/// apply to every license are kept, and the license row is only marked
+/// removed so its hours and compliance history survive.
+pub async fn delete_jurisdiction(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    if_match: IfMatch,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let existing = find_license(&state.conn, user.id, id).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_license(&txn, existing.license.id, &if_match).await?;
+    crate::versioning::bump_user(&txn, user.id, &IfMatch::Any).await?;
+
+    let scoped_entries = credit_entry::Entity::find()
+        .filter(credit_entry::Column::UserStateId.eq(existing.license.id))
+        .all(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut blob_keys = Vec::new();
+    for entry in scoped_entries {
+        blob_keys.extend(
+            crate::ledger::delete_entry_rows(&txn, entry.id)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to remove jurisdiction"))?,
+        );
+    }
+
+    // Planned courses stay on the user's list, just no longer tied to the license
+    course_bookmark::Entity::update_many()
+        .col_expr(course_bookmark::Column::UserStateId, sea_orm::sea_query::Expr::value(Option::<i32>::None))
+        .filter(course_bookmark::Column::UserStateId.eq(existing.license.id))
+        .exec(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to remove jurisdiction"))?;
+
+    let mut removed: user_state::ActiveModel = existing.license.into();
+    removed.removed_at = Set(Some(chrono::Utc::now()));
+    removed
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to remove jurisdiction"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to remove jurisdiction"))?;
+
+    crate::certificates::remove_blobs(state.blobs.as_ref(), blob_keys).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use entity::hours_change;
+
+    #[test]
+    fn checks_bar_numbers_against_the_state_format() {
+        assert!(validate_bar_number("NY", Profession::Attorney, "1234567").is_ok());
+        assert!(validate_bar_number("NY", Profession::Attorney, "123456").is_err());
+        assert!(validate_bar_number("TX", Profession::Attorney, "24012345").is_ok());
+        assert!(validate_bar_number("CA", Profession::Attorney, "CA-1").is_err());
+        // Only the general check applies elsewhere, and to other professions
+        assert!(validate_bar_number("WA", Profession::Attorney, "WA-00123").is_ok());
+        assert!(validate_bar_number("NY", Profession::Cpa, "CPA-12").is_ok());
+        assert!(validate_bar_number("WA", Profession::Attorney, "12 34").is_err());
+    }
+
+    #[tokio::test]
+    async fn removing_a_jurisdiction_keeps_its_hours_history() {
+        let state = test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (user, cookies) = test_support::sign_in(&conn, "ann").await;
+        let ny = test_support::add_license(&conn, user.id, "NY", "2027-06-30").await;
+        crate::hours_history::set_hours(
+            &conn,
+            user.id,
+            user.id,
+            ny.id,
+            Credits::from_whole(5),
+            crate::hours_history::Source::Registration,
+        )
+        .await
+        .unwrap();
+
+        delete_jurisdiction(State(state.clone()), cookies, Path(ny.id), IfMatch::Any)
+            .await
+            .unwrap();
+
+        assert!(crate::licenses::for_user(&conn, user.id).await.unwrap().is_empty());
+        let history = hours_change::Entity::find()
+            .filter(hours_change::Column::UserStateId.eq(ny.id))
+            .all(&conn)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new_hours, Credits::from_whole(5));
+        assert!(crate::ledger::check_license(&conn, user.id, Some(ny.id)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_add_a_jurisdiction_once() {
+        let state = test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (user, cookies) = test_support::sign_in(&conn, "ann").await;
+
+        let add = || {
+            add_jurisdiction(
+                State(state.clone()),
+                cookies.clone(),
+                IfMatch::Any,
+                Json(
+                    serde_json::from_value(serde_json::json!({
+                        "state_code": "NY",
+                        "renewal_date": "2027-06-30",
+                        "completed": 4,
+                    }))
+                    .unwrap(),
+                ),
+            )
+        };
+        let (first, second) = tokio::join!(add(), add());
+        let statuses: Vec<Option<StatusCode>> =
+            [first, second].into_iter().map(|r| r.err().map(|(status, _)| status)).collect();
+        assert!(statuses.contains(&None));
+        assert!(statuses.contains(&Some(StatusCode::CONFLICT)));
+
+        let licenses = user_state::Entity::find()
+            .filter(user_state::Column::UserId.eq(user.id))
+            .all(&conn)
+            .await
+            .unwrap();
+        assert_eq!(licenses.len(), 1);
+    }
+}
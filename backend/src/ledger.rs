@@ -0,0 +1,665 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::NaiveDate;
+use entity::{Credits, credit_entry, credit_entry_category};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tower_cookies::Cookies;
+
+use crate::category::CreditCategory;
+use crate::credits::CreditUnit;
+use crate::currency::Currency;
+use crate::hours_history::Source;
+use crate::licenses::License;
+use crate::versioning::IfMatch;
+
+/// A course or other activity the user completed
+pub const KIND_COURSE: &str = "course";
+/// A correction to a license's total that isn't tied to a course
+pub const KIND_ADJUSTMENT: &str = "adjustment";
+
+/// A ledger entry together with its per-category credits.
+pub struct Entry {
+    pub entry: credit_entry::Model,
+    pub categories: Vec<credit_entry_category::Model>,
+}
+
+impl Entry {
+    /// Whether the entry counts towards the license's current compliance period.
+    ///
+    /// Entries from before the license was first rolled over aren't bounded
+    /// below, so hours reported at registration keep counting.
+    pub fn applies_to(&self, license: &License) -> bool {
+        let completed_on = self.entry.completed_on;
+        self.entry
+            .user_state_id
+            .is_none_or(|id| id == license.license.id)
+            && license.license.period_start.is_none_or(|start| completed_on >= start)
+            && license.deadline().is_none_or(|end| completed_on <= end)
+    }
+
+    /// Credits this entry contributes under a jurisdiction's credit unit.
+    ///
+    /// Entries that record minutes of instruction are re-converted for each
+    /// jurisdiction, with the certificate's category split scaled to match.
+    pub fn applied_credits(&self, unit: CreditUnit) -> Vec<(CreditCategory, Credits)> {
+        let awarded: Vec<(CreditCategory, Credits)> = self
+            .categories
+            .iter()
+            .map(|c| (c.category.parse().unwrap_or_default(), c.credits))
+            .collect();
+
+        let Some(minutes) = self.entry.minutes.filter(|m| *m > 0) else {
+            return awarded;
+        };
+        let awarded_total: Credits = awarded.iter().map(|(_, c)| *c).sum();
+        if awarded_total <= Credits::ZERO {
+            return awarded;
+        }
+
+        let target = unit.credits_for_minutes(minutes as u32);
+        let mut scaled: Vec<(CreditCategory, Credits)> = awarded
+            .iter()
+            .map(|(category, credits)| {
+                // Widened so the product can't overflow; each share is at most
+                // the target, so it fits back in i64
+                let share = i128::from(credits.hundredths()) * i128::from(target.hundredths())
+                    / i128::from(awarded_total.hundredths());
+                let hundredths = i64::try_from(share).unwrap_or(target.hundredths());
+                (*category, Credits::from_hundredths(hundredths))
+            })
+            .collect();
+
+        // Rounding leftovers go to the largest category so the split adds up
+        let remainder = target - scaled.iter().map(|(_, c)| *c).sum::<Credits>();
+        if let Some(largest) = scaled.iter_mut().max_by_key(|(_, c)| *c) {
+            largest.1 += remainder;
+        }
+        scaled
+    }
+}
+
+/// Credits counted towards a single license.
+#[derive(Clone, Default, Serialize)]
+pub struct LicenseTotals {
+    pub total: Credits,
+    pub by_category: BTreeMap<CreditCategory, Credits>,
+}
+
+/// Loads a user's ledger, newest first.
+pub async fn entries_for_user<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<Vec<Entry>, DbErr> {
+    let entries = credit_entry::Entity::find()
+        .filter(credit_entry::Column::UserId.eq(user_id))
+        .order_by_desc(credit_entry::Column::CompletedOn)
+        .order_by_desc(credit_entry::Column::Id)
+        .all(conn)
+        .await?;
+
+    let mut categories: HashMap<i32, Vec<credit_entry_category::Model>> = HashMap::new();
+    for category in credit_entry_category::Entity::find()
+        .filter(credit_entry_category::Column::CreditEntryId.is_in(entries.iter().map(|e| e.id)))
+        .order_by_asc(credit_entry_category::Column::Id)
+        .all(conn)
+        .await?
+    {
+        categories.entry(category.credit_entry_id).or_default().push(category);
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| Entry {
+            categories: categories.remove(&entry.id).unwrap_or_default(),
+            entry,
+        })
+        .collect())
+}
+
+/// Derives each license's totals from the ledger, keyed by license id.
+pub fn totals(licenses: &[License], entries: &[Entry]) -> HashMap<i32, LicenseTotals> {
+    let mut totals = licenses
+        .iter()
+        .map(|license| (license.license.id, LicenseTotals::default()))
+        .collect();
+    for entry in entries {
+        add_to_totals(&mut totals, licenses, entry);
+    }
+    totals
+}
+
+/// Adds one entry's credits to the totals of every license it applies to.
+pub fn add_to_totals(totals: &mut HashMap<i32, LicenseTotals>, licenses: &[License], entry: &Entry) {
+    for license in licenses.iter().filter(|l| entry.applies_to(l)) {
+        let unit = CreditUnit::for_requirement(license.requirement.as_ref());
+        let totals = totals.entry(license.license.id).or_default();
+        for (category, credits) in entry.applied_credits(unit) {
+            totals.total += credits;
+            *totals.by_category.entry(category).or_default() += credits;
+        }
+    }
+}
+
+/// Loads a user's licenses along with the totals the ledger gives each of them.
+pub async fn totals_for_user<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+) -> Result<(Vec<License>, HashMap<i32, LicenseTotals>), DbErr> {
+    let licenses = crate::licenses::for_user(conn, user_id).await?;
+    let entries = entries_for_user(conn, user_id).await?;
+    let totals = totals(&licenses, &entries);
+    Ok((licenses, totals))
+}
+
+/// Records a general-credit correction against a single license.
+pub async fn record_adjustment<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    license_id: i32,
+    title: &str,
+    credits: Credits,
+) -> Result<credit_entry::Model, DbErr> {
+    let entry = credit_entry::ActiveModel {
+        user_id: Set(user_id),
+        user_state_id: Set(Some(license_id)),
+        kind: Set(KIND_ADJUSTMENT.to_string()),
+        title: Set(title.to_string()),
+        completed_on: Set(chrono::Utc::now().date_naive()),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    credit_entry_category::ActiveModel {
+        credit_entry_id: Set(entry.id),
+        category: Set(CreditCategory::General.to_string()),
+        credits: Set(credits),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(entry)
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryCredits {
+    pub category: CreditCategory,
+    pub credits: Credits,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreditEntryRequest {
+    pub title: String,
+    pub provider: Option<String>,
+    pub completed_on: NaiveDate,
+    pub format: Option<String>,
+    pub approval_number: Option<String>,
+    pub notes: Option<String>,
+    /// Minutes of instruction, used to convert credit for each jurisdiction
+    pub minutes: Option<u32>,
+    /// Restricts the entry to one of the user's licenses
+    pub license_id: Option<i32>,
+    pub categories: Vec<CategoryCredits>,
+    /// The catalog course the credit was earned from, if any
+    #[serde(default)]
+    pub course_id: Option<i32>,
+    /// What the user paid, in minor units of `currency`
+    #[serde(default)]
+    pub amount_paid_cents: Option<u32>,
+    /// Defaults to US dollars when an amount is given
+    #[serde(default)]
+    pub currency: Option<Currency>,
+}
+
+impl CreditEntryRequest {
+    /// The currency of the amount paid, if anything was paid.
+    fn paid_currency(&self) -> Option<String> {
+        self.amount_paid_cents
+            .map(|_| self.currency.unwrap_or_default().to_string())
+    }
+}
+
+#[derive(Serialize)]
+pub struct CreditEntryResponse {
+    pub id: i32,
+    pub kind: String,
+    pub title: String,
+    pub provider: Option<String>,
+    pub completed_on: NaiveDate,
+    pub format: Option<String>,
+    pub approval_number: Option<String>,
+    pub notes: Option<String>,
+    pub minutes: Option<i32>,
+    pub license_id: Option<i32>,
+    pub course_id: Option<i32>,
+    pub amount_paid_cents: Option<i32>,
+    pub currency: Option<Currency>,
+    pub categories: Vec<CategoryCredits>,
+    pub total: Credits,
+    /// Send back as a quoted `If-Match` value to guard against lost updates
+    pub version: i32,
+}
+
+impl From<Entry> for CreditEntryResponse {
+    fn from(e: Entry) -> Self {
+        let categories: Vec<CategoryCredits> = e
+            .categories
+            .into_iter()
+            .map(|c| CategoryCredits {
+                category: c.category.parse().unwrap_or_default(),
+                credits: c.credits,
+            })
+            .collect();
+        CreditEntryResponse {
+            id: e.entry.id,
+            kind: e.entry.kind,
+            title: e.entry.title,
+            provider: e.entry.provider,
+            completed_on: e.entry.completed_on,
+            format: e.entry.format,
+            approval_number: e.entry.approval_number,
+            notes: e.entry.notes,
+            minutes: e.entry.minutes,
+            license_id: e.entry.user_state_id,
+            course_id: e.entry.course_id,
+            amount_paid_cents: e.entry.amount_paid_cents,
+            currency: e.entry.currency.and_then(|c| c.parse().ok()),
+            total: categories.iter().map(|c| c.credits).sum(),
+            categories,
+            version: e.entry.version,
+        }
+    }
+}
+
+/// Trims optional text fields, treating blank strings as absent
+pub fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Checks the parts of an entry that don't depend on the database.
+pub fn check_entry(data: &CreditEntryRequest) -> Result<(), (StatusCode, &'static str)> {
+    if data.title.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Title is required"));
+    }
+    if data.categories.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "At least one credit category is required"));
+    }
+    if data.categories.iter().any(|c| c.credits <= Credits::ZERO) {
+        return Err((StatusCode::BAD_REQUEST, "Credits must be greater than zero"));
+    }
+    if data.categories.iter().any(|c| c.credits > Credits::MAX) {
+        return Err((StatusCode::BAD_REQUEST, "Credits are too large"));
+    }
+    let distinct: HashSet<_> = data.categories.iter().map(|c| c.category).collect();
+    if distinct.len() != data.categories.len() {
+        return Err((StatusCode::BAD_REQUEST, "Each category may only appear once"));
+    }
+    if data.completed_on > chrono::Utc::now().date_naive() {
+        return Err((StatusCode::BAD_REQUEST, "Completion date cannot be in the future"));
+    }
+    // Both are stored as 32-bit integers
+    if data.minutes.is_some_and(|m| i32::try_from(m).is_err()) {
+        return Err((StatusCode::BAD_REQUEST, "Minutes are too large"));
+    }
+    if data.amount_paid_cents.is_some_and(|a| i32::try_from(a).is_err()) {
+        return Err((StatusCode::BAD_REQUEST, "Amount paid is too large"));
+    }
+    Ok(())
+}
+
+/// Checks that a license an entry is scoped to belongs to the user.
+pub async fn check_license<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    license_id: Option<i32>,
+) -> Result<(), (StatusCode, &'static str)> {
+    if let Some(license_id) = license_id {
+        entity::user_state::Entity::find_by_id(license_id)
+            .filter(entity::user_state::Column::UserId.eq(user_id))
+            .filter(entity::user_state::Column::RemovedAt.is_null())
+            .one(conn)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+            .ok_or((StatusCode::NOT_FOUND, "License not found for user"))?;
+    }
+
+    Ok(())
+}
+
+/// Checks that a course an entry is linked to is in the catalog. Retired
+/// courses are allowed, since the credit may have been earned before.
+pub async fn check_course<C: ConnectionTrait>(
+    conn: &C,
+    course_id: Option<i32>,
+) -> Result<(), (StatusCode, &'static str)> {
+    if let Some(course_id) = course_id {
+        entity::course::Entity::find_by_id(course_id)
+            .one(conn)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+            .ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+    }
+
+    Ok(())
+}
+
+async fn validate<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    data: &CreditEntryRequest,
+) -> Result<(), (StatusCode, &'static str)> {
+    check_entry(data)?;
+    check_license(conn, user_id, data.license_id).await?;
+    check_course(conn, data.course_id).await
+}
+
+/// Saves a validated course entry and its categories.
+pub async fn insert_entry<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    data: CreditEntryRequest,
+) -> Result<Entry, DbErr> {
+    let currency = data.paid_currency();
+    let entry = credit_entry::ActiveModel {
+        user_id: Set(user_id),
+        user_state_id: Set(data.license_id),
+        kind: Set(KIND_COURSE.to_string()),
+        title: Set(data.title.trim().to_string()),
+        provider: Set(non_blank(data.provider)),
+        completed_on: Set(data.completed_on),
+        format: Set(non_blank(data.format)),
+        approval_number: Set(non_blank(data.approval_number)),
+        notes: Set(non_blank(data.notes)),
+        minutes: Set(data.minutes.map(|m| m as i32)),
+        course_id: Set(data.course_id),
+        amount_paid_cents: Set(data.amount_paid_cents.map(|a| a as i32)),
+        currency: Set(currency),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    let categories = insert_categories(conn, entry.id, &data.categories).await?;
+    Ok(Entry { entry, categories })
+}
+
+/// Deletes an entry along with its categories and certificate rows, returning
+/// the certificate blob keys to remove once the surrounding transaction commits.
+pub async fn delete_entry_rows<C: ConnectionTrait>(conn: &C, entry_id: i32) -> Result<Vec<String>, DbErr> {
+    let blob_keys = crate::certificates::delete_rows_for_entry(conn, entry_id).await?;
+
+    credit_entry_category::Entity::delete_many()
+        .filter(credit_entry_category::Column::CreditEntryId.eq(entry_id))
+        .exec(conn)
+        .await?;
+
+    credit_entry::Entity::delete_by_id(entry_id).exec(conn).await?;
+
+    Ok(blob_keys)
+}
+
+async fn insert_categories<C: ConnectionTrait>(
+    conn: &C,
+    entry_id: i32,
+    categories: &[CategoryCredits],
+) -> Result<Vec<credit_entry_category::Model>, DbErr> {
+    let mut inserted = Vec::with_capacity(categories.len());
+    for c in categories {
+        let model = credit_entry_category::ActiveModel {
+            credit_entry_id: Set(entry_id),
+            category: Set(c.category.to_string()),
+            credits: Set(c.credits),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+        inserted.push(model);
+    }
+    Ok(inserted)
+}
+
+/// Keeps the hours history in step with a write to `entry`, comparing each
+/// license's totals from before the write with the ledger as it is now.
+async fn record_hours_changes<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    entry: &credit_entry::Model,
+    before: &HashMap<i32, LicenseTotals>,
+) -> Result<(), DbErr> {
+    let (_, after) = totals_for_user(conn, user_id).await?;
+    crate::hours_history::record_changes(conn, user_id, entry, before, &after, Source::Credit).await
+}
+
+async fn find_entry<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    id: i32,
+) -> Result<credit_entry::Model, (StatusCode, &'static str)> {
+    credit_entry::Entity::find_by_id(id)
+        .filter(credit_entry::Column::UserId.eq(user_id))
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Credit entry not found"))
+}
+
+pub async fn list_credits(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<CreditEntryResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let entries = entries_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+pub async fn create_credit(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    if_match: IfMatch,
+    Json(data): Json<CreditEntryRequest>,
+) -> Result<Json<CreditEntryResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    validate(&state.conn, user.id, &data).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    let (_, before) = totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let entry = insert_entry(&txn, user.id, data)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+    record_hours_changes(&txn, user.id, &entry.entry, &before)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+
+    Ok(Json(entry.into()))
+}
+
+pub async fn update_credit(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    if_match: IfMatch,
+    Json(data): Json<CreditEntryRequest>,
+) -> Result<Json<CreditEntryResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let existing = find_entry(&state.conn, user.id, id).await?;
+    validate(&state.conn, user.id, &data).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_entry(&txn, existing.id, &if_match).await?;
+    crate::versioning::bump_user(&txn, user.id, &IfMatch::Any).await?;
+    let (_, before) = totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let currency = data.paid_currency();
+    let mut active: credit_entry::ActiveModel = existing.into();
+    active.user_state_id = Set(data.license_id);
+    active.title = Set(data.title.trim().to_string());
+    active.provider = Set(non_blank(data.provider));
+    active.completed_on = Set(data.completed_on);
+    active.format = Set(non_blank(data.format));
+    active.approval_number = Set(non_blank(data.approval_number));
+    active.notes = Set(non_blank(data.notes));
+    active.minutes = Set(data.minutes.map(|m| m as i32));
+    active.course_id = Set(data.course_id);
+    active.amount_paid_cents = Set(data.amount_paid_cents.map(|a| a as i32));
+    active.currency = Set(currency);
+
+    let entry = active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update credit entry"))?;
+
+    credit_entry_category::Entity::delete_many()
+        .filter(credit_entry_category::Column::CreditEntryId.eq(entry.id))
+        .exec(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update credit entry"))?;
+
+    let categories = insert_categories(&txn, entry.id, &data.categories)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update credit entry"))?;
+    record_hours_changes(&txn, user.id, &entry, &before)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update credit entry"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update credit entry"))?;
+
+    Ok(Json(Entry { entry, categories }.into()))
+}
+
+pub async fn delete_credit(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    if_match: IfMatch,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let existing = find_entry(&state.conn, user.id, id).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_entry(&txn, existing.id, &if_match).await?;
+    crate::versioning::bump_user(&txn, user.id, &IfMatch::Any).await?;
+    let (_, before) = totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let blob_keys = delete_entry_rows(&txn, existing.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete credit entry"))?;
+    record_hours_changes(&txn, user.id, &existing, &before)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete credit entry"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete credit entry"))?;
+
+    crate::certificates::remove_blobs(state.blobs.as_ref(), blob_keys).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(minutes: i32, categories: &[(CreditCategory, Credits)]) -> Entry {
+        Entry {
+            entry: credit_entry::Model {
+                id: 1,
+                user_id: 1,
+                user_state_id: None,
+                kind: KIND_COURSE.to_string(),
+                title: "Evidence".to_string(),
+                provider: None,
+                completed_on: NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+                format: None,
+                approval_number: None,
+                notes: None,
+                minutes: Some(minutes),
+                created_at: chrono::Utc::now(),
+                version: 1,
+                course_id: None,
+                amount_paid_cents: None,
+                currency: None,
+            },
+            categories: categories
+                .iter()
+                .map(|(category, credits)| credit_entry_category::Model {
+                    id: 0,
+                    credit_entry_id: 1,
+                    category: category.to_string(),
+                    credits: *credits,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn minutes_are_split_like_the_certificate() {
+        let entry = entry(
+            90,
+            &[(CreditCategory::Ethics, Credits::from_whole(1)), (CreditCategory::General, Credits::from_whole(2))],
+        );
+        assert_eq!(
+            entry.applied_credits(CreditUnit::default()),
+            [
+                (CreditCategory::Ethics, Credits::from_hundredths(50)),
+                (CreditCategory::General, Credits::from_whole(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn large_entries_are_scaled_without_overflow() {
+        // Older rows aren't bounded the way new requests are
+        let huge = Credits::from_hundredths(1 << 40);
+        let entry = entry(i32::MAX, &[(CreditCategory::Ethics, huge), (CreditCategory::General, huge)]);
+        let applied = entry.applied_credits(CreditUnit {
+            minutes_per_credit: 1,
+            increment: Credits::from_hundredths(1),
+        });
+        let target = Credits::from_whole(i64::from(i32::MAX));
+        assert_eq!(applied.iter().map(|(_, c)| *c).sum::<Credits>(), target);
+        assert_eq!(applied[0].1, Credits::from_hundredths(target.hundredths() / 2));
+    }
+}
@@ -0,0 +1,50 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// Standing of a license with its licensing authority.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
+pub enum LicenseStatus {
+    #[default]
+    Active,
+    Inactive,
+    Retired,
+    Suspended,
+}
+
+#[derive(Debug)]
+pub struct ParseLicenseStatusError;
+
+impl fmt::Display for ParseLicenseStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid license status")
+    }
+}
+
+impl std::error::Error for ParseLicenseStatusError {}
+
+impl FromStr for LicenseStatus {
+    type Err = ParseLicenseStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(LicenseStatus::Active),
+            "inactive" => Ok(LicenseStatus::Inactive),
+            "retired" => Ok(LicenseStatus::Retired),
+            "suspended" => Ok(LicenseStatus::Suspended),
+            _ => Err(ParseLicenseStatusError),
+        }
+    }
+}
+
+impl fmt::Display for LicenseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LicenseStatus::Active => "active",
+            LicenseStatus::Inactive => "inactive",
+            LicenseStatus::Retired => "retired",
+            LicenseStatus::Suspended => "suspended",
+        };
+        write!(f, "{}", name)
+    }
+}
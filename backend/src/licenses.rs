@@ -0,0 +1,94 @@
+use chrono::{Months, NaiveDate};
+use entity::{requirement, requirement_category, state, user_state};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+
+/// A jurisdiction a user is licensed in, along with the rule that applies to it.
+pub struct License {
+    pub license: user_state::Model,
+    pub state_code: String,
+    /// Missing only if no requirement has been defined for the license's profession
+    pub requirement: Option<requirement::Model>,
+    /// Specialty minimums that form part of the requirement
+    pub category_minimums: Vec<requirement_category::Model>,
+    /// Specialty minimums of the transitional requirement for new admittees
+    pub new_admittee_minimums: Vec<requirement_category::Model>,
+}
+
+impl License {
+    /// The date the current compliance period ends, taking any override
+    /// (an extension, say) in place of the regular renewal date
+    pub fn deadline(&self) -> Option<NaiveDate> {
+        self.license.deadline_override.or_else(|| self.renewal_date())
+    }
+
+    /// The regular end of the current period, ignoring any override
+    pub fn renewal_date(&self) -> Option<NaiveDate> {
+        self.license
+            .renewal_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+    }
+
+    /// Length of a compliance period in months
+    pub fn period_months(&self) -> u32 {
+        self.requirement
+            .as_ref()
+            .map(|r| r.period_months.max(1) as u32)
+            .unwrap_or(12)
+    }
+
+    /// The date the current compliance period began, worked back from the
+    /// renewal date until the license has been rolled over once.
+    pub fn period_start(&self) -> Option<NaiveDate> {
+        self.license.period_start.or_else(|| {
+            self.renewal_date()
+                .and_then(|d| d.checked_sub_months(Months::new(self.period_months())))
+                .and_then(|d| d.succ_opt())
+        })
+    }
+}
+
+/// Loads every license held by `user_id` together with its state and
+/// requirement. Removed licenses are left out.
+pub async fn for_user<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<Vec<License>, DbErr> {
+    let licenses = user_state::Entity::find()
+        .find_also_related(state::Entity)
+        .filter(user_state::Column::UserId.eq(user_id))
+        .filter(user_state::Column::RemovedAt.is_null())
+        .order_by_asc(user_state::Column::Id)
+        .all(conn)
+        .await?;
+
+    let requirements = requirement::Entity::find()
+        .filter(requirement::Column::StateId.is_in(licenses.iter().map(|(l, _)| l.state_id)))
+        .all(conn)
+        .await?;
+
+    let category_minimums = requirement_category::Entity::find()
+        .filter(requirement_category::Column::RequirementId.is_in(requirements.iter().map(|r| r.id)))
+        .order_by_asc(requirement_category::Column::Id)
+        .all(conn)
+        .await?;
+
+    Ok(licenses
+        .into_iter()
+        .map(|(license, state)| {
+            let requirement = requirements
+                .iter()
+                .find(|r| r.state_id == license.state_id && r.profession == license.profession)
+                .cloned();
+            let (new_admittee_minimums, category_minimums) = category_minimums
+                .iter()
+                .filter(|c| requirement.as_ref().is_some_and(|r| r.id == c.requirement_id))
+                .cloned()
+                .partition(|c| c.new_admittee);
+            License {
+                state_code: state.map(|s| s.name).unwrap_or_default(),
+                requirement,
+                category_minimums,
+                new_admittee_minimums,
+                license,
+            }
+        })
+        .collect())
+}
@@ -1,18 +1,52 @@
-use axum::{Router, routing::{post, get}, http::{Method, HeaderValue}};
+use axum::{Router, extract::DefaultBodyLimit, routing::{post, get, put}, http::{Method, HeaderValue}};
 use sea_orm::{Database, DatabaseConnection};
 use std::env;
+use std::sync::Arc;
 use tower_cookies::CookieManagerLayer;
 use tower_http::cors::CorsLayer;
 
+mod admin;
+mod auth;
+mod blob_store;
+mod budget;
+mod catalog;
+mod category;
+mod certificate_parser;
+mod certificates;
+mod compliance;
+mod credits;
+mod currency;
+mod forms;
+mod hours_history;
+mod import;
+mod ingest;
+mod jurisdictions;
+mod ledger;
+mod license_status;
+mod licenses;
 mod login;
+mod pdf;
+mod periods;
+mod plan;
+mod profession;
 mod recommendations;
 mod register;
+mod reports;
+mod reviews;
+mod search;
+mod sessions;
+#[cfg(test)]
+mod test_support;
 mod update;
 mod user_details;
+mod versioning;
 
 #[derive(Clone)]
 struct AppState {
     conn: DatabaseConnection,
+    blobs: Arc<dyn blob_store::BlobStore>,
+    max_certificate_bytes: usize,
+    forms_dir: std::path::PathBuf,
 }
 
 pub const SALT: &str = "xfpgsctjdluhayufpdj8glbvhukrlstjbgdbljrl4p9fjlgdj476grj7hskul47gpj";
@@ -20,6 +54,16 @@ pub const SALT: &str = "xfpgsctjdluhayufpdj8glbvhukrlstjbgdbljrl4p9fjlgdj476grj7
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
+
+    // Log level comes from RUST_LOG, e.g. `RUST_LOG=debug`. SQL statements
+    // are only logged when asked for, with `sqlx=info`.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info,sqlx=warn")),
+        )
+        .init();
+
     let db_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set in .env file");
     let host = env::var("HOST").expect("HOST is not set in .env file");
     let port = env::var("PORT").expect("PORT is not set in .env file");
@@ -33,20 +77,55 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("Database connection failed");
 
-    let state = AppState { conn };
+    // `ingest` loads a provider feed into the course catalog instead of serving
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("ingest") {
+        return ingest::run_cli(&conn, &args[1..]).await;
+    }
+
+    // Uploaded certificates are kept on the local filesystem
+    let certificate_dir = env::var("CERTIFICATE_DIR")
+        .unwrap_or_else(|_| "./certificates".to_string());
+    let max_certificate_bytes = env::var("MAX_CERTIFICATE_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(certificates::DEFAULT_MAX_CERTIFICATE_BYTES);
+
+    // Jurisdiction certification forms are read from JSON templates
+    let forms_dir = env::var("FORMS_DIR").unwrap_or_else(|_| "./forms".to_string());
+
+    // Expired compliance periods are closed in the background
+    let rollover_interval = env::var("ROLLOVER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(periods::DEFAULT_ROLLOVER_INTERVAL_SECS);
+    tokio::spawn(periods::run_rollover_job(
+        conn.clone(),
+        std::time::Duration::from_secs(rollover_interval),
+    ));
+
+    let state = AppState {
+        conn,
+        blobs: Arc::new(blob_store::LocalBlobStore::new(certificate_dir)),
+        max_certificate_bytes,
+        forms_dir: forms_dir.into(),
+    };
 
     // Configure CORS securely - only allow specific frontend origin
     let cors = CorsLayer::new()
         .allow_origin(frontend_origin.parse::<HeaderValue>().expect("Invalid FRONTEND_URL"))
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers([
             axum::http::header::CONTENT_TYPE,
             axum::http::header::AUTHORIZATION,
             axum::http::header::ACCEPT,
+            axum::http::header::IF_MATCH,
         ])
         .expose_headers([
             axum::http::header::CONTENT_TYPE,
             axum::http::header::AUTHORIZATION,
+            axum::http::header::CONTENT_DISPOSITION,
+            axum::http::header::ETAG,
         ])
         .allow_credentials(true)
         .max_age(std::time::Duration::from_secs(3600));
@@ -56,6 +135,84 @@ async fn main() -> anyhow::Result<()> {
         .route("/register", post(register::register))
         .route("/user/details", get(user_details::user_details))
         .route("/user/hours", post(update::update_hours))
+        .route("/user/hours/log", post(update::log_hours))
+        .route("/user/hours/history", get(hours_history::hours_history))
+        .route("/user/hours/history/{id}/revert", post(hours_history::revert_hours_change))
+        .route("/user/compliance", get(compliance::get_compliance))
+        .route("/user/compliance/history", get(periods::compliance_history))
+        .route(
+            "/user/jurisdictions",
+            get(jurisdictions::list_jurisdictions).post(jurisdictions::add_jurisdiction),
+        )
+        .route(
+            "/user/jurisdictions/{id}",
+            put(jurisdictions::update_jurisdiction).delete(jurisdictions::delete_jurisdiction),
+        )
+        .route("/user/credits", get(ledger::list_credits).post(ledger::create_credit))
+        .route("/user/credits/{id}", put(ledger::update_credit).delete(ledger::delete_credit))
+        .route("/user/credits/convert", post(credits::convert_minutes))
+        .route("/user/credits/import/preview", post(import::preview_import))
+        .route("/user/credits/import/commit", post(import::commit_import))
+        .route(
+            "/user/credits/{id}/certificates",
+            get(certificates::list_certificates)
+                .post(certificates::upload_certificate)
+                // Leave room for the multipart framing around the file itself
+                .layer(DefaultBodyLimit::max(max_certificate_bytes + 64 * 1024)),
+        )
+        .route(
+            "/user/certificates/extract",
+            post(certificates::extract_certificate)
+                .layer(DefaultBodyLimit::max(max_certificate_bytes + 64 * 1024)),
+        )
+        .route(
+            "/user/certificates/{id}",
+            get(certificates::download_certificate).delete(certificates::delete_certificate),
+        )
+        .route("/user/reports/{license_id}", get(reports::compliance_report))
+        .route("/user/forms", get(forms::list_forms))
+        .route("/user/forms/{key}/{license_id}", get(forms::render_form))
+        .route("/admin/providers", get(admin::list_providers).post(admin::create_provider))
+        .route("/admin/providers/{id}", put(admin::update_provider))
+        .route("/admin/providers/{id}/retire", post(admin::retire_provider))
+        .route("/admin/courses", get(admin::list_courses).post(admin::create_course))
+        .route("/admin/courses/{id}", put(admin::update_course))
+        .route("/admin/courses/{id}/retire", post(admin::retire_course))
+        .route("/admin/courses/{id}/sessions", post(sessions::create_session))
+        .route("/admin/sessions/{id}", put(sessions::update_session))
+        .route("/admin/sessions/{id}/cancel", post(sessions::cancel_session))
+        .route("/admin/reviews", get(reviews::moderation_queue))
+        .route("/admin/reviews/{id}/hide", post(reviews::hide_review))
+        .route("/admin/reviews/{id}/publish", post(reviews::publish_review))
+        .route("/admin/audit", get(admin::list_audit))
+        .route("/admin/ingest", post(ingest::ingest_feed))
+        .route("/courses/search", get(search::search_courses))
+        .route("/courses/{id}/sessions", get(sessions::list_course_sessions))
+        .route("/courses/{id}/reviews", get(reviews::list_reviews))
+        .route("/courses/{id}/review", put(reviews::save_review).delete(reviews::delete_review))
+        .route(
+            "/sessions/{id}/registration",
+            post(sessions::register_for_session).delete(sessions::unregister_from_session),
+        )
+        .route("/user/sessions", get(sessions::my_sessions))
+        .route("/user/bookmarks", get(plan::list_bookmarks))
+        .route("/user/bookmarks/{course_id}", put(plan::save_bookmark).delete(plan::delete_bookmark))
+        .route("/user/plan", get(plan::get_plan))
+        .route("/user/plan/{course_id}/complete", post(plan::complete_planned))
+        .route("/user/memberships", get(budget::list_memberships))
+        .route(
+            "/user/memberships/{provider_id}",
+            put(budget::add_membership).delete(budget::delete_membership),
+        )
+        .route(
+            "/user/budget/{year}",
+            get(budget::get_budget).put(budget::save_budget).delete(budget::delete_budget),
+        )
+        .route(
+            "/user/calendar",
+            post(sessions::reset_calendar_token).delete(sessions::delete_calendar_token),
+        )
+        .route("/calendar/{file}", get(sessions::calendar_feed))
         .route("/recommendations", post(recommendations::get_recommendations))
         .layer(CookieManagerLayer::new())
         .layer(cors)
@@ -0,0 +1,111 @@
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+const PAGE_WIDTH: f32 = 215.9;
+const PAGE_HEIGHT: f32 = 279.4;
+const MARGIN: f32 = 18.0;
+/// Rough width of a Helvetica character as a share of the font size, used to
+/// wrap and truncate text without measuring glyphs
+const CHAR_WIDTH: f32 = 0.5;
+const PT_TO_MM: f32 = 0.3528;
+
+/// A minimal top-to-bottom text layout on US Letter pages using the built-in
+/// Helvetica fonts, which is all the reports and forms need.
+pub struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Distance of the next line's baseline from the bottom of the page
+    y: f32,
+}
+
+impl PdfWriter {
+    pub fn new(title: &str) -> Result<Self, printpdf::Error> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(PdfWriter {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    /// Width available between the margins, in millimetres
+    pub fn content_width(&self) -> f32 {
+        PAGE_WIDTH - 2.0 * MARGIN
+    }
+
+    fn line_height(size: f32) -> f32 {
+        size * PT_TO_MM * 1.4
+    }
+
+    /// Starts a new page if there isn't room for `height` more millimetres.
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    pub fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Writes one line of cells, each starting `x` millimetres from the left
+    /// margin and cut short to fit `width`.
+    pub fn row(&mut self, cells: &[(f32, f32, &str)], size: f32, bold: bool) {
+        let height = Self::line_height(size);
+        self.reserve(height);
+        self.y -= height;
+        let font = if bold { &self.bold } else { &self.regular };
+        for (x, width, text) in cells {
+            let text = fit(text, *width, size);
+            self.layer
+                .use_text(text, size, Mm(MARGIN + x), Mm(self.y), font);
+        }
+    }
+
+    /// Writes a paragraph, wrapping at word boundaries to the page width.
+    pub fn paragraph(&mut self, text: &str, size: f32, bold: bool) {
+        let max_chars = (self.content_width() / (size * PT_TO_MM * CHAR_WIDTH)) as usize;
+        for source_line in text.lines() {
+            let mut line = String::new();
+            for word in source_line.split_whitespace() {
+                if !line.is_empty() && line.len() + 1 + word.len() > max_chars {
+                    self.row(&[(0.0, self.content_width(), &line)], size, bold);
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            self.row(&[(0.0, self.content_width(), &line)], size, bold);
+        }
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, printpdf::Error> {
+        self.doc.save_to_bytes()
+    }
+}
+
+/// Keeps text within a cell, replacing characters the built-in fonts can't show
+fn fit(text: &str, width: f32, size: f32) -> String {
+    let max_chars = ((width / (size * PT_TO_MM * CHAR_WIDTH)) as usize).max(1);
+    let cleaned: Vec<char> = text
+        .chars()
+        .map(|c| if (c as u32) < 0x20 || (c as u32) > 0xFF { '?' } else { c })
+        .collect();
+    if cleaned.len() <= max_chars {
+        cleaned.into_iter().collect()
+    } else {
+        let mut cut: String = cleaned[..max_chars.saturating_sub(3)].iter().collect();
+        cut.push_str("...");
+        cut
+    }
+}
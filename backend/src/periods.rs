@@ -0,0 +1,272 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{Months, NaiveDate};
+use entity::{Credits, compliance_period, user_state};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use crate::licenses::License;
+
+/// How often the rollover job runs when `ROLLOVER_INTERVAL_SECS` isn't set
+pub const DEFAULT_ROLLOVER_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Closes every compliance period whose deadline is before `today`.
+///
+/// Each closed period's final totals and evaluation are written to the
+/// history table and the license moves on to its next period. A license that
+/// has missed several deadlines has each of them closed in turn. Returns the
+/// number of periods closed.
+pub async fn roll_over_expired(conn: &DatabaseConnection, today: NaiveDate) -> Result<usize, DbErr> {
+    // Renewal dates are stored as ISO dates, so they compare correctly as text
+    let user_ids: Vec<i32> = user_state::Entity::find()
+        .select_only()
+        .column(user_state::Column::UserId)
+        .distinct()
+        .filter(user_state::Column::RemovedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(user_state::Column::RenewalDate.lt(today.to_string()))
+                .add(user_state::Column::DeadlineOverride.lt(today)),
+        )
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let mut closed = 0;
+    for user_id in user_ids {
+        let txn = conn.begin().await?;
+        let licenses = crate::licenses::for_user(&txn, user_id).await?;
+        let entries = crate::ledger::entries_for_user(&txn, user_id).await?;
+
+        for mut license in licenses {
+            let original = license.license.clone();
+            while let Some(deadline) = license.deadline().filter(|d| *d < today) {
+                let totals = crate::ledger::totals(std::slice::from_ref(&license), &entries)
+                    .remove(&license.license.id)
+                    .unwrap_or_default();
+                // Evaluated the day after the deadline, so anything outstanding is deficient
+                let evaluation = crate::compliance::evaluate(
+                    &license,
+                    &totals,
+                    Some(deadline),
+                    deadline.succ_opt().unwrap_or(deadline),
+                );
+
+                compliance_period::ActiveModel {
+                    user_state_id: Set(license.license.id),
+                    period_start: Set(license.period_start()),
+                    period_end: Set(deadline),
+                    required: Set(evaluation.required),
+                    completed: Set(evaluation.completed),
+                    status: Set(evaluation.status.as_str().to_string()),
+                    summary: Set(serde_json::to_value(&evaluation).unwrap_or_default()),
+                    closed_at: Set(chrono::Utc::now()),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?;
+
+                // An override only ever applies to the period it was set for, so
+                // the next period keeps the regular renewal cycle
+                let regular = license.renewal_date().unwrap_or(deadline);
+                let next_deadline = regular
+                    .checked_add_months(Months::new(license.period_months()))
+                    .unwrap_or(deadline);
+                license.license.period_start = deadline.succ_opt();
+                license.license.renewal_date = Some(next_deadline.to_string());
+                license.license.deadline_override = None;
+                closed += 1;
+            }
+
+            if license.license != original {
+                let mut active: user_state::ActiveModel = original.into();
+                active.period_start = Set(license.license.period_start);
+                active.renewal_date = Set(license.license.renewal_date.clone());
+                active.deadline_override = Set(license.license.deadline_override);
+                active.version = Set(license.license.version + 1);
+                active.update(&txn).await?;
+                crate::versioning::touch_user(&txn, user_id).await?;
+            }
+        }
+
+        txn.commit().await?;
+    }
+
+    Ok(closed)
+}
+
+/// Runs the rollover job forever, once at startup and then every `interval`.
+pub async fn run_rollover_job(conn: DatabaseConnection, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let today = chrono::Utc::now().date_naive();
+        match roll_over_expired(&conn, today).await {
+            Ok(0) => {}
+            Ok(closed) => tracing::info!(closed, "Closed expired compliance periods"),
+            Err(e) => tracing::error!(error = %e, "Compliance period rollover failed"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    license_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct CompliancePeriodResponse {
+    id: i32,
+    license_id: i32,
+    state_code: String,
+    profession: String,
+    period_start: Option<NaiveDate>,
+    period_end: NaiveDate,
+    required: Credits,
+    completed: Credits,
+    status: String,
+    summary: serde_json::Value,
+    closed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CompliancePeriodResponse {
+    fn new(period: compliance_period::Model, license: &License) -> Self {
+        CompliancePeriodResponse {
+            id: period.id,
+            license_id: period.user_state_id,
+            state_code: license.state_code.clone(),
+            profession: license.license.profession.clone(),
+            period_start: period.period_start,
+            period_end: period.period_end,
+            required: period.required,
+            completed: period.completed,
+            status: period.status,
+            summary: period.summary,
+            closed_at: period.closed_at,
+        }
+    }
+}
+
+/// Lists the user's closed compliance periods, most recent first.
+pub async fn compliance_history(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<CompliancePeriodResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    if let Some(license_id) = query.license_id
+        && !licenses.iter().any(|l| l.license.id == license_id)
+    {
+        return Err((StatusCode::NOT_FOUND, "License not found"));
+    }
+    let license_ids: Vec<i32> = licenses
+        .iter()
+        .map(|l| l.license.id)
+        .filter(|id| query.license_id.is_none_or(|wanted| wanted == *id))
+        .collect();
+
+    let periods = compliance_period::Entity::find()
+        .filter(compliance_period::Column::UserStateId.is_in(license_ids))
+        .order_by_desc(compliance_period::Column::PeriodEnd)
+        .order_by_desc(compliance_period::Column::Id)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(
+        periods
+            .into_iter()
+            .filter_map(|period| {
+                let license = licenses.iter().find(|l| l.license.id == period.user_state_id)?;
+                Some(CompliancePeriodResponse::new(period, license))
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn licenses_roll_over_every_missed_deadline_once() {
+        let conn = test_support::database().await;
+        let (user, _) = test_support::sign_in(&conn, "ann").await;
+        let ny = test_support::add_license(&conn, user.id, "NY", "2024-06-30").await;
+        let license = crate::licenses::for_user(&conn, user.id).await.unwrap().remove(0);
+        let months = Months::new(license.period_months());
+
+        let first_deadline = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let second_deadline = first_deadline.checked_add_months(months).unwrap();
+        let today = second_deadline.succ_opt().unwrap();
+
+        // Credit earned in each of the two missed periods
+        let earned = [
+            (first_deadline - chrono::Days::new(10), 5),
+            (first_deadline + chrono::Days::new(10), 3),
+        ];
+        for (completed_on, credits) in earned {
+            let request = serde_json::from_value(json!({
+                "title": "Evidence",
+                "completed_on": completed_on,
+                "categories": [{"category": "general", "credits": credits}],
+            }))
+            .unwrap();
+            crate::ledger::insert_entry(&conn, user.id, request).await.unwrap();
+        }
+
+        assert_eq!(roll_over_expired(&conn, today).await.unwrap(), 2);
+
+        let periods = compliance_period::Entity::find()
+            .filter(compliance_period::Column::UserStateId.eq(ny.id))
+            .order_by_asc(compliance_period::Column::PeriodEnd)
+            .all(&conn)
+            .await
+            .unwrap();
+        let summary: Vec<_> = periods
+            .iter()
+            .map(|p| (p.period_start, p.period_end, p.completed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    first_deadline.checked_sub_months(months).and_then(|d| d.succ_opt()),
+                    first_deadline,
+                    Credits::from_whole(5),
+                ),
+                (first_deadline.succ_opt(), second_deadline, Credits::from_whole(3)),
+            ]
+        );
+
+        let rolled = user_state::Entity::find_by_id(ny.id).one(&conn).await.unwrap().unwrap();
+        assert_eq!(rolled.period_start, second_deadline.succ_opt());
+        assert_eq!(
+            rolled.renewal_date,
+            Some(second_deadline.checked_add_months(months).unwrap().to_string())
+        );
+
+        // Closed periods aren't rolled over again
+        assert_eq!(roll_over_expired(&conn, today).await.unwrap(), 0);
+        let periods = compliance_period::Entity::find()
+            .filter(compliance_period::Column::UserStateId.eq(ny.id))
+            .all(&conn)
+            .await
+            .unwrap();
+        assert_eq!(periods.len(), 2);
+    }
+}
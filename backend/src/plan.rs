@@ -0,0 +1,505 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use entity::{course_bookmark, credit_entry, credit_entry_category};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait, TryIntoModel,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use tower_cookies::Cookies;
+
+use crate::catalog::{Approval, CatalogCourse, CourseResponse};
+use crate::compliance::Evaluation;
+use crate::hours_history::Source;
+use crate::ledger::{CategoryCredits, CreditEntryRequest, CreditEntryResponse, Entry};
+use crate::licenses::License;
+use crate::versioning::IfMatch;
+
+/// Where a shortlisted course stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeserializeFromStr, SerializeDisplay)]
+pub enum BookmarkStatus {
+    /// Kept for later without a commitment to take it
+    Saved,
+    /// Counted in the user's plan
+    Planned,
+}
+
+#[derive(Debug)]
+pub struct ParseBookmarkStatusError;
+
+impl fmt::Display for ParseBookmarkStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid bookmark status")
+    }
+}
+
+impl std::error::Error for ParseBookmarkStatusError {}
+
+impl FromStr for BookmarkStatus {
+    type Err = ParseBookmarkStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "saved" => Ok(BookmarkStatus::Saved),
+            "planned" => Ok(BookmarkStatus::Planned),
+            _ => Err(ParseBookmarkStatusError),
+        }
+    }
+}
+
+impl fmt::Display for BookmarkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BookmarkStatus::Saved => "saved",
+            BookmarkStatus::Planned => "planned",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Serialize)]
+pub struct BookmarkResponse {
+    status: BookmarkStatus,
+    license_id: Option<i32>,
+    planned_for: Option<NaiveDate>,
+    course: CourseResponse,
+}
+
+impl BookmarkResponse {
+    fn new(bookmark: &course_bookmark::Model, course: &CatalogCourse) -> Self {
+        BookmarkResponse {
+            status: bookmark.status.parse().unwrap_or(BookmarkStatus::Saved),
+            license_id: bookmark.user_state_id,
+            planned_for: bookmark.planned_for,
+            course: course.into(),
+        }
+    }
+}
+
+/// The user's bookmarks with their courses, in the order they were added.
+/// Bookmarks on courses that have since been retired are left out.
+pub async fn bookmarks_for_user<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+) -> Result<Vec<(course_bookmark::Model, CatalogCourse)>, (StatusCode, &'static str)> {
+    let bookmarks = course_bookmark::Entity::find()
+        .filter(course_bookmark::Column::UserId.eq(user_id))
+        .order_by_asc(course_bookmark::Column::Id)
+        .all(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let catalog = crate::catalog::load_courses(conn, false)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(bookmarks
+        .into_iter()
+        .filter_map(|bookmark| {
+            let course = catalog.iter().find(|c| c.course.id == bookmark.course_id)?.clone();
+            Some((bookmark, course))
+        })
+        .collect())
+}
+
+pub async fn list_bookmarks(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<BookmarkResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let bookmarks = bookmarks_for_user(&state.conn, user.id).await?;
+
+    Ok(Json(
+        bookmarks
+            .iter()
+            .map(|(bookmark, course)| BookmarkResponse::new(bookmark, course))
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct BookmarkRequest {
+    status: BookmarkStatus,
+    /// Counts a planned course towards one license only
+    license_id: Option<i32>,
+    /// When the user means to take the course; defaults to today in the plan
+    planned_for: Option<NaiveDate>,
+}
+
+/// Saves or plans a course, replacing any earlier bookmark for it.
+pub async fn save_bookmark(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+    Json(data): Json<BookmarkRequest>,
+) -> Result<Json<BookmarkResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    crate::ledger::check_license(&state.conn, user.id, data.license_id).await?;
+
+    let catalog = crate::catalog::load_courses(&state.conn, false)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let course = catalog
+        .iter()
+        .find(|c| c.course.id == course_id)
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+
+    let existing = course_bookmark::Entity::find()
+        .filter(course_bookmark::Column::UserId.eq(user.id))
+        .filter(course_bookmark::Column::CourseId.eq(course_id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let now = Utc::now();
+    let mut active = match existing {
+        Some(bookmark) => bookmark.into(),
+        None => course_bookmark::ActiveModel {
+            user_id: Set(user.id),
+            course_id: Set(course_id),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    };
+    active.status = Set(data.status.to_string());
+    active.user_state_id = Set(data.license_id);
+    active.planned_for = Set(data.planned_for);
+    active.updated_at = Set(now);
+    let saved = active
+        .save(&state.conn)
+        .await
+        .and_then(|saved| saved.try_into_model())
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save bookmark"))?;
+
+    Ok(Json(BookmarkResponse::new(&saved, course)))
+}
+
+pub async fn delete_bookmark(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let deleted = course_bookmark::Entity::delete_many()
+        .filter(course_bookmark::Column::UserId.eq(user.id))
+        .filter(course_bookmark::Column::CourseId.eq(course_id))
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete bookmark"))?;
+    if deleted.rows_affected == 0 {
+        return Err((StatusCode::NOT_FOUND, "Bookmark not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The approval that would grant credit towards a license for a course
+/// completed on `date`.
+pub fn license_approval<'a>(course: &'a CatalogCourse, license: &License, date: NaiveDate) -> Option<&'a Approval> {
+    course.approval_for(&license.state_code, &license.license.profession, date)
+}
+
+/// A ledger entry for a course as if it had been completed towards one
+/// license on `completed_on`, so hypothetical credit is counted with the same
+/// rules as real credit. Credits follow the jurisdiction's approval where it
+/// breaks them down.
+pub fn course_entry(user_id: i32, course: &CatalogCourse, license: &License, completed_on: NaiveDate) -> Entry {
+    let approval = license_approval(course, license, completed_on);
+    Entry {
+        entry: credit_entry::Model {
+            id: 0,
+            user_id,
+            user_state_id: Some(license.license.id),
+            kind: crate::ledger::KIND_COURSE.to_string(),
+            title: course.course.title.clone(),
+            provider: Some(course.provider.name.clone()),
+            completed_on,
+            format: None,
+            approval_number: approval.and_then(|a| a.approval_number.clone()),
+            notes: None,
+            minutes: Some(course.course.duration_minutes),
+            created_at: Utc::now(),
+            version: 1,
+            course_id: Some(course.course.id),
+            amount_paid_cents: None,
+            currency: None,
+        },
+        categories: course
+            .credit_split_for(approval)
+            .into_iter()
+            .map(|(category, credits)| credit_entry_category::Model {
+                id: 0,
+                credit_entry_id: 0,
+                category: category.to_string(),
+                credits,
+            })
+            .collect(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct PlannedCourse {
+    course_id: i32,
+    title: String,
+    planned_for: Option<NaiveDate>,
+    /// Whether the jurisdiction has approved the course for the planned date
+    approved: bool,
+    approval_number: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LicensePlan {
+    license_id: i32,
+    state_code: String,
+    profession: String,
+    /// Where the license stands today
+    current: Evaluation,
+    /// Where it would stand with every planned course completed
+    projected: Evaluation,
+    /// Planned courses that count towards this license
+    courses: Vec<PlannedCourse>,
+}
+
+#[derive(Serialize)]
+pub struct PlanResponse {
+    as_of: NaiveDate,
+    licenses: Vec<LicensePlan>,
+    planned: Vec<BookmarkResponse>,
+}
+
+/// Projects each license's compliance if every planned course is completed
+/// on its planned date. Courses planned after a deadline don't count towards
+/// that period, just as late credit wouldn't.
+pub async fn get_plan(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<PlanResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let today = Utc::now().date_naive();
+
+    let (licenses, current) = crate::ledger::totals_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let mut entries = crate::ledger::entries_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let planned: Vec<_> = bookmarks_for_user(&state.conn, user.id)
+        .await?
+        .into_iter()
+        .filter(|(b, _)| b.status == BookmarkStatus::Planned.to_string())
+        .collect();
+    // A course planned without a license counts towards each of them
+    let mut planned_by_license: HashMap<i32, Vec<(&course_bookmark::Model, &CatalogCourse)>> = HashMap::new();
+    for (bookmark, course) in &planned {
+        for license in licenses
+            .iter()
+            .filter(|l| bookmark.user_state_id.is_none_or(|id| id == l.license.id))
+        {
+            let entry = course_entry(user.id, course, license, bookmark.planned_for.unwrap_or(today));
+            if entry.applies_to(license) {
+                planned_by_license.entry(license.license.id).or_default().push((bookmark, course));
+                entries.push(entry);
+            }
+        }
+    }
+    let projected = crate::ledger::totals(&licenses, &entries);
+
+    let plans = licenses
+        .iter()
+        .map(|license| {
+            let deadline = license.deadline();
+            let current = current.get(&license.license.id).cloned().unwrap_or_default();
+            let projected = projected.get(&license.license.id).cloned().unwrap_or_default();
+            LicensePlan {
+                license_id: license.license.id,
+                state_code: license.state_code.clone(),
+                profession: license.license.profession.clone(),
+                current: crate::compliance::evaluate(license, &current, deadline, today),
+                projected: crate::compliance::evaluate(license, &projected, deadline, today),
+                courses: planned_by_license
+                    .remove(&license.license.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(bookmark, course)| {
+                        let approval = license_approval(course, license, bookmark.planned_for.unwrap_or(today));
+                        PlannedCourse {
+                            course_id: course.course.id,
+                            title: course.course.title.clone(),
+                            planned_for: bookmark.planned_for,
+                            approved: approval.is_some(),
+                            approval_number: approval.and_then(|a| a.approval_number.clone()),
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Json(PlanResponse {
+        as_of: today,
+        licenses: plans,
+        planned: planned
+            .iter()
+            .map(|(bookmark, course)| BookmarkResponse::new(bookmark, course))
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct CompleteRequest {
+    /// Defaults to today
+    completed_on: Option<NaiveDate>,
+    /// Defaults to the number on the license's approval for the course
+    approval_number: Option<String>,
+    notes: Option<String>,
+    /// Defaults to the course's price for the user
+    amount_paid_cents: Option<u32>,
+}
+
+/// Records a planned course as completed credit and takes it off the plan.
+pub async fn complete_planned(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+    if_match: IfMatch,
+    Json(data): Json<CompleteRequest>,
+) -> Result<Json<CreditEntryResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    // A planned course can still be completed after it's been retired
+    let bookmark = course_bookmark::Entity::find()
+        .filter(course_bookmark::Column::UserId.eq(user.id))
+        .filter(course_bookmark::Column::CourseId.eq(course_id))
+        .filter(course_bookmark::Column::Status.eq(BookmarkStatus::Planned.to_string()))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course is not in your plan"))?;
+    let course = crate::catalog::load_course(&state.conn, course_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+    let memberships = crate::budget::memberships(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let price = course.price_for(memberships.contains(&course.provider.id));
+
+    // Credit planned for one license is recorded the way its jurisdiction
+    // approved the course
+    let completed_on = data.completed_on.unwrap_or_else(|| Utc::now().date_naive());
+    let licenses = crate::licenses::for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let approval = licenses
+        .iter()
+        .find(|l| bookmark.user_state_id == Some(l.license.id))
+        .and_then(|l| license_approval(&course, l, completed_on));
+
+    let request = CreditEntryRequest {
+        title: course.course.title.clone(),
+        provider: Some(course.provider.name.clone()),
+        completed_on,
+        format: Some(course.format_label()).filter(|f| !f.is_empty()),
+        approval_number: data
+            .approval_number
+            .or_else(|| approval.and_then(|a| a.approval_number.clone())),
+        notes: data.notes,
+        minutes: Some(course.course.duration_minutes as u32),
+        license_id: bookmark.user_state_id,
+        categories: course
+            .credit_split_for(approval)
+            .into_iter()
+            .map(|(category, credits)| CategoryCredits { category, credits })
+            .collect(),
+        course_id: Some(course.course.id),
+        amount_paid_cents: Some(data.amount_paid_cents.unwrap_or(price as u32)),
+        currency: Some(course.currency()),
+    };
+    crate::ledger::check_entry(&request)?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    let (_, before) = crate::ledger::totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let entry = crate::ledger::insert_entry(&txn, user.id, request)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+    let (_, after) = crate::ledger::totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    crate::hours_history::record_changes(&txn, user.id, &entry.entry, &before, &after, Source::Plan)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+
+    course_bookmark::Entity::delete_by_id(bookmark.id)
+        .exec(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save credit entry"))?;
+
+    Ok(Json(entry.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use entity::course;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn retired_courses_can_still_be_completed() {
+        let state = test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (user, cookies) = test_support::sign_in(&conn, "ann").await;
+        let ny = test_support::add_license(&conn, user.id, "NY", "2027-06-30").await;
+        let course = course::Entity::find().one(&conn).await.unwrap().unwrap();
+
+        let saved = save_bookmark(
+            State(state.clone()),
+            cookies.clone(),
+            Path(course.id),
+            Json(serde_json::from_value(json!({"status": "planned", "license_id": ny.id})).unwrap()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved.0.license_id, Some(ny.id));
+
+        let mut retired: course::ActiveModel = course.clone().into();
+        retired.retired_at = Set(Some(Utc::now()));
+        retired.update(&conn).await.unwrap();
+        assert!(bookmarks_for_user(&conn, user.id).await.unwrap().is_empty());
+
+        let entry = complete_planned(
+            State(state.clone()),
+            cookies.clone(),
+            Path(course.id),
+            IfMatch::Any,
+            Json(serde_json::from_value(json!({"completed_on": "2026-02-01"})).unwrap()),
+        )
+        .await
+        .unwrap();
+        let entry = serde_json::to_value(&entry.0).unwrap();
+        assert_eq!(entry["title"], course.title.as_str());
+        assert_eq!(entry["license_id"], ny.id);
+        assert!(course_bookmark::Entity::find().one(&conn).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// The licensed profession a continuing-education requirement applies to.
+///
+/// Stored in the database using its display name, which matches the keys the
+/// frontend uses in `CE_REQUIREMENTS` and `TOPIC_SUGGESTIONS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
+pub enum Profession {
+    #[default]
+    Attorney,
+    Cpa,
+    Engineer,
+    Architect,
+}
+
+#[derive(Debug)]
+pub struct ParseProfessionError;
+
+impl fmt::Display for ParseProfessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid profession")
+    }
+}
+
+impl std::error::Error for ParseProfessionError {}
+
+impl FromStr for Profession {
+    type Err = ParseProfessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "ATTORNEY" => Ok(Profession::Attorney),
+            "CPA" => Ok(Profession::Cpa),
+            "ENGINEER" => Ok(Profession::Engineer),
+            "ARCHITECT" => Ok(Profession::Architect),
+            _ => Err(ParseProfessionError),
+        }
+    }
+}
+
+impl fmt::Display for Profession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Profession::Attorney => "Attorney",
+            Profession::Cpa => "CPA",
+            Profession::Engineer => "Engineer",
+            Profession::Architect => "Architect",
+        };
+        write!(f, "{}", name)
+    }
+}
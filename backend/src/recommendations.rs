@@ -1,9 +1,17 @@
 use axum::{Json, extract::State, http::StatusCode};
+use chrono::NaiveDate;
+use entity::Credits;
 use serde::{Deserialize, Serialize};
 use tower_cookies::Cookies;
-use sea_orm::{EntityTrait, ColumnTrait, QueryFilter};
+use std::collections::HashMap;
 use std::env;
 
+use crate::catalog::{Approval, CatalogCourse};
+use crate::compliance::Evaluation;
+use crate::credits::CreditUnit;
+use crate::ledger::{CategoryCredits, LicenseTotals};
+use crate::licenses::License;
+
 #[derive(Deserialize)]
 pub struct RecommendationsRequest {
     pub interests: String,
@@ -19,6 +27,37 @@ pub struct CourseRecommendation {
     pub price: String,
     pub url: String,
     pub ai_reason: String,
+    /// Jurisdictions known to grant credit for the course
+    #[serde(default)]
+    pub approvals: Vec<Approval>,
+    /// Average published rating out of five
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<f64>,
+    #[serde(default)]
+    pub review_count: u32,
+    /// The user's shortfalls the course would help close, most urgent first
+    #[serde(default)]
+    pub rationale: Vec<GapRationale>,
+}
+
+/// How a course would count towards one license's outstanding requirement.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GapRationale {
+    pub license_id: i32,
+    pub state_code: String,
+    pub profession: String,
+    /// Whether the jurisdiction has approved the course; credit for courses it
+    /// hasn't may need to be applied for
+    pub approved: bool,
+    pub approval_number: Option<String>,
+    /// Hours of the remaining requirement the course would cover
+    pub hours: Credits,
+    /// Hours the license still needs in total
+    pub remaining: Credits,
+    /// Category shortfalls the course would cover
+    pub categories: Vec<CategoryCredits>,
+    pub deadline: Option<NaiveDate>,
+    pub days_to_deadline: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -56,29 +95,17 @@ pub async fn get_recommendations(
     Json(request): Json<RecommendationsRequest>,
 ) -> Result<Json<RecommendationsResponse>, (StatusCode, &'static str)> {
     // Verify session
-    let session_token = cookies
-        .get("session")
-        .ok_or((StatusCode::UNAUTHORIZED, "Not logged in"))?
-        .value()
-        .to_string();
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
 
-    let user = entity::user::Entity::find()
-        .inner_join(entity::session::Entity)
-        .filter(entity::session::Column::Token.eq(session_token))
-        .one(&state.conn)
-        .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
-        .ok_or((StatusCode::FORBIDDEN, "Not logged in"))?;
-
-    // Get user's states
-    let user_states = entity::user_state::Entity::find()
-        .filter(entity::user_state::Column::UserId.eq(user.id))
-        .all(&state.conn)
+    // Get what each license still needs
+    let (licenses, totals) = crate::ledger::totals_for_user(&state.conn, user.id)
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let today = chrono::Utc::now().date_naive();
+    let shortfalls = shortfalls(&licenses, &totals, today);
 
-    let total_hours_needed: i32 = user_states.iter()
-        .map(|s| s.hours_complete)
+    let total_hours_needed: Credits = shortfalls.iter()
+        .map(|s| s.evaluation.remaining)
         .sum();
 
     // Sanitize user interests
@@ -87,18 +114,24 @@ pub async fn get_recommendations(
     // Get Gemini API key
     let api_key = env::var("GEMINI_API_KEY").unwrap_or_default();
 
+    // Pre-select courses from the catalog by shortfall and keyword matching
+    let catalog = crate::catalog::load_courses(&state.conn, false)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let selected = select_courses(&catalog, &safe_interests, user.id, &shortfalls, today);
+
     let recommendations = if api_key.is_empty() || safe_interests.is_empty() {
-        get_fallback_recommendations()
+        get_fallback_recommendations(&selected)
     } else {
         let start = std::time::Instant::now();
-        match call_gemini_api(&api_key, &user.fullname, total_hours_needed, &safe_interests).await {
+        match call_gemini_api(&api_key, &user.fullname, total_hours_needed, &safe_interests, &selected).await {
             Ok(recs) => {
                 println!("Gemini API call took {:?}", start.elapsed());
                 recs
             },
             Err(e) => {
                 eprintln!("Gemini API error: {}", e);
-                get_fallback_recommendations()
+                get_fallback_recommendations(&selected)
             }
         }
     };
@@ -106,125 +139,224 @@ pub async fn get_recommendations(
     Ok(Json(RecommendationsResponse { recommendations }))
 }
 
-fn get_fallback_recommendations() -> Vec<CourseRecommendation> {
-    vec![
-        CourseRecommendation {
-            title: "Professional Responsibility and Ethics".to_string(),
-            provider: "Massachusetts CLE".to_string(),
-            hours: 2.0,
-            topic: "Ethics".to_string(),
-            format: "Online".to_string(),
-            price: "$50".to_string(),
-            url: "https://www.massbar.org/continuing-legal-education".to_string(),
-            ai_reason: "Essential ethics training for maintaining professional standards".to_string(),
-        },
-        CourseRecommendation {
-            title: "Introduction to Legal Technology Tools".to_string(),
-            provider: "ABA Techshow".to_string(),
-            hours: 1.5,
-            topic: "Legal Tech".to_string(),
-            format: "Online".to_string(),
-            price: "Free".to_string(),
-            url: "https://www.techshow.com/education".to_string(),
-            ai_reason: "Learn practical technology tools for modern legal practice".to_string(),
-        },
+impl CourseRecommendation {
+    fn new(selected: &SelectedCourse, ai_reason: String) -> Self {
+        let course = selected.course;
         CourseRecommendation {
-            title: "Contract Drafting Essentials".to_string(),
-            provider: "Practising Law Institute".to_string(),
-            hours: 3.0,
-            topic: "Contract Law".to_string(),
-            format: "Self-Paced".to_string(),
-            price: "$99".to_string(),
-            url: "https://www.pli.edu/programs/contract-drafting".to_string(),
-            ai_reason: "Build strong foundation in contract drafting skills".to_string(),
-        },
-        CourseRecommendation {
-            title: "Business Law Foundations".to_string(),
-            provider: "Coursera".to_string(),
-            hours: 4.0,
-            topic: "Business Law".to_string(),
-            format: "Self-Paced".to_string(),
-            price: "Free".to_string(),
-            url: "https://www.coursera.org/courses?query=business%20law".to_string(),
-            ai_reason: "Comprehensive overview of key business law concepts".to_string(),
+            title: course.course.title.clone(),
+            provider: course.provider.name.clone(),
+            hours: course.course.credits.hundredths() as f32 / 100.0,
+            topic: course.topic_label(),
+            format: course.format_label(),
+            price: course.price_label(),
+            url: course.course.url.clone(),
+            ai_reason,
+            approvals: course.approvals.clone(),
+            rating: course.rating.map(|r| r.average),
+            review_count: course.rating.map(|r| r.count).unwrap_or_default(),
+            rationale: selected.rationale.clone(),
+        }
+    }
+}
+
+/// Recommends the selected courses by the shortfall they close, or by their
+/// catalog descriptions when they don't close any
+fn get_fallback_recommendations(selected: &[SelectedCourse]) -> Vec<CourseRecommendation> {
+    selected
+        .iter()
+        .map(|s| {
+            let reason = match s.rationale.first() {
+                Some(gap) => gap_reason(gap),
+                None => s.course.course.summary.clone().unwrap_or_else(|| {
+                    format!("Covers {} for your continuing education", s.course.topic_label())
+                }),
+            };
+            CourseRecommendation::new(s, reason)
+        })
+        .collect()
+}
+
+/// Describes a shortfall a course closes, e.g. "Counts for 2 of the 10 hours
+/// you still need in NY, including 1 Ethics, due in 45 days"
+fn gap_reason(gap: &GapRationale) -> String {
+    let mut reason = format!(
+        "Counts for {} of the {} hours you still need in {}",
+        gap.hours, gap.remaining, gap.state_code
+    );
+    if !gap.categories.is_empty() {
+        let categories: Vec<String> = gap
+            .categories
+            .iter()
+            .map(|c| format!("{} {}", c.credits, c.category.label()))
+            .collect();
+        reason.push_str(&format!(", including {}", categories.join(" and ")));
+    }
+    if let Some(days) = gap.days_to_deadline {
+        reason.push_str(&format!(", due in {} days", days));
+    }
+    if !gap.approved {
+        reason.push_str(" (not yet approved there)");
+    }
+    reason
+}
+
+/// A license with hours or category minimums still outstanding.
+struct Shortfall<'a> {
+    license: &'a License,
+    evaluation: Evaluation,
+}
+
+fn shortfalls<'a>(licenses: &'a [License], totals: &HashMap<i32, LicenseTotals>, today: NaiveDate) -> Vec<Shortfall<'a>> {
+    licenses
+        .iter()
+        .map(|license| {
+            let totals = totals.get(&license.license.id).cloned().unwrap_or_default();
+            Shortfall {
+                license,
+                evaluation: crate::compliance::evaluate(license, &totals, license.deadline(), today),
+            }
+        })
+        .filter(|s| {
+            s.evaluation.remaining > Credits::ZERO
+                || s.evaluation.categories.iter().any(|c| c.remaining > Credits::ZERO)
+        })
+        .collect()
+}
+
+/// How much nearer a deadline makes a shortfall count
+fn urgency(days_to_deadline: Option<i64>) -> i64 {
+    match days_to_deadline {
+        Some(days) if days <= crate::compliance::DEADLINE_WARNING_DAYS => 4,
+        Some(days) if days <= 180 => 2,
+        _ => 1,
+    }
+}
+
+/// What a course would close of one license's shortfall if taken now, with
+/// its weight in the ranking. Credit is converted with the jurisdiction's own
+/// rules, and courses held after the deadline don't count.
+fn close_gap(user_id: i32, course: &CatalogCourse, shortfall: &Shortfall, today: NaiveDate) -> Option<(i64, GapRationale)> {
+    let license = shortfall.license;
+    let evaluation = &shortfall.evaluation;
+    let taken_on = course.taken_on(today);
+    let entry = crate::plan::course_entry(user_id, course, license, taken_on);
+    if !entry.applies_to(license) {
+        return None;
+    }
+    let credits = entry.applied_credits(CreditUnit::for_requirement(license.requirement.as_ref()));
+
+    let total: Credits = credits.iter().map(|(_, c)| *c).sum();
+    let hours = total.min(evaluation.remaining);
+    let categories: Vec<CategoryCredits> = evaluation
+        .categories
+        .iter()
+        .filter(|c| c.remaining > Credits::ZERO)
+        .filter_map(|c| {
+            let earned: Credits = credits.iter().filter(|(category, _)| *category == c.category).map(|(_, c)| *c).sum();
+            let closed = earned.min(c.remaining);
+            (closed > Credits::ZERO).then_some(CategoryCredits { category: c.category, credits: closed })
+        })
+        .collect();
+    if hours <= Credits::ZERO && categories.is_empty() {
+        return None;
+    }
+
+    // Category minimums are harder to find courses for than general hours,
+    // so closing one counts on top of the hours themselves
+    let approval = crate::plan::license_approval(course, license, taken_on);
+    let closed = hours.hundredths() + categories.iter().map(|c| c.credits.hundredths()).sum::<i64>();
+    let weight = urgency(evaluation.days_to_deadline) * if approval.is_some() { 2 } else { 1 };
+
+    Some((
+        weight * closed,
+        GapRationale {
+            license_id: license.license.id,
+            state_code: license.state_code.clone(),
+            profession: license.license.profession.clone(),
+            approved: approval.is_some(),
+            approval_number: approval.and_then(|a| a.approval_number.clone()),
+            hours,
+            remaining: evaluation.remaining,
+            categories,
+            deadline: evaluation.deadline,
+            days_to_deadline: evaluation.days_to_deadline,
         },
-    ]
+    ))
 }
 
-fn select_courses_by_keywords(interests: &str) -> Vec<(String, String, f32, String, String, String, String)> {
-    let catalog = vec![
-        ("Professional Responsibility and Ethics", "Massachusetts CLE", 2.0, "Ethics", "Online", "$50", "https://www.massbar.org/continuing-legal-education"),
-        ("Contract Drafting Essentials", "Practising Law Institute", 3.0, "Contract Law", "Self-Paced", "$99", "https://www.pli.edu/programs/contract-drafting"),
-        ("Introduction to Legal Technology Tools", "ABA Techshow", 1.5, "Legal Tech", "Online", "Free", "https://www.techshow.com/education"),
-        ("Florida Bar Ethics Update 2024", "Florida Bar CLE", 2.5, "Ethics", "Live Webinar", "Free", "https://www.floridabar.org/cle/"),
-        ("AI for Legal Professionals", "Stanford CodeX", 2.0, "Legal Tech", "Online", "Free", "https://law.stanford.edu/codex-the-stanford-center-for-legal-informatics/"),
-        ("Advanced Contract Negotiation", "Harvard Law School", 3.5, "Contract Law", "Self-Paced", "$150", "https://online-learning.harvard.edu/catalog"),
-        ("Data Privacy and GDPR Compliance", "IAPP", 2.0, "Privacy Law", "Online", "$200", "https://iapp.org/store/courses/"),
-        ("Legal Writing for Clarity", "Legal Writing Institute", 1.5, "Legal Writing", "Self-Paced", "Free", "https://www.lwionline.org/"),
-        ("Business Law Foundations", "Coursera", 4.0, "Business Law", "Self-Paced", "Free", "https://www.coursera.org/courses?query=business%20law"),
-        ("Immigration Law Essentials", "AILA", 2.5, "Immigration", "Live Webinar", "$125", "https://www.aila.org/cle"),
-        ("Federal Taxation Fundamentals", "NYU School of Law", 3.0, "Tax Law", "Self-Paced", "$175", "https://www.law.nyu.edu/academics/cle"),
-        ("Real Estate Transactions", "California Lawyers Association", 2.5, "Real Estate Law", "Online", "$95", "https://calawyers.org/cle/"),
-        ("Divorce and Child Custody Essentials", "National Business Institute", 3.0, "Family Law", "Live Webinar", "$149", "https://www.nbi-sems.com/"),
-        ("Family Law Practice Fundamentals", "State Bar of Texas", 2.5, "Family Law", "Online", "$125", "https://www.texasbar.com/"),
-        ("Bankruptcy Law Basics", "American Bankruptcy Institute", 2.0, "Bankruptcy Law", "Self-Paced", "$150", "https://www.abi.org/"),
-        ("Personal Injury Litigation", "National Institute for Trial Advocacy", 3.5, "Personal Injury", "Online", "$200", "https://www.nita.org/"),
-        ("Criminal Defense Strategies", "NACDL", 3.0, "Criminal Law", "Live Webinar", "$150", "https://www.nacdl.org/cle/"),
-        ("Employment Discrimination Law", "Georgetown Law", 3.5, "Employment Law", "Self-Paced", "$200", "https://www.law.georgetown.edu/continuing-legal-education/"),
-        ("Civil Litigation Fundamentals", "American Bar Association", 2.5, "Civil Litigation", "Online", "$125", "https://www.americanbar.org/cle/"),
-        ("Intellectual Property Overview", "American Bar Association", 2.5, "IP Law", "Online", "$125", "https://www.americanbar.org/cle/"),
-        ("Estate Planning and Wills", "UC Berkeley Extension", 3.0, "Estate Planning", "Online", "$195", "https://extension.berkeley.edu/"),
-        ("Healthcare Compliance Essentials", "American Health Law Association", 2.0, "Healthcare Law", "Online", "$175", "https://www.americanbar.org/groups/health_law/"),
-        ("Environmental Law Basics", "Environmental Law Institute", 2.5, "Environmental Law", "Self-Paced", "$100", "https://www.eli.org/"),
-        ("Cybersecurity for Law Firms", "ILTA", 1.5, "Legal Tech", "Online", "Free", "https://www.iltanet.org/"),
-    ];
+/// A course picked for the user, with the shortfalls it would close.
+struct SelectedCourse<'a> {
+    course: &'a CatalogCourse,
+    rationale: Vec<GapRationale>,
+}
 
+/// Picks the courses that do most to close the user's shortfalls, nudged
+/// towards their stated interests.
+fn select_courses<'a>(
+    catalog: &'a [CatalogCourse],
+    interests: &str,
+    user_id: i32,
+    shortfalls: &[Shortfall],
+    today: NaiveDate,
+) -> Vec<SelectedCourse<'a>> {
     let interests_lower = interests.to_lowercase();
     let mut scored_courses: Vec<_> = catalog.iter().map(|course| {
-        let title = course.0.to_lowercase();
-        let topic = course.3.to_lowercase();
+        let title = course.course.title.to_lowercase();
+        let topic = course.topic_label().to_lowercase();
         let mut score = 0;
 
-        // Score based on keyword matches
+        // A title match is worth closing an approved hour with a distant
+        // deadline; scores are in hundredths of an hour
         for word in interests_lower.split_whitespace() {
             if word.len() < 3 { continue; }
-            if title.contains(word) { score += 3; }
-            if topic.contains(word) { score += 2; }
+            if title.contains(word) { score += 200; }
+            if topic.contains(word) { score += 100; }
         }
 
-        (score, course.clone())
+        let mut rationale = Vec::new();
+        for shortfall in shortfalls {
+            if let Some((gap_score, gap)) = close_gap(user_id, course, shortfall, today) {
+                score += gap_score;
+                rationale.push(gap);
+            }
+        }
+        rationale.sort_by_key(|g| g.days_to_deadline.unwrap_or(i64::MAX));
+
+        // Described courses win ties, so they're the default picks
+        (score, course.course.summary.is_some(), SelectedCourse { course, rationale })
     }).collect();
 
-    scored_courses.sort_by(|a, b| b.0.cmp(&a.0));
-    scored_courses.iter().take(4).map(|(_, c)| {
-        (c.0.to_string(), c.1.to_string(), c.2, c.3.to_string(), c.4.to_string(), c.5.to_string(), c.6.to_string())
-    }).collect()
+    scored_courses.sort_by_key(|c| std::cmp::Reverse((c.0, c.1)));
+    scored_courses.into_iter().take(4).map(|(_, _, c)| c).collect()
 }
 
 async fn call_gemini_api(
     api_key: &str,
     _user_name: &str,
-    _hours_needed: i32,
+    hours_needed: Credits,
     interests: &str,
+    selected: &[SelectedCourse<'_>],
 ) -> Result<Vec<CourseRecommendation>, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
 
-    // Pre-select courses by keyword matching
-    let selected = select_courses_by_keywords(interests);
-
+    // Each course carries the shortfalls it closes, so reasons can cite them
     let courses_text = selected.iter().enumerate()
-        .map(|(i, c)| format!("{}. {}", i+1, c.0))
+        .map(|(i, s)| match s.rationale.first() {
+            Some(gap) => format!("{}. {} ({})", i+1, s.course.course.title, gap_reason(gap)),
+            None => format!("{}. {}", i+1, s.course.course.title),
+        })
         .collect::<Vec<_>>()
         .join("\n");
 
     let prompt = format!(
         r#"User interests: "{}"
+Hours still needed across their licenses: {}
 Courses:
 {}
 
-Write a brief ai_reason (10-15 words) for each course explaining why it matches their interests.
+Write a brief ai_reason (10-15 words) for each course explaining why it matches their interests and, where given, the requirement it helps them meet.
 Return JSON: ["reason1","reason2","reason3","reason4"]"#,
-        interests, courses_text
+        interests, hours_needed, courses_text
     );
 
     let request_body = serde_json::json!({
@@ -270,16 +402,7 @@ Return JSON: ["reason1","reason2","reason3","reason4"]"#,
 
     // Combine pre-selected courses with AI reasons
     let recommendations = selected.iter().zip(ai_reasons.iter())
-        .map(|(course, reason)| CourseRecommendation {
-            title: course.0.clone(),
-            provider: course.1.clone(),
-            hours: course.2,
-            topic: course.3.clone(),
-            format: course.4.clone(),
-            price: course.5.clone(),
-            url: course.6.clone(),
-            ai_reason: reason.clone(),
-        })
+        .map(|(course, reason)| CourseRecommendation::new(course, reason.clone()))
         .collect();
 
     Ok(recommendations)
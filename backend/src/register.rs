@@ -7,18 +7,26 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
+use crate::profession::Profession;
+
 #[derive(Deserialize)]
 pub struct RegisterData {
     username: String,
     password: String,
     fullname: String,
+    #[serde(default)]
+    profession: Profession,
     states: HashMap<UsState, HourRequirements>,
 }
 
 #[derive(Deserialize)]
 pub struct HourRequirements {
-    completed: u16,
+    completed: entity::Credits,
     due: chrono::NaiveDate,
+    /// Profession this license is held under; defaults to the user's profession
+    profession: Option<Profession>,
+    admission_date: Option<chrono::NaiveDate>,
+    bar_number: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -42,6 +50,19 @@ pub async fn register(
         return Err((StatusCode::CONFLICT, "Username already exists"));
     }
 
+    for (code, requirements) in &data.states {
+        if let Some(bar_number) = requirements.bar_number.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+            crate::jurisdictions::validate_bar_number(
+                &code.to_string(),
+                requirements.profession.unwrap_or(data.profession),
+                bar_number,
+            )?;
+        }
+        if requirements.admission_date.is_some_and(|d| d > chrono::Utc::now().date_naive()) {
+            return Err((StatusCode::BAD_REQUEST, "Admission date cannot be in the future"));
+        }
+    }
+
     // Salt and hash the password
     let salted = format!("{}{}", data.password, crate::SALT);
     let mut hasher = Sha256::new();
@@ -53,6 +74,7 @@ pub async fn register(
         username: Set(data.username),
         password: Set(hashed_password),
         fullname: Set(data.fullname),
+        profession: Set(data.profession.to_string()),
         ..Default::default()
     };
 
@@ -62,7 +84,7 @@ pub async fn register(
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create user"))?;
 
     // Link user to states
-    link_user_to_states(&state.conn, user.id, data.states)
+    link_user_to_states(&state.conn, user.id, data.profession, data.states)
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to link states"))?;
 
@@ -75,6 +97,7 @@ pub async fn register(
 async fn link_user_to_states(
     conn: &DatabaseConnection,
     user_id: i32,
+    profession: Profession,
     state_names: HashMap<UsState, HourRequirements>,
 ) -> Result<(), sea_orm::DbErr> {
     use sea_orm::ConnectionTrait;
@@ -94,17 +117,31 @@ async fn link_user_to_states(
         if let Some(state_row) = state_result {
             let state_id: i32 = state_row.try_get("", "id")?;
 
-            // Insert into user_state table with hours_complete and renewal_date
-            let insert_query = format!(
-                "INSERT INTO user_state (user_id, state_id, hours_complete, renewal_date) VALUES ({}, {}, {}, '{}')",
-                user_id, state_id, state.1.completed, state.1.due
-            );
-
-            conn.execute(Statement::from_string(
-                sea_orm::DatabaseBackend::Sqlite,
-                insert_query,
-            ))
+            // Insert into user_state table with renewal_date and profession
+            let license = entity::user_state::ActiveModel {
+                user_id: Set(user_id),
+                state_id: Set(state_id),
+                renewal_date: Set(Some(state.1.due.to_string())),
+                profession: Set(state.1.profession.unwrap_or(profession).to_string()),
+                admission_date: Set(state.1.admission_date),
+                bar_number: Set(crate::ledger::non_blank(state.1.bar_number)),
+                ..Default::default()
+            }
+            .insert(conn)
             .await?;
+
+            // Hours already completed open the license's ledger
+            if state.1.completed > entity::Credits::ZERO {
+                crate::hours_history::set_hours(
+                    conn,
+                    user_id,
+                    user_id,
+                    license.id,
+                    state.1.completed,
+                    crate::hours_history::Source::Registration,
+                )
+                .await?;
+            }
         }
     }
 
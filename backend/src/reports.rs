@@ -0,0 +1,355 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::NaiveDate;
+use entity::{Credits, compliance_period};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tower_cookies::Cookies;
+
+use crate::category::CreditCategory;
+use crate::compliance::Evaluation;
+use crate::credits::CreditUnit;
+use crate::pdf::PdfWriter;
+
+/// A course as it appears on a compliance report.
+pub struct ReportCourse {
+    pub completed_on: NaiveDate,
+    pub title: String,
+    pub provider: Option<String>,
+    pub approval_number: Option<String>,
+    pub credits: BTreeMap<CreditCategory, Credits>,
+    pub total: Credits,
+}
+
+/// Everything submitted for one license and compliance period.
+pub struct ComplianceReport {
+    pub fullname: String,
+    pub state_code: String,
+    pub profession: String,
+    pub bar_number: Option<String>,
+    pub admission_date: Option<NaiveDate>,
+    pub period_start: Option<NaiveDate>,
+    pub period_end: Option<NaiveDate>,
+    /// Oldest first
+    pub courses: Vec<ReportCourse>,
+    /// Every category that has credits or a minimum, in display order
+    pub categories: Vec<CreditCategory>,
+    pub completed_by_category: BTreeMap<CreditCategory, Credits>,
+    pub evaluation: Evaluation,
+}
+
+impl ComplianceReport {
+    fn filename(&self, extension: &str) -> String {
+        format!(
+            "compliance-{}-{}-{}.{}",
+            self.state_code,
+            self.profession,
+            self.period_end.map(|d| d.to_string()).unwrap_or_else(|| "current".to_string()),
+            extension
+        )
+    }
+
+    /// Completed, required and remaining credits for a category; the last two
+    /// are blank when the category has no minimum
+    pub fn category_totals(&self, category: CreditCategory) -> [String; 3] {
+        let completed = self
+            .completed_by_category
+            .get(&category)
+            .copied()
+            .unwrap_or_default()
+            .to_string();
+        match self.evaluation.categories.iter().find(|c| c.category == category) {
+            Some(minimum) => [completed, minimum.required.to_string(), minimum.remaining.to_string()],
+            None => [completed, String::new(), String::new()],
+        }
+    }
+}
+
+/// Gathers a report for one of the user's licenses, covering either the
+/// current period or a closed one from its history.
+pub async fn build_report<C: ConnectionTrait>(
+    conn: &C,
+    user: &entity::user::Model,
+    license_id: i32,
+    period_id: Option<i32>,
+) -> Result<ComplianceReport, (StatusCode, &'static str)> {
+    let mut license = crate::licenses::for_user(conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .into_iter()
+        .find(|l| l.license.id == license_id)
+        .ok_or((StatusCode::NOT_FOUND, "License not found"))?;
+
+    let mut today = chrono::Utc::now().date_naive();
+    let mut period_start = license.period_start();
+    if let Some(period_id) = period_id {
+        let period = compliance_period::Entity::find_by_id(period_id)
+            .filter(compliance_period::Column::UserStateId.eq(license_id))
+            .one(conn)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+            .ok_or((StatusCode::NOT_FOUND, "Compliance period not found"))?;
+
+        // Evaluate the license as it stood when that period closed
+        license.license.period_start = period.period_start;
+        license.license.deadline_override = Some(period.period_end);
+        period_start = period.period_start;
+        today = period.period_end.succ_opt().unwrap_or(period.period_end);
+    }
+
+    let entries = crate::ledger::entries_for_user(conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let unit = CreditUnit::for_requirement(license.requirement.as_ref());
+    let mut courses: Vec<ReportCourse> = entries
+        .iter()
+        .rev()
+        .filter(|e| e.applies_to(&license))
+        .map(|e| {
+            let mut credits: BTreeMap<CreditCategory, Credits> = BTreeMap::new();
+            for (category, amount) in e.applied_credits(unit) {
+                *credits.entry(category).or_default() += amount;
+            }
+            ReportCourse {
+                completed_on: e.entry.completed_on,
+                title: e.entry.title.clone(),
+                provider: e.entry.provider.clone(),
+                approval_number: e.entry.approval_number.clone(),
+                total: credits.values().copied().sum(),
+                credits,
+            }
+        })
+        .collect();
+    courses.sort_by_key(|c| c.completed_on);
+
+    let totals = crate::ledger::totals(std::slice::from_ref(&license), &entries)
+        .remove(&license.license.id)
+        .unwrap_or_default();
+    let deadline = license.deadline();
+    let evaluation = crate::compliance::evaluate(&license, &totals, deadline, today);
+
+    let categories: BTreeSet<CreditCategory> = courses
+        .iter()
+        .flat_map(|c| c.credits.keys().copied())
+        .chain(evaluation.categories.iter().map(|c| c.category))
+        .collect();
+
+    Ok(ComplianceReport {
+        fullname: user.fullname.clone(),
+        state_code: license.state_code.clone(),
+        profession: license.license.profession.clone(),
+        bar_number: license.license.bar_number.clone(),
+        admission_date: license.license.admission_date,
+        period_start,
+        period_end: deadline,
+        courses,
+        categories: categories.into_iter().collect(),
+        completed_by_category: totals.by_category,
+        evaluation,
+    })
+}
+
+fn optional_date(date: Option<NaiveDate>) -> String {
+    date.map(|d| d.to_string()).unwrap_or_default()
+}
+
+pub fn render_csv(report: &ComplianceReport) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(Vec::new());
+
+    writer.write_record(["Name", &report.fullname])?;
+    writer.write_record(["Bar number", report.bar_number.as_deref().unwrap_or_default()])?;
+    writer.write_record(["Jurisdiction", &report.state_code])?;
+    writer.write_record(["Profession", &report.profession])?;
+    writer.write_record(["Admission date", &optional_date(report.admission_date)])?;
+    writer.write_record(["Period start", &optional_date(report.period_start)])?;
+    writer.write_record(["Period end", &optional_date(report.period_end)])?;
+    writer.write_record(["Status", report.evaluation.status.as_str()])?;
+
+    let mut header = vec!["Date", "Course", "Provider", "Approval number"];
+    header.extend(report.categories.iter().map(|c| c.label()));
+    header.push("Total");
+    writer.write_record(&header)?;
+
+    for course in &report.courses {
+        let mut record = vec![
+            course.completed_on.to_string(),
+            course.title.clone(),
+            course.provider.clone().unwrap_or_default(),
+            course.approval_number.clone().unwrap_or_default(),
+        ];
+        record.extend(report.categories.iter().map(|c| {
+            course.credits.get(c).map(|v| v.to_string()).unwrap_or_default()
+        }));
+        record.push(course.total.to_string());
+        writer.write_record(&record)?;
+    }
+
+    let category_totals: Vec<[String; 3]> = report
+        .categories
+        .iter()
+        .map(|c| report.category_totals(*c))
+        .collect();
+    let overall = [
+        report.evaluation.completed,
+        report.evaluation.required,
+        report.evaluation.remaining,
+    ];
+    for (i, label) in ["Completed", "Required", "Remaining"].into_iter().enumerate() {
+        let mut record = vec![label.to_string(), String::new(), String::new(), String::new()];
+        record.extend(category_totals.iter().map(|t| t[i].clone()));
+        record.push(overall[i].to_string());
+        writer.write_record(&record)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+pub fn render_pdf(report: &ComplianceReport) -> Result<Vec<u8>, printpdf::Error> {
+    let mut pdf = PdfWriter::new("Continuing Education Compliance Report")?;
+    let width = pdf.content_width();
+
+    pdf.paragraph("Continuing Education Compliance Report", 16.0, true);
+    pdf.gap(3.0);
+
+    let details = [
+        ("Name", report.fullname.clone()),
+        ("Bar number", report.bar_number.clone().unwrap_or_else(|| "Not recorded".to_string())),
+        ("Jurisdiction", format!("{} ({})", report.state_code, report.profession)),
+        ("Admission date", optional_date(report.admission_date)),
+        (
+            "Compliance period",
+            format!("{} to {}", optional_date(report.period_start), optional_date(report.period_end)),
+        ),
+        ("Status", report.evaluation.status.as_str().replace('_', " ")),
+    ];
+    for (label, value) in &details {
+        pdf.row(&[(0.0, 40.0, label), (40.0, width - 40.0, value)], 10.0, false);
+    }
+    pdf.gap(5.0);
+
+    let columns = [(0.0, 20.0), (21.0, 68.0), (90.0, 40.0), (131.0, 30.0), (162.0, width - 162.0)];
+    let header = ["Date", "Course", "Provider", "Approval no.", "Credits"];
+    let cells: Vec<(f32, f32, &str)> = columns
+        .iter()
+        .zip(header)
+        .map(|((x, w), text)| (*x, *w, text))
+        .collect();
+    pdf.row(&cells, 9.0, true);
+    pdf.gap(1.0);
+
+    for course in &report.courses {
+        let date = course.completed_on.to_string();
+        let total = course.total.to_string();
+        let values = [
+            date.as_str(),
+            course.title.as_str(),
+            course.provider.as_deref().unwrap_or_default(),
+            course.approval_number.as_deref().unwrap_or_default(),
+            total.as_str(),
+        ];
+        let cells: Vec<(f32, f32, &str)> = columns
+            .iter()
+            .zip(values)
+            .map(|((x, w), text)| (*x, *w, text))
+            .collect();
+        pdf.row(&cells, 9.0, false);
+
+        // Spell out the split whenever it isn't all general credit
+        if course.credits.keys().any(|c| *c != CreditCategory::General) {
+            let split = course
+                .credits
+                .iter()
+                .map(|(category, credits)| format!("{} {}", category.label(), credits))
+                .collect::<Vec<_>>()
+                .join(", ");
+            pdf.row(&[(21.0, width - 21.0, &split)], 8.0, false);
+        }
+    }
+    if report.courses.is_empty() {
+        pdf.row(&[(0.0, width, "No courses recorded for this period.")], 9.0, false);
+    }
+    pdf.gap(5.0);
+
+    pdf.row(
+        &[(0.0, 60.0, "Totals"), (70.0, 30.0, "Completed"), (105.0, 30.0, "Required"), (140.0, 30.0, "Remaining")],
+        10.0,
+        true,
+    );
+    for category in &report.categories {
+        let [completed, required, remaining] = report.category_totals(*category);
+        pdf.row(
+            &[
+                (0.0, 60.0, category.label()),
+                (70.0, 30.0, &completed),
+                (105.0, 30.0, &required),
+                (140.0, 30.0, &remaining),
+            ],
+            10.0,
+            false,
+        );
+    }
+    let completed = report.evaluation.completed.to_string();
+    let required = report.evaluation.required.to_string();
+    let remaining = report.evaluation.remaining.to_string();
+    pdf.row(
+        &[(0.0, 60.0, "All credit"), (70.0, 30.0, &completed), (105.0, 30.0, &required), (140.0, 30.0, &remaining)],
+        10.0,
+        true,
+    );
+
+    pdf.gap(8.0);
+    let generated = format!("Generated {}", chrono::Utc::now().format("%B %-d, %Y"));
+    pdf.row(&[(0.0, width, &generated)], 8.0, false);
+
+    pdf.finish()
+}
+
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    /// `pdf` (the default) or `csv`
+    format: Option<String>,
+    /// A closed period from the compliance history; the current period otherwise
+    period_id: Option<i32>,
+}
+
+pub async fn compliance_report(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(license_id): Path<i32>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let report = build_report(&state.conn, &user, license_id, query.period_id).await?;
+
+    let (content_type, extension, body) = match query.format.as_deref().unwrap_or("pdf") {
+        "pdf" => (
+            "application/pdf",
+            "pdf",
+            render_pdf(&report).map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render report"))?,
+        ),
+        "csv" => (
+            "text/csv; charset=utf-8",
+            "csv",
+            render_csv(&report).map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render report"))?,
+        ),
+        _ => return Err((StatusCode::BAD_REQUEST, "Format must be pdf or csv")),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", report.filename(extension)),
+            ),
+        ],
+        body,
+    ))
+}
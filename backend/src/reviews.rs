@@ -0,0 +1,473 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use entity::{course, course_review, credit_entry, user};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, Set, Statement, TransactionTrait, Value,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tower_cookies::Cookies;
+
+pub const STATUS_PUBLISHED: &str = "published";
+pub const STATUS_HIDDEN: &str = "hidden";
+
+/// Average of the published ratings for a course or provider.
+#[derive(Clone, Copy, Serialize)]
+pub struct Rating {
+    /// Out of five, to one decimal place
+    pub average: f64,
+    pub count: u32,
+}
+
+/// Published ratings per course and per provider, keyed by id.
+pub struct Ratings {
+    pub by_course: HashMap<i32, Rating>,
+    pub by_provider: HashMap<i32, Rating>,
+}
+
+pub async fn ratings<C: ConnectionTrait>(conn: &C) -> Result<Ratings, DbErr> {
+    ratings_for(conn, None).await
+}
+
+/// Ratings for these providers' courses, and for each provider overall
+pub async fn provider_ratings<C: ConnectionTrait>(conn: &C, provider_ids: &[i32]) -> Result<Ratings, DbErr> {
+    ratings_for(conn, Some(provider_ids)).await
+}
+
+/// Runs a query selecting `id`, `average` and `count` into ratings by id.
+async fn averages<C: ConnectionTrait>(
+    conn: &C,
+    sql: String,
+    values: Vec<Value>,
+) -> Result<HashMap<i32, Rating>, DbErr> {
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(DatabaseBackend::Sqlite, sql, values))
+        .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let average: f64 = row.try_get("", "average")?;
+            let count: i64 = row.try_get("", "count")?;
+            let rating = Rating {
+                average: (average * 10.0).round() / 10.0,
+                count: count as u32,
+            };
+            Ok((row.try_get("", "id")?, rating))
+        })
+        .collect()
+}
+
+async fn ratings_for<C: ConnectionTrait>(conn: &C, provider_ids: Option<&[i32]>) -> Result<Ratings, DbErr> {
+    let mut filter = "course_review.status = ?".to_string();
+    let mut values: Vec<Value> = vec![STATUS_PUBLISHED.into()];
+    if let Some(provider_ids) = provider_ids {
+        let placeholders = vec!["?"; provider_ids.len()].join(", ");
+        filter.push_str(&format!(" AND course.provider_id IN ({})", placeholders));
+        values.extend(provider_ids.iter().map(|&id| Value::from(id)));
+    }
+
+    let grouped = |key: &str| {
+        format!(
+            "SELECT {key} AS id, AVG(course_review.rating) AS average, COUNT(*) AS count \
+             FROM course_review JOIN course ON course.id = course_review.course_id \
+             WHERE {filter} GROUP BY {key}"
+        )
+    };
+    Ok(Ratings {
+        by_course: averages(conn, grouped("course_review.course_id"), values.clone()).await?,
+        by_provider: averages(conn, grouped("course.provider_id"), values).await?,
+    })
+}
+
+#[derive(Serialize)]
+pub struct ReviewResponse {
+    id: i32,
+    course_id: i32,
+    /// Reviewer's first name
+    reviewer: String,
+    rating: i32,
+    body: Option<String>,
+    status: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ReviewResponse {
+    fn new(review: course_review::Model, reviewer: Option<&user::Model>) -> Self {
+        ReviewResponse {
+            id: review.id,
+            course_id: review.course_id,
+            reviewer: reviewer
+                .and_then(|u| u.fullname.split_whitespace().next())
+                .unwrap_or("Former member")
+                .to_string(),
+            rating: review.rating,
+            body: review.body,
+            status: review.status,
+            created_at: review.created_at,
+            updated_at: review.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CourseReviews {
+    rating: Option<Rating>,
+    provider_rating: Option<Rating>,
+    /// Published reviews, newest first
+    reviews: Vec<ReviewResponse>,
+    /// The user's own review, even while it's hidden
+    mine: Option<ReviewResponse>,
+}
+
+async fn find_course<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+) -> Result<course::Model, (StatusCode, &'static str)> {
+    course::Entity::find_by_id(id)
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))
+}
+
+pub async fn list_reviews(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+) -> Result<Json<CourseReviews>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let course = find_course(&state.conn, course_id).await?;
+
+    let reviews = course_review::Entity::find()
+        .find_also_related(user::Entity)
+        .filter(course_review::Column::CourseId.eq(course_id))
+        .order_by_desc(course_review::Column::UpdatedAt)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let ratings = ratings(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mine = reviews
+        .iter()
+        .find(|(r, _)| r.user_id == user.id)
+        .map(|(r, u)| ReviewResponse::new(r.clone(), u.as_ref()));
+    Ok(Json(CourseReviews {
+        rating: ratings.by_course.get(&course_id).copied(),
+        provider_rating: ratings.by_provider.get(&course.provider_id).copied(),
+        reviews: reviews
+            .into_iter()
+            .filter(|(r, _)| r.status == STATUS_PUBLISHED)
+            .map(|(r, u)| ReviewResponse::new(r, u.as_ref()))
+            .collect(),
+        mine,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ReviewRequest {
+    /// Whole stars from 1 to 5
+    rating: i32,
+    body: Option<String>,
+}
+
+/// Creates or replaces the user's review of a course. Only users who have
+/// logged credit for the course can review it.
+pub async fn save_review(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+    Json(data): Json<ReviewRequest>,
+) -> Result<Json<ReviewResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    find_course(&state.conn, course_id).await?;
+
+    if !(1..=5).contains(&data.rating) {
+        return Err((StatusCode::BAD_REQUEST, "Rating must be between 1 and 5"));
+    }
+    let body = crate::ledger::non_blank(data.body);
+    if body.as_ref().is_some_and(|b| b.chars().count() > 2000) {
+        return Err((StatusCode::BAD_REQUEST, "Review must be at most 2000 characters"));
+    }
+
+    let logged = credit_entry::Entity::find()
+        .filter(credit_entry::Column::UserId.eq(user.id))
+        .filter(credit_entry::Column::CourseId.eq(course_id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if logged.is_none() {
+        return Err((StatusCode::FORBIDDEN, "Log credit for this course before reviewing it"));
+    }
+
+    let existing = course_review::Entity::find()
+        .filter(course_review::Column::CourseId.eq(course_id))
+        .filter(course_review::Column::UserId.eq(user.id))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let now = Utc::now();
+    // Editing a hidden review leaves it hidden until an admin publishes it again
+    let saved = match existing {
+        Some(review) => {
+            let mut active: course_review::ActiveModel = review.into();
+            active.rating = Set(data.rating);
+            active.body = Set(body);
+            active.updated_at = Set(now);
+            active.update(&state.conn).await
+        }
+        None => {
+            course_review::ActiveModel {
+                course_id: Set(course_id),
+                user_id: Set(user.id),
+                rating: Set(data.rating),
+                body: Set(body),
+                status: Set(STATUS_PUBLISHED.to_string()),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&state.conn)
+            .await
+        }
+    }
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save review"))?;
+
+    Ok(Json(ReviewResponse::new(saved, Some(&user))))
+}
+
+pub async fn delete_review(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let deleted = course_review::Entity::delete_many()
+        .filter(course_review::Column::CourseId.eq(course_id))
+        .filter(course_review::Column::UserId.eq(user.id))
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete review"))?;
+    if deleted.rows_affected == 0 {
+        return Err((StatusCode::NOT_FOUND, "Review not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ModerationQuery {
+    /// `published` or `hidden`
+    status: Option<String>,
+    course_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct ModeratedReview {
+    #[serde(flatten)]
+    review: ReviewResponse,
+    user_id: i32,
+    course_title: Option<String>,
+    moderated_by: Option<i32>,
+    moderation_note: Option<String>,
+}
+
+impl ModeratedReview {
+    fn new(
+        review: course_review::Model,
+        reviewer: Option<&user::Model>,
+        course: Option<&course::Model>,
+    ) -> Self {
+        ModeratedReview {
+            user_id: review.user_id,
+            course_title: course.map(|c| c.title.clone()),
+            moderated_by: review.moderated_by,
+            moderation_note: review.moderation_note.clone(),
+            review: ReviewResponse::new(review, reviewer),
+        }
+    }
+}
+
+/// Lists reviews for moderation, newest first.
+pub async fn moderation_queue(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Query(query): Query<ModerationQuery>,
+) -> Result<Json<Vec<ModeratedReview>>, (StatusCode, &'static str)> {
+    crate::auth::current_admin(&state.conn, &cookies).await?;
+
+    let mut reviews = course_review::Entity::find().find_also_related(course::Entity);
+    if let Some(status) = &query.status {
+        reviews = reviews.filter(course_review::Column::Status.eq(status.as_str()));
+    }
+    if let Some(course_id) = query.course_id {
+        reviews = reviews.filter(course_review::Column::CourseId.eq(course_id));
+    }
+    let reviews = reviews
+        .order_by_desc(course_review::Column::UpdatedAt)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let users: HashMap<i32, user::Model> = user::Entity::find()
+        .filter(user::Column::Id.is_in(reviews.iter().map(|(r, _)| r.user_id)))
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+
+    Ok(Json(
+        reviews
+            .into_iter()
+            .map(|(review, course)| {
+                let reviewer = users.get(&review.user_id);
+                ModeratedReview::new(review, reviewer, course.as_ref())
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ModerationRequest {
+    /// Reason for the decision, kept with the review
+    note: Option<String>,
+}
+
+/// Sets a review's status, recording who moderated it and why.
+async fn moderate(
+    state: &crate::AppState,
+    cookies: &Cookies,
+    id: i32,
+    status: &str,
+    note: Option<String>,
+) -> Result<Json<ModeratedReview>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, cookies).await?;
+
+    let (review, course) = course_review::Entity::find_by_id(id)
+        .find_also_related(course::Entity)
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Review not found"))?;
+    let reviewer = user::Entity::find_by_id(review.user_id)
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let before = ModeratedReview::new(review.clone(), reviewer.as_ref(), course.as_ref());
+    let mut active: course_review::ActiveModel = review.into();
+    active.status = Set(status.to_string());
+    active.moderated_by = Set(Some(admin.id));
+    active.moderation_note = Set(crate::ledger::non_blank(note));
+    let saved = active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to moderate review"))?;
+
+    let after = ModeratedReview::new(saved, reviewer.as_ref(), course.as_ref());
+    let action = if status == STATUS_HIDDEN { "hide" } else { "publish" };
+    crate::catalog::record_audit(&txn, Some(admin.id), "review", id, action, Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to moderate review"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to moderate review"))?;
+
+    Ok(Json(after))
+}
+
+pub async fn hide_review(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    Json(data): Json<ModerationRequest>,
+) -> Result<Json<ModeratedReview>, (StatusCode, &'static str)> {
+    moderate(&state, &cookies, id, STATUS_HIDDEN, data.note).await
+}
+
+pub async fn publish_review(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    Json(data): Json<ModerationRequest>,
+) -> Result<Json<ModeratedReview>, (StatusCode, &'static str)> {
+    moderate(&state, &cookies, id, STATUS_PUBLISHED, data.note).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn ratings_average_published_reviews() {
+        let conn = test_support::database().await;
+        let courses = course::Entity::find().all(&conn).await.unwrap();
+        let (first, sibling) = courses
+            .iter()
+            .find_map(|a| {
+                let b = courses.iter().find(|b| b.provider_id == a.provider_id && b.id != a.id)?;
+                Some((a.clone(), b.clone()))
+            })
+            .unwrap();
+        let other = courses
+            .iter()
+            .find(|c| c.provider_id != first.provider_id)
+            .unwrap()
+            .clone();
+
+        let reviews = [
+            (&first, 5, STATUS_PUBLISHED),
+            (&first, 4, STATUS_PUBLISHED),
+            (&first, 1, STATUS_HIDDEN),
+            (&sibling, 2, STATUS_PUBLISHED),
+            (&other, 3, STATUS_PUBLISHED),
+        ];
+        for (i, (course, rating, status)) in reviews.into_iter().enumerate() {
+            let (user, _) = test_support::sign_in(&conn, &format!("reviewer{}", i)).await;
+            course_review::ActiveModel {
+                course_id: Set(course.id),
+                user_id: Set(user.id),
+                rating: Set(rating),
+                status: Set(status.to_string()),
+                created_at: Set(Utc::now()),
+                updated_at: Set(Utc::now()),
+                ..Default::default()
+            }
+            .insert(&conn)
+            .await
+            .unwrap();
+        }
+
+        let all = ratings(&conn).await.unwrap();
+        assert_eq!(all.by_course[&first.id].average, 4.5);
+        assert_eq!(all.by_course[&first.id].count, 2);
+        assert_eq!(all.by_course[&sibling.id].count, 1);
+        assert_eq!(all.by_provider[&first.provider_id].average, 3.7);
+        assert_eq!(all.by_provider[&first.provider_id].count, 3);
+        assert_eq!(all.by_provider[&other.provider_id].count, 1);
+
+        let one = provider_ratings(&conn, &[first.provider_id]).await.unwrap();
+        assert_eq!(one.by_course.len(), 2);
+        assert!(!one.by_provider.contains_key(&other.provider_id));
+        assert!(provider_ratings(&conn, &[]).await.unwrap().by_course.is_empty());
+    }
+}
@@ -0,0 +1,573 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use entity::Credits;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbErr, Statement, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+use tower_cookies::Cookies;
+
+use crate::catalog::{CatalogCourse, CourseFormat, CourseResponse, KNOWN_TOPICS, topic_category};
+use crate::category::CreditCategory;
+use crate::currency::Currency;
+use crate::profession::Profession;
+use crate::register::UsState;
+
+pub const DEFAULT_PAGE_SIZE: u64 = 20;
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    /// Words to look for in the title, summary, provider and topics
+    q: Option<String>,
+    /// Only courses approved in this jurisdiction on the day they're held, or
+    /// today for on-demand courses
+    state: Option<UsState>,
+    /// Narrows `state` to approvals for this profession
+    profession: Option<Profession>,
+    category: Option<CreditCategory>,
+    format: Option<CourseFormat>,
+    /// Only courses priced in this currency
+    currency: Option<Currency>,
+    min_price_cents: Option<u32>,
+    max_price_cents: Option<u32>,
+    min_hours: Option<Credits>,
+    max_hours: Option<Credits>,
+    /// Courses held on or after this date; on-demand courses have no date and
+    /// are left out once either end of the window is given
+    starts_from: Option<NaiveDate>,
+    starts_until: Option<NaiveDate>,
+    /// `next_cursor` from the previous page
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+/// The facet a filter belongs to. Each facet's counts ignore that facet's own
+/// filter, so picking a format still shows how many courses the other
+/// formats have.
+#[derive(Clone, Copy, PartialEq)]
+enum Facet {
+    State,
+    Category,
+    Format,
+    Other,
+}
+
+/// Whether a `course_approval` row is in force on the day its course is held,
+/// or on the bound day for on-demand courses. Binds that day twice.
+const APPROVAL_IN_FORCE: &str = "(course_approval.valid_from IS NULL \
+     OR course_approval.valid_from <= COALESCE(course.starts_on, ?)) \
+     AND (course_approval.valid_until IS NULL \
+     OR course_approval.valid_until >= COALESCE(course.starts_on, ?))";
+
+/// `course_topic.topic` mapped to its credit category, as `topic_category` does
+static TOPIC_CATEGORY: LazyLock<String> = LazyLock::new(|| {
+    let specialties: String = KNOWN_TOPICS
+        .iter()
+        .map(|topic| (topic, topic_category(topic)))
+        .filter(|(_, category)| *category != CreditCategory::General)
+        .map(|(topic, category)| format!(" WHEN '{}' THEN '{}'", topic.replace('\'', "''"), category))
+        .collect();
+    format!("CASE course_topic.topic{} ELSE '{}' END", specialties, CreditCategory::General)
+});
+
+/// The `FROM` and `WHERE` clauses picking out the courses a search covers,
+/// with the values bound to them in order.
+struct Selection {
+    sql: String,
+    values: Vec<Value>,
+}
+
+impl Selection {
+    fn and(&mut self, condition: &str, values: impl IntoIterator<Item = Value>) {
+        self.sql.push_str(" AND ");
+        self.sql.push_str(condition);
+        self.values.extend(values);
+    }
+}
+
+impl SearchQuery {
+    /// Courses that match every filter but `ignoring`'s. With words to match,
+    /// `course_fts` is joined in so their relevance can be selected.
+    fn selection(&self, expression: Option<&str>, ignoring: Facet, today: NaiveDate) -> Selection {
+        let mut selection = Selection {
+            sql: "FROM course JOIN provider ON provider.id = course.provider_id".to_string(),
+            values: Vec::new(),
+        };
+        if expression.is_some() {
+            selection.sql.push_str(" JOIN course_fts ON course_fts.rowid = course.id");
+        }
+        selection.sql.push_str(" WHERE course.retired_at IS NULL AND provider.retired_at IS NULL");
+        if let Some(expression) = expression {
+            selection.and("course_fts MATCH ?", [expression.into()]);
+        }
+
+        if ignoring != Facet::State
+            && let Some(state) = self.state
+        {
+            let mut condition = format!(
+                "EXISTS (SELECT 1 FROM course_approval JOIN state ON state.id = course_approval.state_id \
+                 WHERE course_approval.course_id = course.id AND state.name = ? AND {}",
+                APPROVAL_IN_FORCE
+            );
+            let mut values = vec![state.to_string().into(), today.into(), today.into()];
+            if let Some(profession) = self.profession {
+                condition.push_str(" AND course_approval.profession = ?");
+                values.push(profession.to_string().into());
+            }
+            condition.push(')');
+            selection.and(&condition, values);
+        }
+        if ignoring != Facet::Category
+            && let Some(category) = self.category
+        {
+            selection.and(
+                &format!(
+                    "(EXISTS (SELECT 1 FROM course_topic WHERE course_topic.course_id = course.id AND {} = ?) \
+                     OR EXISTS (SELECT 1 FROM course_approval JOIN course_approval_credit \
+                     ON course_approval_credit.course_approval_id = course_approval.id \
+                     WHERE course_approval.course_id = course.id AND course_approval_credit.category = ?))",
+                    *TOPIC_CATEGORY
+                ),
+                [category.to_string().into(), category.to_string().into()],
+            );
+        }
+        if ignoring != Facet::Format
+            && let Some(format) = self.format
+        {
+            selection.and(
+                "EXISTS (SELECT 1 FROM course_format \
+                 WHERE course_format.course_id = course.id AND course_format.format = ?)",
+                [format.to_string().into()],
+            );
+        }
+        if let Some(currency) = self.currency {
+            selection.and("course.currency = ?", [currency.to_string().into()]);
+        }
+        if let Some(min) = self.min_price_cents {
+            selection.and("course.price_cents >= ?", [i64::from(min).into()]);
+        }
+        if let Some(max) = self.max_price_cents {
+            selection.and("course.price_cents <= ?", [i64::from(max).into()]);
+        }
+        if let Some(min) = self.min_hours {
+            selection.and("course.credits >= ?", [min.into()]);
+        }
+        if let Some(max) = self.max_hours {
+            selection.and("course.credits <= ?", [max.into()]);
+        }
+        if self.starts_from.is_some() || self.starts_until.is_some() {
+            selection.and("course.starts_on IS NOT NULL", []);
+        }
+        if let Some(from) = self.starts_from {
+            selection.and("course.starts_on >= ?", [from.into()]);
+        }
+        if let Some(until) = self.starts_until {
+            selection.and("course.starts_on <= ?", [until.into()]);
+        }
+        selection
+    }
+}
+
+/// Turns free text into an FTS5 query that matches every word as a prefix.
+/// Quoting each word keeps FTS5's operators and column filters out of reach.
+fn match_expression(q: &str) -> Option<String> {
+    let words: Vec<String> = q
+        .chars()
+        .take(200)
+        .collect::<String>()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("\"{}\"*", w))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Relevance of a `course_fts` match; lower is better. Titles and topics
+/// weigh more than summaries.
+const SCORE: &str = "bm25(course_fts, 10.0, 2.0, 4.0, 5.0)";
+
+/// Where a page starts: just after the course with this score and id
+struct Cursor {
+    score: f64,
+    id: i32,
+}
+
+impl Cursor {
+    fn parse(value: &str) -> Option<Self> {
+        let (score, id) = value.split_once('_')?;
+        Some(Cursor {
+            score: score.parse().ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+
+    fn encode(&self) -> String {
+        format!("{}_{}", self.score, self.id)
+    }
+}
+
+#[derive(Default, Serialize)]
+pub struct Facets {
+    states: BTreeMap<String, usize>,
+    categories: BTreeMap<CreditCategory, usize>,
+    formats: BTreeMap<String, usize>,
+    topics: BTreeMap<String, usize>,
+    providers: BTreeMap<String, usize>,
+}
+
+/// Runs a query selecting `value` and `count` into facet counts.
+async fn counts<C: ConnectionTrait>(
+    conn: &C,
+    sql: String,
+    values: Vec<Value>,
+) -> Result<BTreeMap<String, usize>, DbErr> {
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(DatabaseBackend::Sqlite, sql, values))
+        .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let count: i64 = row.try_get("", "count")?;
+            Ok((row.try_get("", "value")?, count as usize))
+        })
+        .collect()
+}
+
+/// Counts the courses under each value of every facet.
+async fn facets<C: ConnectionTrait>(
+    conn: &C,
+    query: &SearchQuery,
+    expression: Option<&str>,
+    today: NaiveDate,
+) -> Result<Facets, DbErr> {
+    let selection = query.selection(expression, Facet::State, today);
+    let states = counts(
+        conn,
+        format!(
+            "SELECT state.name AS value, COUNT(DISTINCT course.id) AS count FROM course_approval \
+             JOIN state ON state.id = course_approval.state_id \
+             JOIN course ON course.id = course_approval.course_id \
+             WHERE course.id IN (SELECT course.id {}) AND {} \
+             GROUP BY state.name",
+            selection.sql, APPROVAL_IN_FORCE
+        ),
+        selection.values.into_iter().chain([today.into(), today.into()]).collect(),
+    )
+    .await?;
+
+    let selection = query.selection(expression, Facet::Category, today);
+    let categories = counts(
+        conn,
+        format!(
+            "SELECT category AS value, COUNT(DISTINCT course_id) AS count FROM ( \
+             SELECT course_topic.course_id, {} AS category FROM course_topic \
+             UNION ALL \
+             SELECT course_approval.course_id, course_approval_credit.category FROM course_approval \
+             JOIN course_approval_credit ON course_approval_credit.course_approval_id = course_approval.id) \
+             WHERE course_id IN (SELECT course.id {}) \
+             GROUP BY category",
+            *TOPIC_CATEGORY, selection.sql
+        ),
+        selection.values,
+    )
+    .await?
+    .into_iter()
+    .filter_map(|(category, count)| Some((category.parse().ok()?, count)))
+    .collect();
+
+    let selection = query.selection(expression, Facet::Format, today);
+    let formats = counts(
+        conn,
+        format!(
+            "SELECT format AS value, COUNT(*) AS count FROM course_format \
+             WHERE course_id IN (SELECT course.id {}) GROUP BY format",
+            selection.sql
+        ),
+        selection.values,
+    )
+    .await?;
+
+    let selection = query.selection(expression, Facet::Other, today);
+    let topics = counts(
+        conn,
+        format!(
+            "SELECT topic AS value, COUNT(*) AS count FROM course_topic \
+             WHERE course_id IN (SELECT course.id {}) GROUP BY topic",
+            selection.sql
+        ),
+        selection.values.clone(),
+    )
+    .await?;
+    let providers = counts(
+        conn,
+        format!("SELECT provider.name AS value, COUNT(*) AS count {} GROUP BY provider.name", selection.sql),
+        selection.values,
+    )
+    .await?;
+
+    Ok(Facets {
+        states,
+        categories,
+        formats,
+        topics,
+        providers,
+    })
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    /// Courses matching the search across all pages
+    total: usize,
+    courses: Vec<CourseResponse>,
+    facets: Facets,
+    next_cursor: Option<String>,
+}
+
+/// Searches the catalog, most relevant first when there are words to match
+/// and in catalog order otherwise.
+pub async fn search_courses(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, (StatusCode, &'static str)> {
+    crate::auth::current_user(&state.conn, &cookies).await?;
+
+    if query.profession.is_some() && query.state.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Profession can only be given with a state"));
+    }
+    if let (Some(min), Some(max)) = (query.min_price_cents, query.max_price_cents)
+        && min > max
+    {
+        return Err((StatusCode::BAD_REQUEST, "Minimum price is above the maximum"));
+    }
+    if let (Some(min), Some(max)) = (query.min_hours, query.max_hours)
+        && min > max
+    {
+        return Err((StatusCode::BAD_REQUEST, "Minimum hours are above the maximum"));
+    }
+    if let (Some(from), Some(until)) = (query.starts_from, query.starts_until)
+        && from > until
+    {
+        return Err((StatusCode::BAD_REQUEST, "Date window ends before it starts"));
+    }
+    let cursor = match &query.cursor {
+        Some(value) => Some(Cursor::parse(value).ok_or((StatusCode::BAD_REQUEST, "Invalid cursor"))?),
+        None => None,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+
+    let expression = query.q.as_deref().and_then(match_expression);
+    search(&state.conn, &query, expression.as_deref(), cursor, limit, Utc::now().date_naive())
+        .await
+        .map(Json)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
+}
+
+/// Runs a checked search: the page of courses after `cursor`, the total
+/// across all pages and the facet counts.
+async fn search<C: ConnectionTrait>(
+    conn: &C,
+    query: &SearchQuery,
+    expression: Option<&str>,
+    cursor: Option<Cursor>,
+    limit: usize,
+    today: NaiveDate,
+) -> Result<SearchResponse, DbErr> {
+    let selection = query.selection(expression, Facet::Other, today);
+    let total: i64 = match conn
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            format!("SELECT COUNT(*) AS total {}", selection.sql),
+            selection.values.clone(),
+        ))
+        .await?
+    {
+        Some(row) => row.try_get("", "total")?,
+        None => 0,
+    };
+
+    // Unscored searches all tie, which leaves them in catalog order
+    let score = if expression.is_some() { SCORE } else { "0.0" };
+    let mut sql = format!(
+        "SELECT id, score FROM (SELECT course.id AS id, {} AS score {})",
+        score, selection.sql
+    );
+    let mut values = selection.values;
+    if let Some(cursor) = &cursor {
+        sql.push_str(" WHERE (score, id) > (?, ?)");
+        values.extend([cursor.score.into(), cursor.id.into()]);
+    }
+    sql.push_str(" ORDER BY score, id LIMIT ?");
+    values.push((limit as i64 + 1).into());
+    let page: Vec<(i32, f64)> = conn
+        .query_all(Statement::from_sql_and_values(DatabaseBackend::Sqlite, sql, values))
+        .await?
+        .into_iter()
+        .map(|row| Ok((row.try_get("", "id")?, row.try_get("", "score")?)))
+        .collect::<Result<_, DbErr>>()?;
+    let next_cursor = (page.len() > limit).then(|| {
+        let (id, score) = page[limit - 1];
+        Cursor { score, id }.encode()
+    });
+
+    let ids: Vec<i32> = page.iter().take(limit).map(|(id, _)| *id).collect();
+    let mut courses: HashMap<i32, CatalogCourse> = crate::catalog::load_courses_by_id(conn, &ids)
+        .await?
+        .into_iter()
+        .map(|c| (c.course.id, c))
+        .collect();
+
+    Ok(SearchResponse {
+        total: total as usize,
+        courses: ids
+            .iter()
+            .filter_map(|id| courses.remove(id))
+            .map(|c| CourseResponse::from(&c))
+            .collect(),
+        facets: facets(conn, query, expression, today).await?,
+        next_cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(value: serde_json::Value) -> SearchQuery {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// Every page of a search, followed through its cursors
+    async fn all_pages(
+        conn: &sea_orm::DatabaseConnection,
+        query: &SearchQuery,
+        today: NaiveDate,
+    ) -> (usize, Vec<i32>) {
+        let expression = query.q.as_deref().and_then(match_expression);
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = search(conn, query, expression.as_deref(), cursor, 3, today).await.unwrap();
+            ids.extend(page.courses.iter().map(|c| c.id));
+            match page.next_cursor {
+                Some(next) => cursor = Cursor::parse(&next),
+                None => return (page.total, ids),
+            }
+        }
+    }
+
+    /// The filters worked out course by course, to check the SQL against
+    fn expected(catalog: &[CatalogCourse], query: &SearchQuery, today: NaiveDate) -> Vec<i32> {
+        catalog
+            .iter()
+            .filter(|course| {
+                let c = &course.course;
+                query.state.is_none_or(|state| {
+                    course.approvals.iter().any(|a| {
+                        a.state_code == state.to_string()
+                            && a.is_valid_on(course.taken_on(today))
+                            && query.profession.is_none_or(|p| a.profession == p.to_string())
+                    })
+                }) && query.category.is_none_or(|category| {
+                    course.topics.iter().any(|t| topic_category(t) == category)
+                        || course.approvals.iter().any(|a| a.credits.iter().any(|c| c.category == category))
+                }) && query.format.is_none_or(|f| course.formats.contains(&f))
+                    && query.currency.is_none_or(|currency| course.currency() == currency)
+                    && query.min_price_cents.is_none_or(|min| i64::from(c.price_cents) >= i64::from(min))
+                    && query.max_price_cents.is_none_or(|max| i64::from(c.price_cents) <= i64::from(max))
+                    && query.min_hours.is_none_or(|min| c.credits >= min)
+                    && query.max_hours.is_none_or(|max| c.credits <= max)
+                    && (query.starts_from.is_none() && query.starts_until.is_none()
+                        || c.starts_on.is_some_and(|date| {
+                            query.starts_from.is_none_or(|from| date >= from)
+                                && query.starts_until.is_none_or(|until| date <= until)
+                        }))
+            })
+            .map(|c| c.course.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn filters_match_the_catalog() {
+        let conn = crate::test_support::database().await;
+        let today = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        // Scheduled courses, for the date window
+        let seminars = include_str!("../fixtures/feeds/state_bar_seminars.ics");
+        crate::ingest::ingest(&conn, "State Bar Seminars", crate::ingest::FeedFormat::Ical, seminars, false)
+            .await
+            .unwrap();
+        let catalog = crate::catalog::load_courses(&conn, false).await.unwrap();
+        assert!(catalog.len() > 3, "the seeded catalog should span several pages");
+
+        let queries = [
+            json!({}),
+            json!({"state": "NY"}),
+            json!({"state": "CA", "profession": "Attorney"}),
+            json!({"category": "ethics"}),
+            json!({"category": "general", "format": "online"}),
+            json!({"min_price_cents": 1, "max_price_cents": 20000}),
+            json!({"min_hours": 1.5, "max_hours": 3}),
+            json!({"starts_from": "2026-11-01", "starts_until": "2026-11-30"}),
+            json!({"starts_until": "2026-12-31"}),
+            json!({"currency": "USD", "state": "TX"}),
+            json!({"state": "TX", "category": "general"}),
+        ];
+        for value in queries {
+            let query = query(value.clone());
+            let expected = expected(&catalog, &query, today);
+            assert_eq!(all_pages(&conn, &query, today).await, (expected.len(), expected), "{}", value);
+        }
+    }
+
+    #[tokio::test]
+    async fn facets_count_courses_ignoring_their_own_filter() {
+        let conn = crate::test_support::database().await;
+        let today = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let catalog = crate::catalog::load_courses(&conn, false).await.unwrap();
+
+        let online = query(json!({"format": "online"}));
+        let results = search(&conn, &online, None, None, 1, today).await.unwrap();
+        for format in [CourseFormat::Online, CourseFormat::InPerson] {
+            let count = catalog.iter().filter(|c| c.formats.contains(&format)).count();
+            assert_eq!(results.facets.formats.get(&format.to_string()).copied().unwrap_or(0), count);
+        }
+        let online_courses: Vec<&CatalogCourse> =
+            catalog.iter().filter(|c| c.formats.contains(&CourseFormat::Online)).collect();
+        let ethics = online_courses
+            .iter()
+            .filter(|c| c.topics.iter().any(|t| t == "Ethics"))
+            .count();
+        assert_eq!(results.facets.topics.get("Ethics").copied().unwrap_or(0), ethics);
+        let providers: usize = results.facets.providers.values().sum();
+        assert_eq!(providers, online_courses.len());
+    }
+
+    #[tokio::test]
+    async fn words_rank_matches_across_pages() {
+        let conn = crate::test_support::database().await;
+        let today = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let catalog = crate::catalog::load_courses(&conn, false).await.unwrap();
+
+        let (total, ids) = all_pages(&conn, &query(json!({"q": "ethics"})), today).await;
+        assert!(total > 0);
+        assert_eq!(ids.len(), total);
+        assert_eq!(ids.iter().collect::<std::collections::HashSet<_>>().len(), total);
+        for id in ids {
+            let course = catalog.iter().find(|c| c.course.id == id).unwrap();
+            let text = format!(
+                "{} {} {} {}",
+                course.course.title,
+                course.course.summary.as_deref().unwrap_or_default(),
+                course.provider.name,
+                course.topics.join(" ")
+            );
+            assert!(text.to_lowercase().contains("ethic"), "{}", course.course.title);
+        }
+    }
+}
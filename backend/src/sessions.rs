@@ -0,0 +1,760 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use entity::{course, course_registration, course_session, user};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter,
+    QueryOrder, Set, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use tower_cookies::Cookies;
+use uuid::Uuid;
+
+/// Accepts an IANA zone name like `America/New_York`, or `UTC`.
+fn check_time_zone(name: &str) -> Result<Tz, (StatusCode, &'static str)> {
+    name.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Time zone must be UTC or a name like America/New_York"))
+}
+
+/// When a session starts or ends: a local time like `2026-03-08T10:00:00`
+/// read in the session's time zone, or an RFC 3339 time with its offset.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SessionTime {
+    Offset(DateTime<FixedOffset>),
+    Local(NaiveDateTime),
+}
+
+impl SessionTime {
+    /// The time in UTC. Local times skipped by a daylight saving change don't
+    /// exist; ones that happen twice are taken the first time round.
+    fn to_utc(&self, zone: Tz) -> Option<DateTime<Utc>> {
+        match self {
+            SessionTime::Offset(time) => Some(time.with_timezone(&Utc)),
+            SessionTime::Local(time) => zone.from_local_datetime(time).earliest().map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SessionRequest {
+    pub starts_at: SessionTime,
+    pub ends_at: SessionTime,
+    pub time_zone: String,
+    pub capacity: Option<u32>,
+    pub join_url: Option<String>,
+}
+
+impl SessionRequest {
+    /// Checks the request, returning when it runs in UTC.
+    fn check(&self) -> Result<Range<DateTime<Utc>>, (StatusCode, &'static str)> {
+        let zone = check_time_zone(self.time_zone.trim())?;
+        let starts_at = self
+            .starts_at
+            .to_utc(zone)
+            .ok_or((StatusCode::BAD_REQUEST, "Start time doesn't exist in that time zone"))?;
+        let ends_at = self
+            .ends_at
+            .to_utc(zone)
+            .ok_or((StatusCode::BAD_REQUEST, "End time doesn't exist in that time zone"))?;
+        if ends_at <= starts_at {
+            return Err((StatusCode::BAD_REQUEST, "Session must end after it starts"));
+        }
+        if self.capacity.is_some_and(|c| i32::try_from(c).is_err()) {
+            return Err((StatusCode::BAD_REQUEST, "Capacity is too large"));
+        }
+        if self.capacity == Some(0) {
+            return Err((StatusCode::BAD_REQUEST, "Capacity must be greater than zero"));
+        }
+        if let Some(url) = crate::ledger::non_blank(self.join_url.clone()) {
+            crate::admin::check_url(&url)?;
+        }
+        Ok(starts_at..ends_at)
+    }
+}
+
+#[derive(Serialize)]
+pub struct SessionResponse {
+    id: i32,
+    course_id: i32,
+    course_title: String,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    time_zone: String,
+    capacity: Option<i32>,
+    registered_count: i32,
+    seats_left: Option<i32>,
+    registered: bool,
+    /// Only shown to registered users and admins
+    join_url: Option<String>,
+    cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl SessionResponse {
+    fn new(
+        session: course_session::Model,
+        course: &course::Model,
+        registered_count: i32,
+        registered: bool,
+        admin: bool,
+    ) -> Self {
+        SessionResponse {
+            id: session.id,
+            course_id: session.course_id,
+            course_title: course.title.clone(),
+            starts_at: session.starts_at,
+            ends_at: session.ends_at,
+            time_zone: session.time_zone,
+            capacity: session.capacity,
+            registered_count,
+            seats_left: session.capacity.map(|c| (c - registered_count).max(0)),
+            registered,
+            join_url: session.join_url.filter(|_| registered || admin),
+            cancelled_at: session.cancelled_at,
+        }
+    }
+}
+
+/// Registration counts for the given sessions, and which of them the user is in.
+async fn registrations<C: ConnectionTrait>(
+    conn: &C,
+    session_ids: Vec<i32>,
+    user_id: i32,
+) -> Result<(HashMap<i32, i32>, Vec<i32>), (StatusCode, &'static str)> {
+    let rows = course_registration::Entity::find()
+        .filter(course_registration::Column::SessionId.is_in(session_ids))
+        .all(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut counts = HashMap::new();
+    let mut mine = Vec::new();
+    for row in rows {
+        *counts.entry(row.session_id).or_default() += 1;
+        if row.user_id == user_id {
+            mine.push(row.session_id);
+        }
+    }
+    Ok((counts, mine))
+}
+
+async fn find_session<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+) -> Result<(course_session::Model, course::Model), (StatusCode, &'static str)> {
+    let (session, course) = course_session::Entity::find_by_id(id)
+        .find_also_related(course::Entity)
+        .one(conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Session not found"))?;
+    let course = course.ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+    Ok((session, course))
+}
+
+async fn session_response<C: ConnectionTrait>(
+    conn: &C,
+    id: i32,
+    user_id: i32,
+    admin: bool,
+) -> Result<SessionResponse, (StatusCode, &'static str)> {
+    let (session, course) = find_session(conn, id).await?;
+    let (counts, mine) = registrations(conn, vec![id], user_id).await?;
+    Ok(SessionResponse::new(
+        session,
+        &course,
+        counts.get(&id).copied().unwrap_or_default(),
+        mine.contains(&id),
+        admin,
+    ))
+}
+
+/// Lists a course's sessions that haven't ended yet, soonest first.
+pub async fn list_course_sessions(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+) -> Result<Json<Vec<SessionResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let course = course::Entity::find_by_id(course_id)
+        .filter(course::Column::RetiredAt.is_null())
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+
+    let sessions = course_session::Entity::find()
+        .filter(course_session::Column::CourseId.eq(course_id))
+        .filter(course_session::Column::EndsAt.gt(Utc::now()))
+        .order_by_asc(course_session::Column::StartsAt)
+        .all(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let (counts, mine) =
+        registrations(&state.conn, sessions.iter().map(|s| s.id).collect(), user.id).await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|s| {
+                let count = counts.get(&s.id).copied().unwrap_or_default();
+                let registered = mine.contains(&s.id);
+                SessionResponse::new(s, &course, count, registered, user.is_admin)
+            })
+            .collect(),
+    ))
+}
+
+/// Lists the sessions the user is registered for that haven't ended yet.
+pub async fn my_sessions(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SessionResponse>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let sessions = upcoming_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let (counts, _) =
+        registrations(&state.conn, sessions.iter().map(|(s, _)| s.id).collect(), user.id).await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|(s, course)| {
+                let count = counts.get(&s.id).copied().unwrap_or_default();
+                SessionResponse::new(s, &course, count, true, user.is_admin)
+            })
+            .collect(),
+    ))
+}
+
+async fn upcoming_for_user<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+) -> Result<Vec<(course_session::Model, course::Model)>, sea_orm::DbErr> {
+    let session_ids: Vec<i32> = course_registration::Entity::find()
+        .filter(course_registration::Column::UserId.eq(user_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|r| r.session_id)
+        .collect();
+
+    Ok(course_session::Entity::find()
+        .find_also_related(course::Entity)
+        .filter(course_session::Column::Id.is_in(session_ids))
+        .filter(course_session::Column::EndsAt.gt(Utc::now()))
+        .order_by_asc(course_session::Column::StartsAt)
+        .all(conn)
+        .await?
+        .into_iter()
+        .filter_map(|(session, course)| Some((session, course?)))
+        .collect())
+}
+
+/// Registers the user for a session. Registering twice is allowed and
+/// leaves the first registration in place.
+pub async fn register_for_session(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<Json<SessionResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let (session, course) = find_session(&state.conn, id).await?;
+
+    if session.cancelled_at.is_some() || course.retired_at.is_some() {
+        return Err((StatusCode::CONFLICT, "Session has been cancelled"));
+    }
+    if session.starts_at <= Utc::now() {
+        return Err((StatusCode::CONFLICT, "Session has already started"));
+    }
+
+    // Counting and inserting in one statement keeps concurrent registrations
+    // from overfilling the session
+    let inserted = state
+        .conn
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO course_registration (session_id, user_id, created_at)
+             SELECT ?, ?, ?
+             WHERE NOT EXISTS (SELECT 1 FROM course_registration WHERE session_id = ? AND user_id = ?)
+               AND (? IS NULL OR (SELECT count(*) FROM course_registration WHERE session_id = ?) < ?)",
+            [
+                id.into(),
+                user.id.into(),
+                Utc::now().into(),
+                id.into(),
+                user.id.into(),
+                session.capacity.into(),
+                id.into(),
+                session.capacity.into(),
+            ],
+        ))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to register"))?
+        .rows_affected();
+
+    let response = session_response(&state.conn, id, user.id, user.is_admin).await?;
+    if inserted == 0 && !response.registered {
+        return Err((StatusCode::CONFLICT, "Session is full"));
+    }
+    Ok(Json(response))
+}
+
+pub async fn unregister_from_session(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let deleted = course_registration::Entity::delete_many()
+        .filter(course_registration::Column::SessionId.eq(id))
+        .filter(course_registration::Column::UserId.eq(user.id))
+        .exec(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to cancel registration"))?;
+    if deleted.rows_affected == 0 {
+        return Err((StatusCode::NOT_FOUND, "Registration not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn create_session(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(course_id): Path<i32>,
+    Json(data): Json<SessionRequest>,
+) -> Result<Json<SessionResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let times = data.check()?;
+
+    course::Entity::find_by_id(course_id)
+        .filter(course::Column::RetiredAt.is_null())
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Course not found"))?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let created = course_session::ActiveModel {
+        course_id: Set(course_id),
+        starts_at: Set(times.start),
+        ends_at: Set(times.end),
+        time_zone: Set(data.time_zone.trim().to_string()),
+        capacity: Set(data.capacity.map(|c| c as i32)),
+        join_url: Set(crate::ledger::non_blank(data.join_url)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save session"))?;
+
+    let after = session_response(&txn, created.id, admin.id, true).await?;
+    crate::catalog::record_audit(&txn, Some(admin.id), "session", created.id, "create", None, Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save session"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save session"))?;
+
+    Ok(Json(after))
+}
+
+pub async fn update_session(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+    Json(data): Json<SessionRequest>,
+) -> Result<Json<SessionResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let times = data.check()?;
+    let (existing, _) = find_session(&state.conn, id).await?;
+    let before = session_response(&state.conn, id, admin.id, true).await?;
+    let capacity = data.capacity.map(|c| c as i32);
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    // Seats already taken aren't given back by lowering the capacity. Counting
+    // and updating in one statement keeps a registration from landing between
+    // the two, as in `register_for_session`.
+    let updated = txn
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE course_session SET capacity = ?
+             WHERE id = ?
+               AND (? IS NULL OR (SELECT count(*) FROM course_registration WHERE session_id = ?) <= ?)",
+            [capacity.into(), id.into(), capacity.into(), id.into(), capacity.into()],
+        ))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update session"))?
+        .rows_affected();
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Capacity is below the number of registrations"));
+    }
+
+    let mut active: course_session::ActiveModel = existing.into();
+    active.starts_at = Set(times.start);
+    active.ends_at = Set(times.end);
+    active.time_zone = Set(data.time_zone.trim().to_string());
+    active.capacity = Set(capacity);
+    active.join_url = Set(crate::ledger::non_blank(data.join_url));
+    active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update session"))?;
+
+    let after = session_response(&txn, id, admin.id, true).await?;
+    crate::catalog::record_audit(&txn, Some(admin.id), "session", id, "update", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update session"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update session"))?;
+
+    Ok(Json(after))
+}
+
+/// Cancels a session. Registrations are kept so subscribed calendars show
+/// the cancellation instead of the event silently disappearing.
+pub async fn cancel_session(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    Path(id): Path<i32>,
+) -> Result<Json<SessionResponse>, (StatusCode, &'static str)> {
+    let admin = crate::auth::current_admin(&state.conn, &cookies).await?;
+    let (existing, _) = find_session(&state.conn, id).await?;
+    let before = session_response(&state.conn, id, admin.id, true).await?;
+    if existing.cancelled_at.is_some() {
+        return Ok(Json(before));
+    }
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let mut active: course_session::ActiveModel = existing.into();
+    active.cancelled_at = Set(Some(Utc::now()));
+    active
+        .update(&txn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to cancel session"))?;
+
+    let after = session_response(&txn, id, admin.id, true).await?;
+    crate::catalog::record_audit(&txn, Some(admin.id), "session", id, "cancel", Some(&before), Some(&after))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to cancel session"))?;
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to cancel session"))?;
+
+    Ok(Json(after))
+}
+
+#[derive(Serialize)]
+pub struct CalendarFeedResponse {
+    /// Path of the feed, relative to the API's address
+    path: String,
+}
+
+/// Creates a new secret address for the user's calendar feed. Any earlier
+/// address stops working.
+pub async fn reset_calendar_token(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<Json<CalendarFeedResponse>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+    let token = Uuid::new_v4().simple().to_string();
+
+    let mut active: user::ActiveModel = user.into();
+    active.calendar_token = Set(Some(token.clone()));
+    active
+        .update(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create calendar feed"))?;
+
+    Ok(Json(CalendarFeedResponse {
+        path: format!("/calendar/{}.ics", token),
+    }))
+}
+
+pub async fn delete_calendar_token(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    let mut active: user::ActiveModel = user.into();
+    active.calendar_token = Set(None);
+    active
+        .update(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to turn off calendar feed"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Serves the user's upcoming sessions as an iCalendar feed. The token in
+/// the address stands in for the session cookie.
+pub async fn calendar_feed(
+    state: State<crate::AppState>,
+    Path(file): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let token = file
+        .strip_suffix(".ics")
+        .filter(|t| !t.is_empty())
+        .ok_or((StatusCode::NOT_FOUND, "Calendar not found"))?;
+
+    let user = user::Entity::find()
+        .filter(user::Column::CalendarToken.eq(token))
+        .one(&state.conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Calendar not found"))?;
+
+    let sessions = upcoming_for_user(&state.conn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "private, max-age=900"),
+        ],
+        calendar(&sessions),
+    ))
+}
+
+fn calendar(sessions: &[(course_session::Model, course::Model)]) -> String {
+    let stamp = ical_time(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Coffee Overflow//Course Sessions//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:My CLE sessions".to_string(),
+    ];
+    for (session, course) in sessions {
+        let mut description = format!("Time zone: {}", session.time_zone);
+        if let Some(url) = &session.join_url {
+            description.push_str(&format!("\nJoin: {}", url));
+        }
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:course-session-{}@coffee-overflow", session.id),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", ical_time(session.starts_at)),
+            format!("DTEND:{}", ical_time(session.ends_at)),
+            format!("SUMMARY:{}", ical_text(&course.title)),
+            format!("DESCRIPTION:{}", ical_text(&description)),
+        ]);
+        if let Some(url) = ical_uri(session.join_url.as_deref().unwrap_or(&course.url)) {
+            lines.push(format!("URL:{}", url));
+        }
+        if let Some(url) = &session.join_url {
+            lines.push(format!("LOCATION:{}", ical_text(url)));
+        }
+        if session.cancelled_at.is_some() {
+            lines.push("STATUS:CANCELLED".to_string());
+        } else {
+            lines.push("STATUS:CONFIRMED".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect::<Vec<_>>().join("")
+}
+
+fn ical_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a text value. Every kind of line break becomes `\n`, so a value
+/// can't start a property of its own.
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\\n")
+}
+
+/// A URL that can go in a `URL` property as it is. URI values aren't
+/// escaped, so ones with whitespace or control characters are left out.
+fn ical_uri(url: &str) -> Option<&str> {
+    (!url.is_empty() && !url.chars().any(|c| c.is_whitespace() || c.is_control())).then_some(url)
+}
+
+/// Ends a content line with CRLF, folding it so no line is over 75 bytes.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(starts_at: &str, ends_at: &str, time_zone: &str) -> SessionRequest {
+        serde_json::from_value(json!({
+            "starts_at": starts_at,
+            "ends_at": ends_at,
+            "time_zone": time_zone,
+        }))
+        .unwrap()
+    }
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn time_zones_must_exist() {
+        assert!(check_time_zone("America/New_York").is_ok());
+        assert!(check_time_zone("UTC").is_ok());
+        assert!(check_time_zone("America/Nowhere").is_err());
+        assert!(check_time_zone("New York").is_err());
+    }
+
+    #[test]
+    fn local_times_are_read_in_the_time_zone() {
+        // Standard time in January, daylight saving time in July
+        let winter = request("2026-01-15T12:00:00", "2026-01-15T13:30:00", "America/New_York");
+        assert_eq!(winter.check().unwrap(), utc("2026-01-15T17:00:00Z")..utc("2026-01-15T18:30:00Z"));
+        let summer = request("2026-07-15T12:00:00", "2026-07-15T13:00:00", "America/New_York");
+        assert_eq!(summer.check().unwrap().start, utc("2026-07-15T16:00:00Z"));
+
+        // Times with an offset keep it, whatever the zone
+        let offset = request("2026-07-15T12:00:00+02:00", "2026-07-15T13:00:00+02:00", "America/Chicago");
+        assert_eq!(offset.check().unwrap().start, utc("2026-07-15T10:00:00Z"));
+    }
+
+    #[test]
+    fn times_skipped_by_daylight_saving_are_rejected() {
+        // Clocks in New York went from 2:00 to 3:00 on 8 March 2026
+        let skipped = request("2026-03-08T02:30:00", "2026-03-08T04:00:00", "America/New_York");
+        assert!(skipped.check().is_err());
+
+        // 1:30 happened twice on 1 November 2026; the first is in daylight time
+        let repeated = request("2026-11-01T01:30:00", "2026-11-01T03:00:00", "America/New_York");
+        assert_eq!(repeated.check().unwrap().start, utc("2026-11-01T05:30:00Z"));
+    }
+
+    #[test]
+    fn calendar_values_cannot_add_properties() {
+        let session = course_session::Model {
+            id: 7,
+            course_id: 3,
+            starts_at: utc("2026-07-15T16:00:00Z"),
+            ends_at: utc("2026-07-15T17:00:00Z"),
+            time_zone: "America/New_York".to_string(),
+            capacity: None,
+            join_url: Some("https://example.com/join\r\nATTENDEE:mailto:x@example.com".to_string()),
+            cancelled_at: None,
+        };
+        let course = course::Model {
+            id: 3,
+            provider_id: 1,
+            title: "Evidence\rSTATUS:CANCELLED\r\nX-INJECTED:1".to_string(),
+            summary: None,
+            credits: entity::Credits::from_whole(1),
+            duration_minutes: 60,
+            price_cents: 0,
+            url: "https://example.com/evidence".to_string(),
+            retired_at: None,
+            starts_on: None,
+            source: "manual".to_string(),
+            currency: "USD".to_string(),
+            member_price_cents: None,
+        };
+
+        let ics = calendar(&[(session, course)]);
+        let properties: Vec<&str> = ics
+            .split("\r\n")
+            .filter(|line| !line.starts_with(' '))
+            .filter_map(|line| line.split([':', ';']).next())
+            .collect();
+        assert!(!properties.iter().any(|p| ["ATTENDEE", "X-INJECTED", "URL"].contains(p)));
+        assert_eq!(properties.iter().filter(|p| **p == "STATUS").count(), 1);
+        assert!(ics.contains("SUMMARY:Evidence\\nSTATUS:CANCELLED\\nX-INJECTED:1"));
+        assert!(!ics.replace("\r\n", "").contains('\r'));
+    }
+
+    #[tokio::test]
+    async fn capacity_cannot_drop_below_registrations() {
+        let state = crate::test_support::app_state().await;
+        let conn = state.conn.clone();
+        let (admin, admin_cookies) = crate::test_support::sign_in(&conn, "admin").await;
+        let mut promoted: user::ActiveModel = admin.into();
+        promoted.is_admin = Set(true);
+        promoted.update(&conn).await.unwrap();
+        let course = course::Entity::find().one(&conn).await.unwrap().unwrap();
+
+        let session = |capacity: u32| {
+            Json(
+                serde_json::from_value(json!({
+                    "starts_at": "2099-07-15T12:00:00",
+                    "ends_at": "2099-07-15T13:00:00",
+                    "time_zone": "America/New_York",
+                    "capacity": capacity,
+                }))
+                .unwrap(),
+            )
+        };
+        let created = create_session(State(state.clone()), admin_cookies.clone(), Path(course.id), session(3))
+            .await
+            .unwrap();
+        for username in ["ann", "bob"] {
+            let (_, cookies) = crate::test_support::sign_in(&conn, username).await;
+            let _ = register_for_session(State(state.clone()), cookies, Path(created.0.id)).await.unwrap();
+        }
+
+        let lowered = update_session(State(state.clone()), admin_cookies.clone(), Path(created.0.id), session(1)).await;
+        assert_eq!(lowered.err().map(|(status, _)| status), Some(StatusCode::CONFLICT));
+        let unchanged = course_session::Entity::find_by_id(created.0.id).one(&conn).await.unwrap().unwrap();
+        assert_eq!(unchanged.capacity, Some(3));
+
+        let updated = update_session(State(state.clone()), admin_cookies, Path(created.0.id), session(2))
+            .await
+            .unwrap();
+        assert_eq!((updated.0.capacity, updated.0.seats_left), (Some(2), Some(0)));
+    }
+}
@@ -0,0 +1,83 @@
+//! Shared setup for tests that run against a database.
+
+use entity::{session, state, user, user_state};
+use migration::MigratorTrait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Database, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::sync::Arc;
+use tower_cookies::{Cookie, Cookies};
+
+/// A fresh in-memory database with every migration applied
+pub async fn database() -> DatabaseConnection {
+    let conn = Database::connect("sqlite::memory:").await.unwrap();
+    migration::Migrator::up(&conn, None).await.unwrap();
+    conn
+}
+
+/// Application state around a fresh database, with blobs in a scratch directory
+pub async fn app_state() -> crate::AppState {
+    let scratch = std::env::temp_dir().join(format!("coffee_overflow-{}", uuid::Uuid::new_v4()));
+    crate::AppState {
+        conn: database().await,
+        blobs: Arc::new(crate::blob_store::LocalBlobStore::new(scratch.join("certificates"))),
+        max_certificate_bytes: crate::certificates::DEFAULT_MAX_CERTIFICATE_BYTES,
+        forms_dir: scratch.join("forms"),
+    }
+}
+
+/// Adds an attorney with a session, returning cookies signed in as them
+pub async fn sign_in(conn: &DatabaseConnection, username: &str) -> (user::Model, Cookies) {
+    let user = user::ActiveModel {
+        username: Set(username.to_string()),
+        password: Set(String::new()),
+        fullname: Set(username.to_string()),
+        profession: Set("Attorney".to_string()),
+        version: Set(1),
+        is_admin: Set(false),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .unwrap();
+
+    let token = uuid::Uuid::new_v4().to_string();
+    session::ActiveModel {
+        token: Set(token.clone()),
+        user_id: Set(user.id),
+        created_at: Set(chrono::Utc::now()),
+    }
+    .insert(conn)
+    .await
+    .unwrap();
+
+    let cookies = Cookies::default();
+    cookies.add(Cookie::new("session", token));
+    (user, cookies)
+}
+
+/// Licenses the user as an attorney in `state_code`, renewing on `renewal_date`
+pub async fn add_license(
+    conn: &DatabaseConnection,
+    user_id: i32,
+    state_code: &str,
+    renewal_date: &str,
+) -> user_state::Model {
+    let state = state::Entity::find()
+        .filter(state::Column::Name.eq(state_code))
+        .one(conn)
+        .await
+        .unwrap()
+        .unwrap();
+
+    user_state::ActiveModel {
+        user_id: Set(user_id),
+        state_id: Set(state.id),
+        renewal_date: Set(Some(renewal_date.to_string())),
+        profession: Set("Attorney".to_string()),
+        status: Set("active".to_string()),
+        version: Set(1),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .unwrap()
+}
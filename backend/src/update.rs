@@ -1,42 +1,40 @@
 use axum::{Json, extract::State, http::StatusCode};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use chrono::NaiveDate;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use entity::Credits;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use tower_cookies::Cookies;
 
+use crate::category::CreditCategory;
+use crate::hours_history::Source;
+use crate::ledger::{CategoryCredits, CreditEntryRequest};
+use crate::profession::Profession;
+use crate::versioning::IfMatch;
+
 #[derive(Deserialize)]
 pub struct UpdateHoursRequest {
     pub state_id: String,
-    pub hours: i32,
+    pub hours: Credits,
+    /// Needed only when the user holds more than one license in the state
+    pub profession: Option<Profession>,
 }
 
 #[derive(Serialize)]
 pub struct UpdateHoursResponse {
-    pub hours_complete: i32,
+    pub hours_complete: Credits,
 }
 
 pub async fn update_hours(
     state: State<crate::AppState>,
     cookies: Cookies,
+    if_match: IfMatch,
     Json(data): Json<UpdateHoursRequest>,
 ) -> Result<Json<UpdateHoursResponse>, (StatusCode, &'static str)> {
-    // Get session token from cookie
-    let session_token = cookies
-        .get("session")
-        .ok_or((StatusCode::UNAUTHORIZED, "Not logged in"))?
-        .value()
-        .to_string();
-
-    // Verify session and get user ID
-    let user = entity::user::Entity::find()
-        .inner_join(entity::session::Entity)
-        .filter(entity::session::Column::Token.eq(session_token))
-        .one(&state.conn)
-        .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
-        .ok_or((StatusCode::FORBIDDEN, "Not logged in"))?;
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
 
     // Validate hours is non-negative
-    if data.hours < 0 {
+    if data.hours.is_negative() {
         return Err((StatusCode::BAD_REQUEST, "Hours cannot be negative"));
     }
 
@@ -48,25 +46,185 @@ pub async fn update_hours(
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
         .ok_or((StatusCode::NOT_FOUND, "State code not found"))?;
 
-    // Find the user_state entry for this user and state
-    let user_state_entry = entity::user_state::Entity::find()
+    // Find the user_state entry for this user, state and (optionally) profession
+    let mut query = entity::user_state::Entity::find()
         .filter(entity::user_state::Column::UserId.eq(user.id))
         .filter(entity::user_state::Column::StateId.eq(state_record.id))
-        .one(&state.conn)
+        .filter(entity::user_state::Column::RemovedAt.is_null());
+    if let Some(profession) = data.profession {
+        query = query.filter(entity::user_state::Column::Profession.eq(profession.to_string()));
+    }
+
+    let mut entries = query
+        .all(&state.conn)
         .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    if entries.len() > 1 {
+        return Err((StatusCode::BAD_REQUEST, "Profession required for this state"));
+    }
+    let user_state_entry = entries
+        .pop()
         .ok_or((StatusCode::NOT_FOUND, "State not found for user"))?;
 
-    // Update the hours_complete field
-    let mut user_state_active: entity::user_state::ActiveModel = user_state_entry.into();
-    user_state_active.hours_complete = Set(data.hours);
+    // Totals come from the ledger, so the difference is recorded as an
+    // adjustment and the change kept in the hours history
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    // Rejects the write if the details were changed since the client read them
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+    crate::hours_history::set_hours(
+        &txn,
+        user.id,
+        user.id,
+        user_state_entry.id,
+        data.hours,
+        Source::Update,
+    )
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update hours"))?;
 
-    let updated = user_state_active
-        .update(&state.conn)
+    txn.commit()
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update hours"))?;
 
     Ok(Json(UpdateHoursResponse {
-        hours_complete: updated.hours_complete,
+        hours_complete: data.hours,
     }))
 }
+
+#[derive(Deserialize)]
+pub struct LogHoursRequest {
+    /// Licenses the credit counts towards; each gets its own ledger entry
+    pub license_ids: Vec<i32>,
+    pub credits: Vec<CategoryCredits>,
+    /// Defaults to today
+    pub completed_on: Option<NaiveDate>,
+    pub title: Option<String>,
+    pub provider: Option<String>,
+    pub notes: Option<String>,
+    /// The catalog course the credit was earned from
+    pub course_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct LoggedHours {
+    pub license_id: i32,
+    pub state_code: String,
+    pub profession: String,
+    pub entry_id: i32,
+    pub hours_added: Credits,
+    pub hours_complete: Credits,
+    pub hours_by_category: BTreeMap<CreditCategory, Credits>,
+}
+
+/// Adds credit to one or more of the user's licenses in a single transaction,
+/// returning each license's new totals.
+pub async fn log_hours(
+    state: State<crate::AppState>,
+    cookies: Cookies,
+    if_match: IfMatch,
+    Json(data): Json<LogHoursRequest>,
+) -> Result<Json<Vec<LoggedHours>>, (StatusCode, &'static str)> {
+    let user = crate::auth::current_user(&state.conn, &cookies).await?;
+
+    if data.license_ids.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "At least one jurisdiction is required"));
+    }
+    let distinct: HashSet<_> = data.license_ids.iter().collect();
+    if distinct.len() != data.license_ids.len() {
+        return Err((StatusCode::BAD_REQUEST, "Each jurisdiction may only appear once"));
+    }
+
+    let title = crate::ledger::non_blank(data.title)
+        .unwrap_or_else(|| Source::Log.entry_title().to_string());
+    let completed_on = data
+        .completed_on
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let requests: Vec<CreditEntryRequest> = data
+        .license_ids
+        .iter()
+        .map(|license_id| CreditEntryRequest {
+            title: title.clone(),
+            provider: data.provider.clone(),
+            completed_on,
+            format: None,
+            approval_number: None,
+            notes: data.notes.clone(),
+            minutes: None,
+            license_id: Some(*license_id),
+            categories: data.credits.clone(),
+            course_id: data.course_id,
+            amount_paid_cents: None,
+            currency: None,
+        })
+        .collect();
+    for request in &requests {
+        crate::ledger::check_entry(request)?;
+    }
+    crate::ledger::check_course(&state.conn, data.course_id).await?;
+
+    let txn = state
+        .conn
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    let (licenses, before) = crate::ledger::totals_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if data
+        .license_ids
+        .iter()
+        .any(|id| !licenses.iter().any(|l| l.license.id == *id))
+    {
+        return Err((StatusCode::NOT_FOUND, "License not found for user"));
+    }
+
+    crate::versioning::bump_user(&txn, user.id, &if_match).await?;
+
+    let mut entries = Vec::new();
+    for request in requests {
+        let entry = crate::ledger::insert_entry(&txn, user.id, request)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to log hours"))?;
+        entries.push(entry.entry);
+    }
+
+    let ledger = crate::ledger::entries_for_user(&txn, user.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let mut after = crate::ledger::totals(&licenses, &ledger);
+
+    let mut logged = Vec::new();
+    for (license_id, entry) in data.license_ids.iter().zip(&entries) {
+        let old = before.get(license_id).map(|t| t.total).unwrap_or_default();
+        let new = after.remove(license_id).unwrap_or_default();
+        crate::hours_history::record_change(&txn, user.id, *license_id, entry, old, new.total, Source::Log)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to log hours"))?;
+
+        let Some(license) = licenses.iter().find(|l| l.license.id == *license_id) else {
+            continue;
+        };
+        logged.push(LoggedHours {
+            license_id: *license_id,
+            state_code: license.state_code.clone(),
+            profession: license.license.profession.clone(),
+            entry_id: entry.id,
+            hours_added: new.total - old,
+            hours_complete: new.total,
+            hours_by_category: new.by_category,
+        });
+    }
+
+    txn.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to log hours"))?;
+
+    Ok(Json(logged))
+}
@@ -1,76 +1,84 @@
-use axum::{Json, extract::State, http::StatusCode};
-use sea_orm::{ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect};
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use entity::Credits;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use tower_cookies::Cookies;
 
+use crate::category::CreditCategory;
+
 #[derive(Serialize)]
 pub struct StateHours {
+    id: i32,
     state_code: String,
-    hours_complete: u16,
-    legal_hours: u16,
+    profession: String,
+    hours_complete: Credits,
+    hours_by_category: BTreeMap<CreditCategory, Credits>,
+    legal_hours: Credits,
     renewal_date: Option<String>,
+    period_start: Option<chrono::NaiveDate>,
+    deadline_override: Option<chrono::NaiveDate>,
+    admission_date: Option<chrono::NaiveDate>,
+    bar_number: Option<String>,
+    license_status: String,
+    version: i32,
 }
 
 #[derive(Serialize)]
 pub struct UserDetailsResponse {
     username: String,
     fullname: String,
+    profession: String,
     states: Vec<StateHours>,
 }
 
 pub async fn user_details(
     state: State<crate::AppState>,
     cookies: Cookies,
-) -> Result<Json<UserDetailsResponse>, (StatusCode, &'static str)> {
-    // Get session token from cookie
-    let session_token = cookies
-        .get("session")
-        .ok_or((StatusCode::UNAUTHORIZED, "Not logged in"))?
-        .value()
-        .to_string();
-
-    let resp = entity::user::Entity::find()
-        .inner_join(entity::session::Entity)
-        .filter(entity::session::Column::Token.eq(session_token))
-        .one(&state.conn)
-        .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
-        .ok_or((StatusCode::FORBIDDEN, "Not logged in"))?;
-
-    #[derive(FromQueryResult)]
-    struct QueryRes {
-        name: String,
-        legal_hours: u16,
-        hours_complete: u16,
-        renewal_date: Option<String>,
-    }
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let resp = crate::auth::current_user(&state.conn, &cookies).await?;
 
-    let hours = entity::state::Entity::find()
-        .inner_join(entity::user_state::Entity)
-        .filter(entity::user_state::Column::UserId.eq(resp.id))
-        .select_only()
-        .column(entity::user_state::Column::HoursComplete)
-        .column(entity::user_state::Column::RenewalDate)
-        .column(entity::state::Column::LegalHours)
-        .column(entity::state::Column::Name)
-        .into_model::<QueryRes>()
-        .all(&state.conn)
+    // Hours completed are derived from the credit ledger
+    let (licenses, mut totals) = crate::ledger::totals_for_user(&state.conn, resp.id)
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
 
-    let states_response = hours
-        .iter()
-        .map(|h| StateHours {
-            state_code: h.name.clone(),
-            hours_complete: h.hours_complete,
-            legal_hours: h.legal_hours,
-            renewal_date: h.renewal_date.clone(),
+    let states_response = licenses
+        .into_iter()
+        .map(|l| {
+            let totals = totals.remove(&l.license.id).unwrap_or_default();
+            let period_start = l.period_start();
+            StateHours {
+                id: l.license.id,
+                state_code: l.state_code,
+                profession: l.license.profession,
+                hours_complete: totals.total,
+                hours_by_category: totals.by_category,
+                period_start,
+                legal_hours: l.requirement.map(|r| r.hours).unwrap_or_default(),
+                renewal_date: l.license.renewal_date,
+                deadline_override: l.license.deadline_override,
+                admission_date: l.license.admission_date,
+                bar_number: l.license.bar_number,
+                license_status: l.license.status,
+                version: l.license.version,
+            }
         })
         .collect();
 
-    Ok(Json(UserDetailsResponse {
-        username: resp.username,
-        fullname: resp.fullname,
-        states: states_response,
-    }))
+    // Writes to anything shown here bump the user's version, so it tags the
+    // whole response for If-Match checks
+    Ok((
+        [(header::ETAG, crate::versioning::etag(resp.version))],
+        Json(UserDetailsResponse {
+            username: resp.username,
+            fullname: resp.fullname,
+            profession: resp.profession,
+            states: states_response,
+        }),
+    ))
 }
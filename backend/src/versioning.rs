@@ -0,0 +1,126 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderValue, StatusCode, header, request::Parts},
+};
+use entity::{credit_entry, user, user_state};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, sea_query::Expr};
+
+/// The versions a write may apply to, taken from the request's `If-Match`
+/// header. Requests without the header aren't checked.
+pub enum IfMatch {
+    Any,
+    Versions(Vec<i32>),
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(IfMatch::Any);
+        };
+        let value = value
+            .to_str()
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid If-Match header"))?
+            .trim();
+        if value == "*" {
+            return Ok(IfMatch::Any);
+        }
+
+        // If-Match uses strong comparison, so weak tags never match
+        Ok(IfMatch::Versions(
+            value
+                .split(',')
+                .filter_map(|tag| tag.trim().strip_prefix('"')?.strip_suffix('"')?.parse().ok())
+                .collect(),
+        ))
+    }
+}
+
+/// The entity tag for a record at the given version.
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).expect("entity tag is a valid header value")
+}
+
+/// Increments a record's version if it still has one the client's `If-Match`
+/// allows, returning whether it did. Checking and incrementing in one update
+/// keeps concurrent writers from passing the check together.
+async fn bump<E, C>(
+    conn: &C,
+    id_column: E::Column,
+    version_column: E::Column,
+    id: i32,
+    if_match: &IfMatch,
+) -> Result<bool, DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let mut update = E::update_many()
+        .col_expr(version_column, Expr::col(version_column).add(1))
+        .filter(id_column.eq(id));
+    if let IfMatch::Versions(versions) = if_match {
+        update = update.filter(version_column.is_in(versions.iter().copied()));
+    }
+
+    Ok(update.exec(conn).await?.rows_affected > 0)
+}
+
+fn precondition(bumped: Result<bool, DbErr>) -> Result<(), (StatusCode, &'static str)> {
+    match bumped {
+        Ok(true) => Ok(()),
+        Ok(false) => Err((StatusCode::PRECONDITION_FAILED, "Record has changed since it was read")),
+        Err(_) => Err((StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    }
+}
+
+/// Marks a change to anything shown in the user's details, which share the
+/// user's version as their entity tag.
+pub async fn bump_user<C: ConnectionTrait>(
+    conn: &C,
+    user_id: i32,
+    if_match: &IfMatch,
+) -> Result<(), (StatusCode, &'static str)> {
+    precondition(bump::<user::Entity, _>(conn, user::Column::Id, user::Column::Version, user_id, if_match).await)
+}
+
+/// Marks a change to the user's details made outside of a request.
+pub async fn touch_user<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<(), DbErr> {
+    bump::<user::Entity, _>(conn, user::Column::Id, user::Column::Version, user_id, &IfMatch::Any)
+        .await
+        .map(|_| ())
+}
+
+pub async fn bump_license<C: ConnectionTrait>(
+    conn: &C,
+    license_id: i32,
+    if_match: &IfMatch,
+) -> Result<(), (StatusCode, &'static str)> {
+    precondition(
+        bump::<user_state::Entity, _>(
+            conn,
+            user_state::Column::Id,
+            user_state::Column::Version,
+            license_id,
+            if_match,
+        )
+        .await,
+    )
+}
+
+pub async fn bump_entry<C: ConnectionTrait>(
+    conn: &C,
+    entry_id: i32,
+    if_match: &IfMatch,
+) -> Result<(), (StatusCode, &'static str)> {
+    precondition(
+        bump::<credit_entry::Entity, _>(
+            conn,
+            credit_entry::Column::Id,
+            credit_entry::Column::Version,
+            entry_id,
+            if_match,
+        )
+        .await,
+    )
+}
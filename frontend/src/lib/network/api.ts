@@ -16,6 +16,10 @@ enum Endpoint {
     recommendations = "recommendations"
 }
 
+// Entity tag of the last user details fetched, sent back with hours updates so
+// they're rejected with 412 if someone else changed the details in between
+let detailsEtag: string | undefined;
+
 const urlString = (path: Endpoint): string => {
     return `${BASE_URL}/${path.valueOf()}`;
 }
@@ -43,6 +47,7 @@ export const login = async (requestBody: LoginRequest): Promise<LoginResponse> =
 export const getUserDetails = async (): Promise<UserDetailsResponse> => {
     try {
         let res = await api.get<UserDetailsResponse>(urlString(Endpoint.userDetails));
+        detailsEtag = res.headers["etag"];
         return res.data;
     } catch (error) {
         console.error('Get user details error:', error);
@@ -52,7 +57,9 @@ export const getUserDetails = async (): Promise<UserDetailsResponse> => {
 
 export const updateHours = async (requestBody: UpdateHoursRequest): Promise<UpdateHoursResponse> => {
     try {
-        let res = await api.post<UpdateHoursResponse>(urlString(Endpoint.userHours), requestBody);
+        let res = await api.post<UpdateHoursResponse>(urlString(Endpoint.userHours), requestBody, {
+            headers: detailsEtag ? { "If-Match": detailsEtag } : {},
+        });
         return res.data;
     } catch (error) {
         console.error('Update hours error:', error);
@@ -82,6 +89,28 @@ export interface CourseRecommendation {
     price: string;
     url: string;
     ai_reason: string;
+    approvals: Array<{
+        state_code: string;
+        profession: string;
+        approval_number: string | null;
+        valid_from: string | null;
+        valid_until: string | null;
+        credits: Array<{ category: string; credits: number }>;
+    }>;
+    rating?: number;
+    review_count: number;
+    rationale: Array<{
+        license_id: number;
+        state_code: string;
+        profession: string;
+        approved: boolean;
+        approval_number: string | null;
+        hours: number;
+        remaining: number;
+        categories: Array<{ category: string; credits: number }>;
+        deadline: string | null;
+        days_to_deadline: number | null;
+    }>;
 }
 
 export interface RecommendationsResponse {
@@ -2,7 +2,8 @@ export interface RegisterRequest {
     username: string;
     password: string;
     fullname: string;  // Backend expects lowercase
-    states: Record<string, { completed: number; due: string }>;  // HashMap format
+    profession?: string;  // Attorney, CPA, Engineer or Architect; defaults to Attorney
+    states: Record<string, { completed: number; due: string; profession?: string }>;  // HashMap format
 }
 
 export interface RegisterResponse {
@@ -22,17 +23,28 @@ export interface LoginResponse {
 export interface UserDetailsResponse {
     username: string;
     fullname: string;
+    profession: string;
     states: Array<{
+        id: number;  // License id, used to scope credit entries
         state_code: string;
-        hours_complete: number;
+        profession: string;
+        hours_complete: number;  // Derived from the credit ledger
+        hours_by_category: Record<string, number>;
         legal_hours: number;
         renewal_date: string | null;
+        period_start: string | null;
+        deadline_override: string | null;
+        admission_date: string | null;
+        bar_number: string | null;
+        license_status: string;  // active, inactive, retired or suspended
+        version: number;  // Sent as a quoted If-Match value when editing the jurisdiction
     }>;
 }
 
 export interface UpdateHoursRequest {
     state_id: string;
     hours: number;
+    profession?: string;  // Only needed when licensed in the state for several professions
 }
 
 export interface UpdateHoursResponse {